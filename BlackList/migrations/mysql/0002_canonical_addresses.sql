@@ -0,0 +1,45 @@
+-- 地址统一为小写存储；同一链上大小写不同的重复记录只保留最早的一条，
+-- 其余记录的原因合并到保留的记录中，任一记录永久有效则保留的记录永久有效
+SET SESSION group_concat_max_len = 1048576;
+UPDATE blockchain_blacklist k
+JOIN (
+    SELECT MIN(id) AS id,
+           GROUP_CONCAT(DISTINCT reason ORDER BY reason SEPARATOR '; ') AS reason,
+           IF(COUNT(expires_at) < COUNT(*), NULL, MAX(expires_at)) AS expires_at
+    FROM blockchain_blacklist
+    GROUP BY chain_type, LOWER(TRIM(address))
+    HAVING COUNT(*) > 1
+) m ON m.id = k.id
+SET k.reason = m.reason, k.expires_at = m.expires_at;
+DELETE b FROM blockchain_blacklist b
+JOIN blockchain_blacklist a
+    ON a.chain_type = b.chain_type
+    AND LOWER(TRIM(a.address)) = LOWER(TRIM(b.address))
+    AND a.id < b.id;
+UPDATE blockchain_blacklist SET address = LOWER(TRIM(address));
+ALTER TABLE blockchain_blacklist ADD UNIQUE KEY uk_blacklist_chain_address (chain_type, address);
+
+UPDATE blockchain_blacklist_shadow k
+JOIN (
+    SELECT MIN(id) AS id,
+           GROUP_CONCAT(DISTINCT reason ORDER BY reason SEPARATOR '; ') AS reason,
+           IF(COUNT(expires_at) < COUNT(*), NULL, MAX(expires_at)) AS expires_at
+    FROM blockchain_blacklist_shadow
+    GROUP BY chain_type, LOWER(TRIM(address))
+    HAVING COUNT(*) > 1
+) m ON m.id = k.id
+SET k.reason = m.reason, k.expires_at = m.expires_at;
+DELETE b FROM blockchain_blacklist_shadow b
+JOIN blockchain_blacklist_shadow a
+    ON a.chain_type = b.chain_type
+    AND LOWER(TRIM(a.address)) = LOWER(TRIM(b.address))
+    AND a.id < b.id;
+UPDATE blockchain_blacklist_shadow SET address = LOWER(TRIM(address));
+ALTER TABLE blockchain_blacklist_shadow ADD UNIQUE KEY uk_blacklist_chain_address (chain_type, address);
+
+-- 其余表的地址由程序写入，大小写形式一一对应，不会产生重复
+UPDATE blacklist_function_scope SET address = LOWER(address), project = LOWER(project);
+UPDATE blacklist_function_scope_shadow SET address = LOWER(address), project = LOWER(project);
+UPDATE blacklist_project_sync SET address = LOWER(address), project = LOWER(project);
+UPDATE tx_nonce SET sender = LOWER(sender);
+UPDATE registry_pause SET project = LOWER(project), attacker = LOWER(attacker);
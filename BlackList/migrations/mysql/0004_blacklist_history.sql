@@ -0,0 +1,32 @@
+-- 黑名单的变更历史，只追加；不设外键，记录删除后历史仍保留
+CREATE TABLE IF NOT EXISTS blacklist_history (
+    id BIGINT NOT NULL AUTO_INCREMENT,
+    entry_id BIGINT NOT NULL,
+    address VARCHAR(64) NOT NULL,
+    chain_type VARCHAR(32) NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    actor VARCHAR(64) NULL,
+    old_value TEXT NULL,
+    new_value TEXT NULL,
+    changed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id),
+    KEY idx_history_address (address),
+    KEY idx_history_entry (entry_id)
+);
+
+CREATE TABLE IF NOT EXISTS blacklist_history_shadow (
+    id BIGINT NOT NULL AUTO_INCREMENT,
+    entry_id BIGINT NOT NULL,
+    address VARCHAR(64) NOT NULL,
+    chain_type VARCHAR(32) NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    actor VARCHAR(64) NULL,
+    old_value TEXT NULL,
+    new_value TEXT NULL,
+    changed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id),
+    KEY idx_history_shadow_address (address),
+    KEY idx_history_shadow_entry (entry_id)
+);
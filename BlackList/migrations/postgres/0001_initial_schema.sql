@@ -0,0 +1,59 @@
+-- 黑名单主表，影子表结构与其一致
+CREATE TABLE IF NOT EXISTS blockchain_blacklist (
+    id BIGSERIAL PRIMARY KEY,
+    address VARCHAR(64) NOT NULL,
+    reason TEXT NOT NULL,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    status VARCHAR(16) NOT NULL DEFAULT 'active',
+    expires_at TIMESTAMPTZ NULL,
+    chain_type VARCHAR(32) NOT NULL,
+    created_by VARCHAR(64) NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_blacklist_address ON blockchain_blacklist (address);
+CREATE INDEX IF NOT EXISTS idx_blacklist_status_expires ON blockchain_blacklist (status, expires_at);
+
+CREATE TABLE IF NOT EXISTS blockchain_blacklist_shadow (LIKE blockchain_blacklist INCLUDING ALL);
+
+-- 函数级黑名单
+CREATE TABLE IF NOT EXISTS blacklist_function_scope (
+    address VARCHAR(64) NOT NULL,
+    project VARCHAR(64) NOT NULL,
+    func_sig CHAR(10) NOT NULL,
+    synced BOOLEAN NOT NULL DEFAULT FALSE,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address, project, func_sig)
+);
+
+CREATE TABLE IF NOT EXISTS blacklist_function_scope_shadow (LIKE blacklist_function_scope INCLUDING ALL);
+
+-- 各项目、各代币上的同步状态
+CREATE TABLE IF NOT EXISTS blacklist_project_sync (
+    address VARCHAR(64) NOT NULL,
+    project VARCHAR(64) NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    tx_hash VARCHAR(66) NULL,
+    nonce BIGINT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address, project)
+);
+
+-- 发送账户的 nonce
+CREATE TABLE IF NOT EXISTS tx_nonce (
+    chain_id BIGINT NOT NULL,
+    sender VARCHAR(64) NOT NULL,
+    next_nonce BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (chain_id, sender)
+);
+
+-- 自动暂停记录，func_sig 为空表示暂停整个项目
+CREATE TABLE IF NOT EXISTS registry_pause (
+    project VARCHAR(64) NOT NULL,
+    func_sig VARCHAR(10) NOT NULL DEFAULT '',
+    attacker VARCHAR(64) NOT NULL,
+    paused_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    unpaused_at TIMESTAMPTZ NULL,
+    PRIMARY KEY (project, func_sig)
+);
@@ -0,0 +1,41 @@
+-- 地址统一为小写存储；同一链上大小写不同的重复记录只保留最早的一条，
+-- 其余记录的原因合并到保留的记录中，任一记录永久有效则保留的记录永久有效
+UPDATE blockchain_blacklist k
+SET reason = m.reason, expires_at = m.expires_at
+FROM (
+    SELECT MIN(id) AS id,
+           STRING_AGG(DISTINCT reason, '; ') AS reason,
+           CASE WHEN COUNT(expires_at) < COUNT(*) THEN NULL ELSE MAX(expires_at) END AS expires_at
+    FROM blockchain_blacklist
+    GROUP BY chain_type, LOWER(TRIM(address))
+    HAVING COUNT(*) > 1
+) m
+WHERE m.id = k.id;
+DELETE FROM blockchain_blacklist b
+USING blockchain_blacklist a
+WHERE a.chain_type = b.chain_type
+    AND LOWER(TRIM(a.address)) = LOWER(TRIM(b.address))
+    AND a.id < b.id;
+UPDATE blockchain_blacklist SET address = LOWER(TRIM(address));
+CREATE UNIQUE INDEX IF NOT EXISTS uk_blacklist_chain_address
+    ON blockchain_blacklist (chain_type, address);
+
+UPDATE blockchain_blacklist_shadow k
+SET reason = m.reason, expires_at = m.expires_at
+FROM (
+    SELECT MIN(id) AS id,
+           STRING_AGG(DISTINCT reason, '; ') AS reason,
+           CASE WHEN COUNT(expires_at) < COUNT(*) THEN NULL ELSE MAX(expires_at) END AS expires_at
+    FROM blockchain_blacklist_shadow
+    GROUP BY chain_type, LOWER(TRIM(address))
+    HAVING COUNT(*) > 1
+) m
+WHERE m.id = k.id;
+DELETE FROM blockchain_blacklist_shadow b
+USING blockchain_blacklist_shadow a
+WHERE a.chain_type = b.chain_type
+    AND LOWER(TRIM(a.address)) = LOWER(TRIM(b.address))
+    AND a.id < b.id;
+UPDATE blockchain_blacklist_shadow SET address = LOWER(TRIM(address));
+CREATE UNIQUE INDEX IF NOT EXISTS uk_blacklist_shadow_chain_address
+    ON blockchain_blacklist_shadow (chain_type, address);
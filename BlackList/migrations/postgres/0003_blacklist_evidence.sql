@@ -0,0 +1,37 @@
+-- 黑名单记录的证据，一条记录可以有多次检测结果；记录删除时一并删除
+-- 同一交易、同一检测器只记录一次，tx_hash 为空时不去重
+CREATE TABLE IF NOT EXISTS blacklist_evidence (
+    id BIGSERIAL PRIMARY KEY,
+    entry_id BIGINT NOT NULL REFERENCES blockchain_blacklist (id) ON DELETE CASCADE,
+    tx_hash VARCHAR(66) NULL,
+    block_number BIGINT NULL,
+    block_hash VARCHAR(66) NULL,
+    chain_type VARCHAR(32) NOT NULL,
+    detector VARCHAR(64) NOT NULL,
+    severity VARCHAR(16) NOT NULL,
+    project VARCHAR(64) NOT NULL,
+    func_sig VARCHAR(10) NULL,
+    loss_wei VARCHAR(78) NOT NULL DEFAULT '0',
+    call_path TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE UNIQUE INDEX IF NOT EXISTS uk_evidence_tx
+    ON blacklist_evidence (entry_id, tx_hash, detector);
+
+CREATE TABLE IF NOT EXISTS blacklist_evidence_shadow (
+    id BIGSERIAL PRIMARY KEY,
+    entry_id BIGINT NOT NULL REFERENCES blockchain_blacklist_shadow (id) ON DELETE CASCADE,
+    tx_hash VARCHAR(66) NULL,
+    block_number BIGINT NULL,
+    block_hash VARCHAR(66) NULL,
+    chain_type VARCHAR(32) NOT NULL,
+    detector VARCHAR(64) NOT NULL,
+    severity VARCHAR(16) NOT NULL,
+    project VARCHAR(64) NOT NULL,
+    func_sig VARCHAR(10) NULL,
+    loss_wei VARCHAR(78) NOT NULL DEFAULT '0',
+    call_path TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE UNIQUE INDEX IF NOT EXISTS uk_evidence_shadow_tx
+    ON blacklist_evidence_shadow (entry_id, tx_hash, detector);
@@ -0,0 +1,12 @@
+-- 白名单：这些地址不会被写入黑名单，也不会被推送上链；生产与影子模式共用
+-- suppressed_count 记录被抑制的检测结果数，用于调整检测器
+CREATE TABLE IF NOT EXISTS blacklist_allowlist (
+    address VARCHAR(64) NOT NULL,
+    chain_type VARCHAR(32) NOT NULL,
+    reason VARCHAR(255) NOT NULL,
+    owner VARCHAR(64) NOT NULL,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    suppressed_count BIGINT NOT NULL DEFAULT 0,
+    last_suppressed_at TIMESTAMPTZ NULL,
+    PRIMARY KEY (chain_type, address)
+);
@@ -0,0 +1,79 @@
+-- SQLite 用于测试与小型部署，表结构与 MySQL 一致；黑名单主表，影子表结构与其一致
+CREATE TABLE IF NOT EXISTS blockchain_blacklist (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    address TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    added_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    status TEXT NOT NULL DEFAULT 'active',
+    expires_at TIMESTAMP NULL,
+    chain_type TEXT NOT NULL,
+    created_by TEXT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_blacklist_address ON blockchain_blacklist (address);
+CREATE INDEX IF NOT EXISTS idx_blacklist_status_expires ON blockchain_blacklist (status, expires_at);
+
+CREATE TABLE IF NOT EXISTS blockchain_blacklist_shadow (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    address TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    added_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    status TEXT NOT NULL DEFAULT 'active',
+    expires_at TIMESTAMP NULL,
+    chain_type TEXT NOT NULL,
+    created_by TEXT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_blacklist_shadow_address ON blockchain_blacklist_shadow (address);
+CREATE INDEX IF NOT EXISTS idx_blacklist_shadow_status_expires
+    ON blockchain_blacklist_shadow (status, expires_at);
+
+-- 函数级黑名单
+CREATE TABLE IF NOT EXISTS blacklist_function_scope (
+    address TEXT NOT NULL,
+    project TEXT NOT NULL,
+    func_sig TEXT NOT NULL,
+    synced BOOLEAN NOT NULL DEFAULT FALSE,
+    added_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address, project, func_sig)
+);
+
+CREATE TABLE IF NOT EXISTS blacklist_function_scope_shadow (
+    address TEXT NOT NULL,
+    project TEXT NOT NULL,
+    func_sig TEXT NOT NULL,
+    synced BOOLEAN NOT NULL DEFAULT FALSE,
+    added_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address, project, func_sig)
+);
+
+-- 各项目、各代币上的同步状态
+CREATE TABLE IF NOT EXISTS blacklist_project_sync (
+    address TEXT NOT NULL,
+    project TEXT NOT NULL,
+    status TEXT NOT NULL,
+    tx_hash TEXT NULL,
+    nonce INTEGER NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address, project)
+);
+
+-- 发送账户的 nonce
+CREATE TABLE IF NOT EXISTS tx_nonce (
+    chain_id INTEGER NOT NULL,
+    sender TEXT NOT NULL,
+    next_nonce INTEGER NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (chain_id, sender)
+);
+
+-- 自动暂停记录，func_sig 为空表示暂停整个项目
+CREATE TABLE IF NOT EXISTS registry_pause (
+    project TEXT NOT NULL,
+    func_sig TEXT NOT NULL DEFAULT '',
+    attacker TEXT NOT NULL,
+    paused_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    unpaused_at TIMESTAMP NULL,
+    PRIMARY KEY (project, func_sig)
+);
@@ -0,0 +1,67 @@
+-- 地址统一为小写存储；同一链上大小写不同的重复记录只保留最早的一条，
+-- 其余记录的原因合并到保留的记录中，任一记录永久有效则保留的记录永久有效
+UPDATE blockchain_blacklist
+SET reason = (
+        SELECT GROUP_CONCAT(reason, '; ')
+        FROM (
+            SELECT DISTINCT d.reason
+            FROM blockchain_blacklist d
+            WHERE d.chain_type = blockchain_blacklist.chain_type
+                AND LOWER(TRIM(d.address)) = LOWER(TRIM(blockchain_blacklist.address))
+            ORDER BY d.reason
+        )
+    ),
+    expires_at = (
+        SELECT CASE WHEN COUNT(d.expires_at) < COUNT(*) THEN NULL ELSE MAX(d.expires_at) END
+        FROM blockchain_blacklist d
+        WHERE d.chain_type = blockchain_blacklist.chain_type
+            AND LOWER(TRIM(d.address)) = LOWER(TRIM(blockchain_blacklist.address))
+    )
+WHERE id IN (
+    SELECT MIN(id) FROM blockchain_blacklist
+    GROUP BY chain_type, LOWER(TRIM(address))
+    HAVING COUNT(*) > 1
+);
+DELETE FROM blockchain_blacklist
+WHERE EXISTS (
+    SELECT 1 FROM blockchain_blacklist a
+    WHERE a.chain_type = blockchain_blacklist.chain_type
+        AND LOWER(TRIM(a.address)) = LOWER(TRIM(blockchain_blacklist.address))
+        AND a.id < blockchain_blacklist.id
+);
+UPDATE blockchain_blacklist SET address = LOWER(TRIM(address));
+CREATE UNIQUE INDEX IF NOT EXISTS uk_blacklist_chain_address
+    ON blockchain_blacklist (chain_type, address);
+
+UPDATE blockchain_blacklist_shadow
+SET reason = (
+        SELECT GROUP_CONCAT(reason, '; ')
+        FROM (
+            SELECT DISTINCT d.reason
+            FROM blockchain_blacklist_shadow d
+            WHERE d.chain_type = blockchain_blacklist_shadow.chain_type
+                AND LOWER(TRIM(d.address)) = LOWER(TRIM(blockchain_blacklist_shadow.address))
+            ORDER BY d.reason
+        )
+    ),
+    expires_at = (
+        SELECT CASE WHEN COUNT(d.expires_at) < COUNT(*) THEN NULL ELSE MAX(d.expires_at) END
+        FROM blockchain_blacklist_shadow d
+        WHERE d.chain_type = blockchain_blacklist_shadow.chain_type
+            AND LOWER(TRIM(d.address)) = LOWER(TRIM(blockchain_blacklist_shadow.address))
+    )
+WHERE id IN (
+    SELECT MIN(id) FROM blockchain_blacklist_shadow
+    GROUP BY chain_type, LOWER(TRIM(address))
+    HAVING COUNT(*) > 1
+);
+DELETE FROM blockchain_blacklist_shadow
+WHERE EXISTS (
+    SELECT 1 FROM blockchain_blacklist_shadow a
+    WHERE a.chain_type = blockchain_blacklist_shadow.chain_type
+        AND LOWER(TRIM(a.address)) = LOWER(TRIM(blockchain_blacklist_shadow.address))
+        AND a.id < blockchain_blacklist_shadow.id
+);
+UPDATE blockchain_blacklist_shadow SET address = LOWER(TRIM(address));
+CREATE UNIQUE INDEX IF NOT EXISTS uk_blacklist_shadow_chain_address
+    ON blockchain_blacklist_shadow (chain_type, address);
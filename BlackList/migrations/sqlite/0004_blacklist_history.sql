@@ -0,0 +1,30 @@
+-- 黑名单的变更历史，只追加；不设外键，记录删除后历史仍保留
+CREATE TABLE IF NOT EXISTS blacklist_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entry_id BIGINT NOT NULL,
+    address VARCHAR(64) NOT NULL,
+    chain_type VARCHAR(32) NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    actor VARCHAR(64) NULL,
+    old_value TEXT NULL,
+    new_value TEXT NULL,
+    changed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_history_address ON blacklist_history (address);
+CREATE INDEX IF NOT EXISTS idx_history_entry ON blacklist_history (entry_id);
+
+CREATE TABLE IF NOT EXISTS blacklist_history_shadow (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entry_id BIGINT NOT NULL,
+    address VARCHAR(64) NOT NULL,
+    chain_type VARCHAR(32) NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    actor VARCHAR(64) NULL,
+    old_value TEXT NULL,
+    new_value TEXT NULL,
+    changed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_history_shadow_address ON blacklist_history_shadow (address);
+CREATE INDEX IF NOT EXISTS idx_history_shadow_entry ON blacklist_history_shadow (entry_id);
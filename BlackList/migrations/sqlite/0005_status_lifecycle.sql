@@ -0,0 +1,6 @@
+-- 状态生命周期：记录最近一次状态变化的时间，每次转换的时间见变更历史
+ALTER TABLE blockchain_blacklist ADD COLUMN status_changed_at TIMESTAMP NULL;
+UPDATE blockchain_blacklist SET status_changed_at = updated_at;
+
+ALTER TABLE blockchain_blacklist_shadow ADD COLUMN status_changed_at TIMESTAMP NULL;
+UPDATE blockchain_blacklist_shadow SET status_changed_at = updated_at;
@@ -0,0 +1,42 @@
+use alloy::transports::http::reqwest::{Client, Url};
+use anyhow::Result;
+use serde_json::json;
+use std::env;
+
+/// 需要人工介入时的告警，配置 `ALERT_WEBHOOK` 时同时推送到 webhook
+#[derive(Debug, Clone)]
+pub struct Alerter {
+    webhook: Option<Url>,
+    client: Client,
+}
+
+impl Alerter {
+    pub fn from_env() -> Result<Self> {
+        let webhook = match env::var("ALERT_WEBHOOK") {
+            Ok(url) => Some(url.parse()?),
+            Err(_) => None,
+        };
+        Ok(Self {
+            webhook,
+            client: Client::new(),
+        })
+    }
+
+    /// webhook 推送失败只记录日志，不影响调用方
+    pub async fn alert(&self, message: &str) {
+        eprintln!("[alert] {}", message);
+        let Some(webhook) = &self.webhook else {
+            return;
+        };
+        let result = self
+            .client
+            .post(webhook.clone())
+            .json(&json!({ "text": message }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(err) = result {
+            eprintln!("告警推送失败: {}", err);
+        }
+    }
+}
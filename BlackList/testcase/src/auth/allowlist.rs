@@ -0,0 +1,30 @@
+use alloy::primitives::Address;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// 白名单表：这些地址不会被写入黑名单，也不会被推送上链。生产与影子模式共用
+pub const ALLOWLIST_TABLE: &str = "blacklist_allowlist";
+
+/// 新增或更新的白名单地址
+#[derive(Debug, Clone)]
+pub struct NewAllowlistEntry {
+    pub address: Address,
+    pub chain_type: String,
+    /// 加入白名单的原因，例如路由、聚合器
+    pub reason: String,
+    /// 负责人
+    pub owner: String,
+}
+
+/// 白名单表中的一行
+#[derive(Debug, FromRow)]
+pub struct AllowlistEntry {
+    pub address: String,
+    pub chain_type: String,
+    pub reason: String,
+    pub owner: String,
+    pub added_at: DateTime<Utc>,
+    /// 被白名单抑制的检测结果数，用于调整检测器
+    pub suppressed_count: i64,
+    pub last_suppressed_at: Option<DateTime<Utc>>,
+}
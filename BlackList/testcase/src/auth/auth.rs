@@ -1,37 +1,77 @@
 use alloy::{
-    dyn_abi::DynSolValue,
-    network::{EthereumWallet, NetworkWallet, TransactionBuilder},
-    primitives::{address, utils::parse_ether, Address, BlockHash, Bytes, FixedBytes, U256},
+    network::{EthereumWallet, TransactionBuilder},
+    primitives::{Address, FixedBytes, TxHash, U256},
     providers::{
-        ext::{DebugApi, TraceApi},
-        fillers::{NonceFiller, NonceManager},
-        Provider, ProviderBuilder, RootProvider, WalletProvider, WsConnect,
+        ext::DebugApi, fillers::NonceFiller, PendingTransactionBuilder, PendingTransactionError,
+        Provider, ProviderBuilder, RootProvider, WalletProvider, WatchTxError, WsConnect,
     },
     pubsub::PubSubFrontend,
     rpc::types::{
         trace::geth::{CallFrame, GethDebugTracerType, GethDebugTracingOptions, TraceResult},
-        TransactionRequest,
+        BlockTransactionsKind, TransactionReceipt, TransactionRequest,
     },
-    signers::{k256::elliptic_curve::rand_core::le, local::PrivateKeySigner},
+    signers::local::PrivateKeySigner,
     sol,
     transports::http::reqwest::Url,
 };
 use anyhow::{anyhow, Result};
-use chrono::{DateTime, Duration, Utc};
+use async_trait::async_trait;
+use chrono::Utc;
 use futures_util::{stream, StreamExt};
-use sqlx::{mysql::MySqlPoolOptions, MySql, Pool};
-use std::{collections::HashSet, env, f32::consts::E, str::FromStr, sync::Arc};
-use tokio::{
+use std::{
+    collections::{BTreeMap, HashSet},
+    env,
+    path::PathBuf,
     sync::{
-        broadcast::{self, Receiver, Sender},
-        mpsc, Mutex,
+        atomic::{AtomicU64, Ordering},
+        Arc,
     },
+    time::Duration,
+};
+use tokio::{
+    sync::{broadcast, Mutex, RwLock},
     task::JoinSet,
+    time::Instant,
 };
 
 use crate::auth::{
-    data::{address_exists_in_blacklist, create_blacklist_entry},
-    generate_data::generate_random_reason,
+    alert::Alerter,
+    batch::{BatchPolicy, GasSplit, PendingQueue},
+    data::{
+        BlacklistEntry, EntryStatus, NewBlacklistEntry, UpsertOutcome, BLACKLIST_TABLE,
+        SHADOW_BLACKLIST_TABLE,
+    },
+    evidence::{Evidence, Severity, REENTRANCY_DETECTOR},
+    expiry::ExpiryPolicy,
+    export::{
+        archive_batch, load_pending, serve, write_batch, ExportConfig, ExportedBatch,
+        RawTransaction,
+    },
+    fees::FeeConfig,
+    history::{ChangeContext, ChangeSource},
+    nonce::NonceManager,
+    ofac::SdnSync,
+    pause::{EscalationPolicy, PauseLimiter, PauseTarget},
+    payload::{
+        AuthModuleCall, BatchSetInfoPayload, ChangeModePayload, RemoveInfoPayload, SetInfoPayload,
+    },
+    preflight::simulate,
+    projects::{ProjectDirectory, ProjectMode, ProjectPolicy},
+    query::{BlacklistQuery, MAX_PAGE_SIZE},
+    reconcile::{
+        black_list_mode, index_blacklist_events, is_globally_blacklisted, onchain_blacklist,
+        onchain_function_blacklist, scope_candidates, ReconcilePlan,
+    },
+    replay::record_block,
+    retry::RetryBackoff,
+    scope::FunctionScope,
+    signer::SignerConfig,
+    store::{connect, database_url, BlacklistStore},
+    sync::{
+        env_or, mark_exported, mark_failed, mark_in_sync, mark_submitted, mark_synced,
+        track_receipt, ReceiptTracker, SyncConfig, SyncStatus,
+    },
+    token::{add_calldata, is_blacklisted, onchain_token_blacklist, remove_calldata, TokenTargets},
 };
 
 sol!(
@@ -66,23 +106,150 @@ type FillProvider = alloy::providers::fillers::FillProvider<
     PubSubFrontend,
     alloy::network::Ethereum,
 >;
+
+/// 运行模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// 写入生产黑名单表，并广播链上更新交易
+    Production,
+    /// 影子模式：写入影子表，链上更新只模拟不广播
+    Shadow,
+}
+
+impl RunMode {
+    /// 通过环境变量 `SHADOW_MODE` 开启影子模式
+    pub fn from_env() -> Self {
+        match env::var("SHADOW_MODE") {
+            Ok(v) if matches!(v.to_lowercase().as_str(), "1" | "true" | "yes") => Self::Shadow,
+            _ => Self::Production,
+        }
+    }
+
+    /// 当前模式写入的黑名单表
+    pub fn table(&self) -> &'static str {
+        match self {
+            Self::Production => BLACKLIST_TABLE,
+            Self::Shadow => SHADOW_BLACKLIST_TABLE,
+        }
+    }
+}
+
+/// 调用路径节选最多保留的调用数，取离重入点最近的部分
+const MAX_CALL_PATH: usize = 16;
+
+/// 一次检测结果：发起攻击的地址，以及被重入的合约与函数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub address: Address,
+    pub target: Address,
+    /// 经 fallback 重入时没有 selector
+    pub selector: Option<FixedBytes<4>>,
+    pub tx_hash: Option<TxHash>,
+    pub detector: &'static str,
+    /// 被重入合约在该交易中转出的原生代币
+    pub loss: U256,
+    /// 从交易入口到重入点的调用路径，每一项为 `合约|selector`
+    pub call_path: Vec<String>,
+}
+
+impl Finding {
+    /// 被重入的是受保护项目且识别出了函数时，只在该函数上拦截
+    pub fn function_scope(&self, project: Address) -> Option<FunctionScope> {
+        match self.selector {
+            Some(selector) if self.target == project => Some(FunctionScope {
+                address: self.address,
+                project,
+                selector,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// 更新器的初始化范围，命令行子命令只初始化所需部分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Startup {
+    /// 常驻运行：启动时发现项目并恢复未完成的同步
+    Run,
+    /// 单次发送交易的命令：需要签名，用到项目列表时才发现项目
+    Command,
+    /// 只读链上状态的命令：不需要签名
+    ReadOnly,
+}
+
 pub struct BlackListUpdater {
     provider: Arc<FillProvider>,
-    black_list: Arc<Mutex<Vec<Address>>>,
+    store: Arc<dyn BlacklistStore>,
+    mode: RunMode,
+    record_dir: Option<PathBuf>,
+    chain_id: u64,
+    proxy: Address,
+    auth_module: Address,
+    project_policy: ProjectPolicy,
+    projects: Arc<RwLock<ProjectDirectory>>,
+    project_refresh: Duration,
+    /// 同步代币级黑名单的 RegulatedToken 部署
+    tokens: TokenTargets,
+    sync_config: SyncConfig,
+    expiry_policy: ExpiryPolicy,
+    reap_interval: Duration,
+    fee_config: FeeConfig,
+    nonce_manager: NonceManager,
+    /// 为 false 时所有检测结果都走全局拦截
+    function_scope: bool,
+    /// 注册表为 RegistryV2 时才转发 changeMode，V1 只能通过 setInfo 开启全局模式
+    registry_v2: bool,
+    batch_policy: BatchPolicy,
+    /// 严重发现时自动暂停项目或函数
+    escalation: EscalationPolicy,
+    pause_limiter: Mutex<PauseLimiter>,
+    alerter: Alerter,
+    /// 同步失败的目标与函数级黑名单按指数退避重试，相同错误只告警一次
+    sync_backoff: Mutex<RetryBackoff<Address>>,
+    scope_backoff: Mutex<RetryBackoff<FunctionScope>>,
+    /// 各项目的待同步队列
+    black_list: Arc<Mutex<BTreeMap<Address, PendingQueue>>>,
+    function_list: Arc<Mutex<Vec<FunctionScope>>>,
+    /// 多签导出模式，设置后生产模式不再直接签名发送
+    export: Option<ExportConfig>,
+    exported: Arc<Mutex<Vec<ExportedBatch>>>,
+    export_seq: AtomicU64,
 }
 
 impl BlackListUpdater {
     pub async fn new() -> Result<Self> {
-        // 创建mysql实例
-        // let mysql = mysql::MySqlPool::connect(&env::var("MYSQL_URL")?).await?;
-        // println!("{:?}", mysql);
+        Self::start(Startup::Run).await
+    }
+
+    pub async fn start(startup: Startup) -> Result<Self> {
+        let mode = RunMode::from_env();
+
+        // 黑名单、同步状态、nonce 等表都通过存储后端访问，按连接串选择数据库
+        let url = database_url().ok_or_else(|| anyhow!("未配置 DATABASE_URL/MYSQL_URL"))?;
+        let store = connect(&url, mode.table()).await?;
+        store.verify_schema().await?;
+        if mode == RunMode::Shadow {
+            println!("影子模式：检测结果写入 {}，链上更新仅模拟", mode.table());
+        }
+
+        let export = ExportConfig::from_env()?;
 
         // 创建provider
         let rpc = env::var("WSSRPC")?.parse::<Url>()?;
         let ws = WsConnect::new(rpc);
-        let signer: PrivateKeySigner = env::var("PK")?.parse()?;
-
-        let wallet = EthereumWallet::from(signer);
+        let signer = match startup {
+            Startup::ReadOnly => None,
+            Startup::Run | Startup::Command => SignerConfig::from_env()?,
+        };
+        let wallet = match (&export, signer) {
+            (_, Some(config)) => config.into_wallet()?,
+            // 导出模式与只读命令不发送交易，不需要持有管理员私钥
+            (Some(_), None) => EthereumWallet::from(PrivateKeySigner::random()),
+            (None, None) if startup == Startup::ReadOnly => {
+                EthereumWallet::from(PrivateKeySigner::random())
+            }
+            (None, None) => return Err(anyhow!("未配置签名方式: REMOTE_SIGNER/KEYSTORE/PK")),
+        };
 
         let provider = Arc::new(
             ProviderBuilder::new()
@@ -91,11 +258,78 @@ impl BlackListUpdater {
                 .on_ws(ws)
                 .await?,
         );
+        let chain_id = provider.get_chain_id().await?;
+        // 导出模式下交易由 Safe 发出，模拟与估算都以 Safe 为发送方
+        let sender = match &export {
+            Some(config) => config.safe,
+            None => provider.default_signer_address(),
+        };
+        let nonce_manager = NonceManager::new(store.clone(), chain_id, sender);
+        let exported = match &export {
+            Some(config) => load_pending(config)?,
+            None => vec![],
+        };
+
+        // 从注册表事件中发现启用了鉴权模块的项目
+        let proxy: Address = env::var("PROXY")?.parse()?;
+        let auth_module: Address = env::var("AUTHMOD")?.parse()?;
+        let mut projects =
+            ProjectDirectory::new(auth_module, env_or("PROJECT_DISCOVERY_FROM_BLOCK", 0));
+        if startup == Startup::Run {
+            projects.refresh(provider.as_ref(), proxy).await?;
+        }
+        let project_policy = ProjectPolicy::from_env()?;
 
-        Ok(Self {
+        // 已暂停且尚未人工恢复的目标不再重复暂停
+        let escalation = EscalationPolicy::from_env()?;
+        let mut paused = vec![];
+        if mode == RunMode::Production {
+            paused = store.list_active_pauses().await?;
+        }
+        let pause_limiter = PauseLimiter::new(paused.into_iter().map(|record| record.target));
+        if startup == Startup::Run {
+            println!(
+                "推送黑名单的项目: {:?}",
+                project_policy.targets(&projects.projects())
+            );
+        }
+
+        let updater = Self {
             provider,
-            black_list: Arc::new(Mutex::new(vec![])),
-        })
+            store,
+            mode,
+            record_dir: env::var("RECORD_DIR").ok().map(PathBuf::from),
+            chain_id,
+            proxy,
+            auth_module,
+            project_policy,
+            projects: Arc::new(RwLock::new(projects)),
+            project_refresh: Duration::from_secs(env_or("PROJECT_REFRESH_SECS", 600)),
+            tokens: TokenTargets::from_env()?,
+            sync_config: SyncConfig::from_env(),
+            expiry_policy: ExpiryPolicy::from_env()?,
+            reap_interval: Duration::from_secs(env_or("EXPIRY_REAP_INTERVAL_SECS", 300)),
+            fee_config: FeeConfig::from_env(),
+            nonce_manager,
+            function_scope: env_or("FUNCTION_SCOPE", true),
+            registry_v2: env_or("REGISTRY_V2", false),
+            batch_policy: BatchPolicy::from_env()?,
+            escalation,
+            pause_limiter: Mutex::new(pause_limiter),
+            alerter: Alerter::from_env()?,
+            sync_backoff: Mutex::new(RetryBackoff::from_env()),
+            scope_backoff: Mutex::new(RetryBackoff::from_env()),
+            black_list: Arc::new(Mutex::new(BTreeMap::new())),
+            function_list: Arc::new(Mutex::new(vec![])),
+            export,
+            exported: Arc::new(Mutex::new(exported)),
+            export_seq: AtomicU64::new(0),
+        };
+        if startup == Startup::Run && mode == RunMode::Production {
+            updater.resume_unsynced().await?;
+        }
+
+        Ok(updater)
     }
 
     pub async fn run(self: Arc<Self>) -> Result<JoinSet<()>> {
@@ -116,11 +350,51 @@ impl BlackListUpdater {
             }
         });
 
+        // 定期发现新注册的项目
+        let self_clone_projects = Arc::clone(&self);
+        set.spawn(async move {
+            let mut interval = tokio::time::interval(self_clone_projects.project_refresh);
+            loop {
+                interval.tick().await;
+                if let Err(err) = self_clone_projects.refresh_projects().await {
+                    eprintln!("刷新项目列表失败: {}", err);
+                }
+            }
+        });
+
+        // 多签可能在更新程序停止或漏掉区块时执行，启动时及定期按链上状态确认已导出的批次
+        if let Some(config) = &self.export {
+            let self_clone_exported = Arc::clone(&self);
+            let check_interval = config.check_interval;
+            set.spawn(async move {
+                let mut interval = tokio::time::interval(check_interval);
+                loop {
+                    interval.tick().await;
+                    if let Err(err) = self_clone_exported.check_exported_onchain().await {
+                        eprintln!("Failed to check exported batches on chain: {}", err);
+                    }
+                }
+            });
+        }
+
+        // 通过 HTTP 提供待签名的批次
+        if let Some(config) = &self.export {
+            if let Some(http) = config.http.clone() {
+                let config = config.clone();
+                set.spawn(async move {
+                    if let Err(err) = serve(config, http).await {
+                        eprintln!("导出服务退出: {}", err);
+                    }
+                });
+            }
+        }
+
         // 检测
         let self_clone_detect = Arc::clone(&self);
         set.spawn(async move {
             let mut receiver = sender_hash.subscribe();
             let provider = self_clone_detect.provider.clone();
+            let store = self_clone_detect.store.as_ref();
 
             while let Ok(block_hash) = receiver.recv().await {
                 let trace_options = GethDebugTracingOptions {
@@ -129,180 +403,1513 @@ impl BlackListUpdater {
                     )),
                     ..Default::default()
                 };
-                let info = provider
-                    .debug_trace_block_by_hash(block_hash.into(), trace_options)
+                let info = match provider
+                    .debug_trace_block_by_hash(block_hash, trace_options)
                     .await
-                    .unwrap();
-                // 本次检测得到的黑名单地址
-                let mut new_black_list = detect(info).unwrap();
-                let db_url = env::var("MYSQL_URL").expect("MYSQL_URL must be set");
-                let pool = MySqlPoolOptions::new()
-                    .max_connections(5)
-                    .connect(&db_url)
-                    .await
-                    .unwrap();
-                // 黑名单加入数据库
-                for address in &new_black_list {
-                    // 检查数据库中是否已存在该地址
-                    if let Ok(exists) =
-                        address_exists_in_blacklist(&pool, &address.to_string()).await
+                {
+                    Ok(info) => info,
+                    Err(err) => {
+                        eprintln!("Failed to trace block {}: {}", block_hash, err);
+                        continue;
+                    }
+                };
+                // 录制区块，供离线回放
+                if let Some(dir) = &self_clone_detect.record_dir {
+                    if let Err(err) = record_block(provider.as_ref(), dir, block_hash, &info).await
                     {
-                        if exists {
-                            eprintln!(
-                                "Address {} is already in the blacklist. Skipping insertion.",
-                                address
-                            );
-                            continue; // 如果存在，则跳过插入
+                        eprintln!("Failed to record block {}: {}", block_hash, err);
+                    }
+                }
+                // 检查已导出的批次是否已被多签执行
+                if let Err(err) = self_clone_detect.observe_exported(&info).await {
+                    eprintln!("Failed to check exported batches: {}", err);
+                }
+                let findings = match detect(info) {
+                    Ok(findings) => findings,
+                    Err(err) => {
+                        eprintln!("Failed to detect block {}: {}", block_hash, err);
+                        continue;
+                    }
+                };
+                // 白名单地址的检测结果在暂停与写库之前去掉
+                let findings = self_clone_detect.suppress_allowlisted(findings).await;
+                // 严重发现先暂停，不等待黑名单批次
+                if self_clone_detect.escalation.is_enabled() {
+                    for finding in &findings {
+                        if let Some(target) = self_clone_detect.escalation.target(finding) {
+                            let updater = Arc::clone(&self_clone_detect);
+                            let attacker = finding.address;
+                            tokio::spawn(async move {
+                                if let Err(err) = updater.escalate(target, attacker).await {
+                                    eprintln!("暂停 {:?} 失败: {}", target, err);
+                                }
+                            });
                         }
                     }
-                    let address = address.to_string();
-                    let reason = generate_random_reason();
-                    let status = "active";
-                    let expires_at = Some(Utc::now());
-                    let chain_type = "ETH";
-                    let created_by = None;
-
-                    let result = create_blacklist_entry(
-                        &pool, &address, &reason, status, expires_at, chain_type, created_by,
-                    )
-                    .await;
-
-                    if let Err(err) = result {
-                        eprintln!(
-                            "Failed to insert blacklist entry for address {}: {}",
-                            address, err
-                        );
+                }
+                let targets = self_clone_detect.targets().await;
+                // 被重入的是受保护项目且识别出了函数时只做函数级拦截，其余推送到所有接收全局拦截的项目
+                let mut new_black_list = vec![];
+                let mut new_scopes = vec![];
+                for finding in &findings {
+                    let scope = targets
+                        .iter()
+                        .find_map(|(project, _)| finding.function_scope(*project))
+                        .filter(|_| self_clone_detect.function_scope);
+                    match scope {
+                        Some(scope) => new_scopes.push(scope),
+                        None => new_black_list.push(finding.address),
+                    }
+                }
+                // 黑名单连同证据加入数据库，证据需要区块号，只在有发现时查询区块头
+                let block_number = if findings.is_empty() {
+                    None
+                } else {
+                    provider
+                        .get_block_by_hash(block_hash, BlockTransactionsKind::Hashes)
+                        .await
+                        .ok()
+                        .flatten()
+                        .map(|block| block.header.number)
+                };
+                let evidence: Vec<Evidence> = findings
+                    .iter()
+                    .map(|finding| {
+                        let severity = Severity::of(finding, &self_clone_detect.escalation);
+                        Evidence::new(finding, block_number, block_hash, severity)
+                    })
+                    .collect();
+                let recorded = record_findings(
+                    self_clone_detect.store.as_ref(),
+                    &self_clone_detect.expiry_policy,
+                    &evidence,
+                )
+                .await;
+                // 暂停、申诉、审核中或在白名单中的地址不推送上链
+                new_black_list.retain(|address| !recorded.held.contains(address));
+                new_scopes.retain(|scope| !recorded.held.contains(&scope.address));
+                let inserted = recorded.inserted;
+                for scope in &new_scopes {
+                    if let Err(err) = store.record_function_scope(scope).await {
+                        eprintln!("Failed to persist function scope: {}", err);
                     }
                 }
+                if !new_scopes.is_empty() {
+                    let mut scopes = self_clone_detect.function_list.lock().await;
+                    for scope in new_scopes {
+                        if !scopes.contains(&scope) {
+                            scopes.push(scope);
+                        }
+                    }
+                    // 函数级拦截逐条发送，不必等待凑满批次
+                    sender_bool.send(true).unwrap();
+                }
+                let policy = &self_clone_detect.batch_policy;
+                // 任一证据达到立即上链的严重程度时不等待凑满批次
+                let urgent = |address: &Address| {
+                    evidence.iter().any(|evidence| {
+                        evidence.attacker == *address && policy.is_urgent(evidence.severity)
+                    })
+                };
                 let mut locked = self_clone_detect.black_list.lock().await;
-                // 更新
-                locked.append(&mut new_black_list);
-                println!("locked {:?}", locked);
-                if locked.len() > 2 {
+                let mut flush = false;
+                for (project, mode) in &targets {
+                    if *mode != ProjectMode::All || new_black_list.is_empty() {
+                        continue;
+                    }
+                    if self_clone_detect.mode == RunMode::Production {
+                        if let Err(err) = store.mark_pending(*project, &new_black_list).await {
+                            eprintln!("Failed to persist sync state: {}", err);
+                        }
+                    }
+                    // 更新
+                    let queue = locked.entry(*project).or_default();
+                    for address in &new_black_list {
+                        queue.push(*address, urgent(address));
+                    }
+                    println!("项目 {} 待同步: {:?}", project, queue.addresses());
+                    flush |= policy.should_flush(queue, Instant::now());
+                }
+                // 选中的新记录同步到代币黑名单
+                for token in &self_clone_detect.tokens.tokens {
+                    let selected: Vec<(Address, bool)> = inserted
+                        .iter()
+                        .filter(|(address, reason)| {
+                            new_black_list.contains(address)
+                                && self_clone_detect.tokens.selects(reason)
+                        })
+                        .map(|(address, _)| (*address, urgent(address)))
+                        .collect();
+                    if selected.is_empty() {
+                        continue;
+                    }
+                    let addresses: Vec<Address> = selected.iter().map(|(addr, _)| *addr).collect();
+                    if self_clone_detect.mode == RunMode::Production {
+                        if let Err(err) = store.mark_pending(*token, &addresses).await {
+                            eprintln!("Failed to persist sync state: {}", err);
+                        }
+                    }
+                    let queue = locked.entry(*token).or_default();
+                    for (address, urgent) in selected {
+                        queue.push(address, urgent);
+                    }
+                    println!("代币 {} 待同步: {:?}", token, queue.addresses());
+                    flush |= policy.should_flush(queue, Instant::now());
+                }
+                if flush {
                     sender_bool.send(true).unwrap();
                 }
-                // 将locked内容写入黑名单数据库表中
             }
         });
 
         // 发交易，更新auth模块的地址
         let self_clone_send = Arc::clone(&self);
         set.spawn(async move {
-            while let Ok(_) = rece_bool.recv().await {
-                // TODO calldata
-                let proxy_addr = env::var("PROXY").unwrap().parse::<Address>().unwrap();
-                let proxy = RegistryProxy::new(proxy_addr, self_clone_send.provider.clone());
-                let implement = Arc::new(Registry::new(
-                    env::var("IMPL").unwrap().parse::<Address>().unwrap(),
-                    self_clone_send.provider.clone(),
-                ));
+            // 定时检查最早入队的地址是否已等待超时
+            let mut tick = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                let notified = tokio::select! {
+                    received = rece_bool.recv() => match received {
+                        Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => true,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = tick.tick() => false,
+                };
+                // 需要发送的项目及其待同步地址，跳过仍在退避中的目标
+                let now = Instant::now();
+                let flush: Vec<(Address, Vec<Address>)> = {
+                    let locked = self_clone_send.black_list.lock().await;
+                    let backoff = self_clone_send.sync_backoff.lock().await;
+                    locked
+                        .iter()
+                        .filter(|(project, queue)| {
+                            backoff.ready(project, now)
+                                && self_clone_send.batch_policy.should_flush(queue, now)
+                        })
+                        .map(|(project, queue)| (*project, queue.addresses()))
+                        .collect()
+                };
+                if !notified && flush.is_empty() {
+                    continue;
+                }
 
-                let black_list_clone = self.black_list.lock().await.clone();
+                let scopes: Vec<FunctionScope> = {
+                    let backoff = self_clone_send.scope_backoff.lock().await;
+                    self_clone_send
+                        .function_list
+                        .lock()
+                        .await
+                        .iter()
+                        .filter(|scope| backoff.ready(scope, now))
+                        .copied()
+                        .collect()
+                };
+                for scope in scopes {
+                    match self_clone_send.sync_function_scope(&scope).await {
+                        Ok(()) => {
+                            self_clone_send.scope_backoff.lock().await.succeeded(&scope);
+                            self_clone_send
+                                .function_list
+                                .lock()
+                                .await
+                                .retain(|s| *s != scope)
+                        }
+                        Err(err) => {
+                            let delay = self_clone_send
+                                .scope_backoff
+                                .lock()
+                                .await
+                                .failed(scope, Instant::now());
+                            eprintln!("函数级黑名单同步失败，{:?} 后重试: {}", delay, err);
+                        }
+                    }
+                }
 
-                let tokens = DynSolValue::Tuple(
-                    black_list_clone
-                        .into_iter()
-                        .map(|addr| DynSolValue::Address(addr))
-                        .collect::<Vec<_>>(),
-                );
-                // let param0 = env::var("PROJECT").unwrap().parse::<Address>().unwrap(); // address project
-                // let param1 = DynSolValue::Bool((true)); // bool _isFuctionAccessBlacklist
-                //                                         // let param2 = env::var("FUNCSIG").unwrap().parse::<Address>().unwrap(); // bytes4 funcSig
-                // let param3 = env::var("BLACKADDR").unwrap().parse::<Address>().unwrap(); // address blackAddr
-                // let param4 = DynSolValue::Bool((true)); // bool isblack
-                // let param5 = DynSolValue::Bool((true)); // bool useFunctionAccess
-
-                // let tokens = DynSolValue::Tuple(vec![
-                //     DynSolValue::Address(param0),
-                //     param1,
-                //     DynSolValue::Bytes(vec![0x1, 0x2, 0x3, 0x4]),
-                //     DynSolValue::Address(param3),
-                //     param4,
-                //     param5,
-                // ]);
-                let param = tokens.abi_encode();
-                let call_data = implement
-                    .updataModuleInfo(
-                        env::var("AUTHMOD").unwrap().parse::<Address>().unwrap(),
-                        param.into(),
-                    )
-                    .calldata()
-                    .clone();
-                let call_on_data = proxy.CallOn(call_data).calldata().clone();
-                let tx = TransactionRequest::default()
-                    .with_to(proxy_addr) // to auth_mod 地址
-                    .with_input(call_on_data) // calldata
-                    .with_chain_id(self_clone_send.provider.get_chain_id().await.unwrap())
-                    .with_value(U256::ZERO) // chain_id
-                    .with_gas_limit(10000000)
-                    .with_max_fee_per_gas(0)
-                    .with_max_priority_fee_per_gas(0);
-                println!("等待发送交易");
-                let pending_tx = self_clone_send.provider.send_transaction(tx).await.unwrap();
-                let mut locked = self_clone_send.black_list.lock().await;
-
-                locked.clear();
+                for (target, batch) in flush {
+                    let result = if self_clone_send.tokens.contains(&target) {
+                        self_clone_send.sync_token(target, &batch).await
+                    } else {
+                        self_clone_send.sync_chunked(target, &batch).await
+                    };
+                    let mut backoff = self_clone_send.sync_backoff.lock().await;
+                    match result {
+                        Ok(()) => backoff.succeeded(&target),
+                        Err(err) => {
+                            let delay = backoff.failed(target, Instant::now());
+                            eprintln!(
+                                "{} 黑名单同步失败，地址保留，{:?} 后重试: {}",
+                                target, delay, err
+                            );
+                        }
+                    }
+                }
             }
         });
 
-        // todo 添加一个守护线程去判断更新交易是否成功执行
+        // 定期清理过期的黑名单
+        let self_clone_reap = Arc::clone(&self);
+        set.spawn(async move {
+            let mut interval = tokio::time::interval(self_clone_reap.reap_interval);
+            loop {
+                interval.tick().await;
+                match self_clone_reap.reap_expired().await {
+                    Ok(0) => {}
+                    Ok(n) => println!("{} 个黑名单地址已过期", n),
+                    Err(err) => eprintln!("清理过期黑名单失败: {}", err),
+                }
+            }
+        });
 
         Ok(set)
     }
+
+    /// 将到期的记录置为 expired，并从所有已推送的项目中移除
+    pub async fn reap_expired(&self) -> Result<usize> {
+        let mut reaped = 0;
+        for entry in self.store.list_expired(Utc::now()).await? {
+            if let Ok(address) = entry.address.parse::<Address>() {
+                // 移除失败时保持 active，下一轮重试
+                if !self.remove_on_chain(address).await? {
+                    continue;
+                }
+            }
+            let context = ChangeContext::new(ChangeSource::Expiry);
+            self.store
+                .update(entry.id, EntryStatus::Expired, entry.expires_at, &context)
+                .await?;
+            reaped += 1;
+        }
+        Ok(reaped)
+    }
+
+    /// 人工修改地址的状态，进入 active 时加入待同步队列，离开 active 时先从链上移除；
+    /// 返回原状态
+    pub async fn set_status(
+        &self,
+        address: Address,
+        status: EntryStatus,
+        context: &ChangeContext,
+    ) -> Result<EntryStatus> {
+        let entry = self
+            .store
+            .find(&address)
+            .await?
+            .ok_or_else(|| anyhow!("{} 不在黑名单中", address))?;
+        self.update_status(&entry, status, context).await
+    }
+
+    // 按 set_status 的规则修改指定记录的状态并同步链上，返回原状态
+    async fn update_status(
+        &self,
+        entry: &BlacklistEntry,
+        status: EntryStatus,
+        context: &ChangeContext,
+    ) -> Result<EntryStatus> {
+        let address: Address = entry.address.parse()?;
+        let from: EntryStatus = entry.status.parse()?;
+        from.transition(status)?;
+        // 链上移除失败时不修改状态，保持与链上一致
+        if from.is_listed() && !status.is_listed() && !self.remove_on_chain(address).await? {
+            return Err(anyhow!("从链上移除 {} 失败，状态未修改", address));
+        }
+        self.store
+            .update(entry.id, status, entry.expires_at, context)
+            .await?;
+        if !from.is_listed() && status.is_listed() {
+            // 人工恢复的记录立即上链
+            self.refresh_projects().await?;
+            self.add_on_chain(address, &entry.reason, true).await;
+        }
+        Ok(from)
+    }
+
+    /// 立即发送该地址在各目标上的待同步记录，其余积压留给常驻进程，供命令行在退出前调用
+    pub async fn flush_address(&self, address: Address) -> Result<()> {
+        let targets: Vec<Address> = self
+            .black_list
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, queue)| queue.addresses().contains(&address))
+            .map(|(target, _)| *target)
+            .collect();
+        for target in targets {
+            if self.tokens.contains(&target) {
+                self.sync_token(target, &[address]).await?;
+            } else {
+                self.sync_chunked(target, &[address]).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // 增量索引注册表事件，发现新启用鉴权模块的项目
+    async fn refresh_projects(&self) -> Result<()> {
+        self.projects
+            .write()
+            .await
+            .refresh(self.provider.as_ref(), self.proxy)
+            .await
+    }
+
+    // 地址加入所有接收全局拦截的项目及选中该原因的代币的待同步队列，由批处理发送；
+    // urgent 为 true 时不等待凑满批次
+    async fn add_on_chain(&self, address: Address, reason: &str, urgent: bool) {
+        let mut targets: Vec<Address> = self
+            .targets()
+            .await
+            .into_iter()
+            .filter(|(_, mode)| *mode == ProjectMode::All)
+            .map(|(project, _)| project)
+            .collect();
+        if self.tokens.selects(reason) {
+            targets.extend(self.tokens.tokens.iter().copied());
+        }
+        let mut locked = self.black_list.lock().await;
+        for target in targets {
+            if self.mode == RunMode::Production {
+                if let Err(err) = self.store.mark_pending(target, &[address]).await {
+                    eprintln!("Failed to persist sync state: {}", err);
+                }
+            }
+            locked.entry(target).or_default().push(address, urgent);
+        }
+    }
+
+    // 从所有已推送的项目、函数级拦截及代币中移除地址，返回是否全部移除成功
+    async fn remove_on_chain(&self, address: Address) -> Result<bool> {
+        // 尚未推送的地址从待同步队列中移除即可
+        for queue in self.black_list.lock().await.values_mut() {
+            queue.retain(|addr| *addr != address);
+        }
+        self.function_list
+            .lock()
+            .await
+            .retain(|scope| scope.address != address);
+        let pushed = match self.mode {
+            RunMode::Production => self.store.pushed_projects(&address).await?,
+            RunMode::Shadow => self
+                .targets()
+                .await
+                .into_iter()
+                .map(|(project, _)| project)
+                .collect(),
+        };
+        let mut calls = vec![];
+        let mut tokens = vec![];
+        for project in pushed {
+            if self.tokens.contains(&project) {
+                tokens.push(project);
+                continue;
+            }
+            calls.push(RemoveInfoPayload::new(project, Default::default(), address));
+        }
+        if self.mode == RunMode::Shadow {
+            tokens = self.tokens.tokens.clone();
+        }
+        for (scope, synced) in self.store.list_scopes(&address).await? {
+            if synced || self.mode == RunMode::Shadow {
+                calls.push(RemoveInfoPayload::new(
+                    scope.project,
+                    scope.selector,
+                    address,
+                ));
+            }
+        }
+        for payload in calls {
+            let project = payload.project;
+            let call = AuthModuleCall::RemoveInfo(payload);
+            if let Err(err) = self.submit(&call, project, &[]).await {
+                eprintln!("移除地址 {} 失败: {}", address, err);
+                return Ok(false);
+            }
+        }
+        for token in tokens {
+            if let Err(err) = self.remove_from_token(token, address).await {
+                eprintln!("从代币 {} 移除地址 {} 失败: {}", token, address, err);
+                return Ok(false);
+            }
+        }
+        self.store.delete_scopes(&address).await?;
+        if self.mode == RunMode::Production {
+            self.store.delete_sync_state(&address).await?;
+        }
+        Ok(true)
+    }
+
+    // 构造调用auth模块的交易：经 RegistryProxy.CallOn 转发到注册表对应入口
+    fn build_tx(&self, call: &AuthModuleCall) -> TransactionRequest {
+        TransactionRequest::default()
+            .with_to(self.proxy)
+            .with_input(call.proxy_calldata(self.auth_module))
+            .with_from(self.nonce_manager.sender())
+            .with_chain_id(self.chain_id)
+            .with_value(U256::ZERO)
+    }
+
+    /// 按批次大小与 gas 预算拆分后逐批同步，每批确认后即从待同步队列移除，
+    /// 发送期间新检测到的地址留待下一批。单独发送也超出预算的地址告警后移出队列，
+    /// 数据库中保持待同步状态，调整预算后由重启或 reconcile 补发
+    async fn sync_chunked(&self, project: Address, addresses: &[Address]) -> Result<()> {
+        let addresses = self.without_allowlisted(project, addresses).await?;
+        for chunk in self.batch_policy.chunks(&addresses) {
+            let split = self.split_by_gas(project, chunk).await?;
+            for (address, gas) in split.oversized {
+                self.alerter
+                    .alert(&format!(
+                        "地址 {} 推送到 {} 需要 {} gas，超出预算 {}，暂不发送",
+                        address, project, gas, self.batch_policy.gas_budget
+                    ))
+                    .await;
+                if let Some(queue) = self.black_list.lock().await.get_mut(&project) {
+                    queue.retain(|addr| *addr != address);
+                }
+            }
+            for (batch, gas) in split.batches {
+                self.sync_batch(project, &batch, gas).await?;
+                if let Some(queue) = self.black_list.lock().await.get_mut(&project) {
+                    queue.retain(|addr| !batch.contains(addr));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 白名单中的全部地址
+    async fn allowlisted(&self) -> Result<HashSet<Address>> {
+        Ok(self
+            .store
+            .list_allowlist()
+            .await?
+            .iter()
+            .filter_map(|entry| entry.address.parse().ok())
+            .collect())
+    }
+
+    // 去掉白名单地址的检测结果并计数；读取白名单失败时保留全部结果，
+    // 写库与推送前仍会再次检查
+    async fn suppress_allowlisted(&self, findings: Vec<Finding>) -> Vec<Finding> {
+        if findings.is_empty() {
+            return findings;
+        }
+        let allowlisted = match self.allowlisted().await {
+            Ok(allowlisted) => allowlisted,
+            Err(err) => {
+                eprintln!("读取白名单失败: {}", err);
+                return findings;
+            }
+        };
+        let mut kept = vec![];
+        for finding in findings {
+            if !allowlisted.contains(&finding.address) {
+                kept.push(finding);
+                continue;
+            }
+            eprintln!(
+                "白名单地址 {} 的 {} 检测结果已抑制，目标 {} 函数 {:?} 交易 {:?}",
+                finding.address,
+                finding.detector,
+                finding.target,
+                finding.selector,
+                finding.tx_hash
+            );
+            if let Err(err) = self.store.record_suppressed(&finding.address).await {
+                eprintln!("记录白名单抑制次数失败: {}", err);
+            }
+        }
+        kept
+    }
+
+    // 推送前去掉白名单地址，并将其移出该目标的待同步队列、清除同步状态。
+    // 读取白名单失败时不推送，留待重试
+    async fn without_allowlisted(
+        &self,
+        target: Address,
+        addresses: &[Address],
+    ) -> Result<Vec<Address>> {
+        let allowlisted = self.allowlisted().await?;
+        let (blocked, allowed): (Vec<Address>, Vec<Address>) = addresses
+            .iter()
+            .partition(|address| allowlisted.contains(*address));
+        for address in &blocked {
+            eprintln!("{} 在白名单中，不推送到 {}", address, target);
+            if let Some(queue) = self.black_list.lock().await.get_mut(&target) {
+                queue.retain(|addr| addr != address);
+            }
+            if self.mode == RunMode::Production {
+                self.store.delete_sync_state(address).await?;
+            }
+        }
+        Ok(allowed)
+    }
+
+    /// 当前需要推送的项目及其模式
+    pub async fn targets(&self) -> Vec<(Address, ProjectMode)> {
+        let projects = self.projects.read().await.projects();
+        self.project_policy.targets(&projects)
+    }
+
+    // 估算批量调用的 gas，超出预算时对半拆分，返回各批地址及预检得到的 gas
+    async fn split_by_gas(&self, project: Address, chunk: Vec<Address>) -> Result<GasSplit> {
+        self.batch_policy
+            .split_by_gas(chunk, |batch| async move {
+                let size = batch.len();
+                let call = AuthModuleCall::BatchSetInfo(BatchSetInfoPayload::new(project, batch));
+                self.preflight(&self.build_tx(&call), project, size).await
+            })
+            .await
+    }
+
+    /// 同步一批地址到链上，只有在更新交易确认后才返回 Ok。
+    /// batchSetInfo 只在项目已开启全局模式时生效且不触发事件，未开启时先开启：
+    /// RegistryV2 通过 changeMode，V1 以 setInfo 写入第一个地址。
+    /// 已开启全局模式时只发送拆分批次时预检过的 batchSetInfo，沿用其 gas 估算
+    async fn sync_batch(&self, project: Address, batch: &[Address], gas: u64) -> Result<()> {
+        let Some((first, rest)) = batch.split_first() else {
+            return Ok(());
+        };
+        let (function_mode, global_mode) =
+            black_list_mode(self.provider.as_ref(), self.auth_module, project).await?;
+        let mut calls = vec![];
+        let mut addresses = batch.to_vec();
+        if !global_mode {
+            if self.registry_v2 {
+                calls.push(AuthModuleCall::ChangeMode(ChangeModePayload::new(
+                    project,
+                    function_mode,
+                    true,
+                )));
+            } else {
+                calls.push(AuthModuleCall::SetInfo(SetInfoPayload::global(
+                    project, *first, true,
+                )));
+                addresses = rest.to_vec();
+            }
+        }
+        if !addresses.is_empty() {
+            calls.push(AuthModuleCall::BatchSetInfo(BatchSetInfoPayload::new(
+                project, addresses,
+            )));
+        }
+        let preflighted = global_mode.then_some(gas);
+        self.submit_calls(&calls, project, batch, None, preflighted)
+            .await
+    }
+
+    // 依次提交多笔调用，只有最后一笔确认后才记录 batch 的同步状态；
+    // 导出模式下放在同一个多签批次中。preflighted 为最后一笔调用已预检得到的 gas
+    async fn submit_calls(
+        &self,
+        calls: &[AuthModuleCall],
+        project: Address,
+        batch: &[Address],
+        scope: Option<FunctionScope>,
+        preflighted: Option<u64>,
+    ) -> Result<()> {
+        if self.mode == RunMode::Production && self.export.is_some() {
+            for (index, call) in calls.iter().enumerate() {
+                if index + 1 == calls.len() && preflighted.is_some() {
+                    break;
+                }
+                self.preflight(&self.build_tx(call), project, batch.len().max(1))
+                    .await?;
+            }
+            let transactions = calls.iter().map(|call| self.raw_tx(call)).collect();
+            return self.export_batch(transactions, project, batch, scope).await;
+        }
+        for (index, call) in calls.iter().enumerate() {
+            if index + 1 == calls.len() {
+                let tx = self.build_tx(call);
+                return self
+                    .submit_preflighted(tx, project, batch, preflighted)
+                    .await;
+            }
+            self.submit(call, project, &[]).await?;
+        }
+        Ok(())
+    }
+
+    // 提交一次auth模块调用，batch 为需要记录 project 上同步状态的地址
+    async fn submit(
+        &self,
+        call: &AuthModuleCall,
+        project: Address,
+        batch: &[Address],
+    ) -> Result<()> {
+        self.submit_tx(self.build_tx(call), project, batch).await
+    }
+
+    // 提交任意更新交易：生产模式发送或导出，影子模式只模拟
+    async fn submit_tx(
+        &self,
+        tx: TransactionRequest,
+        project: Address,
+        batch: &[Address],
+    ) -> Result<()> {
+        self.submit_preflighted(tx, project, batch, None).await
+    }
+
+    // 提交更新交易，gas 为已预检得到的估算，为 None 时先预检
+    async fn submit_preflighted(
+        &self,
+        tx: TransactionRequest,
+        project: Address,
+        batch: &[Address],
+        gas: Option<u64>,
+    ) -> Result<()> {
+        match self.mode {
+            RunMode::Production => {
+                let gas = match gas {
+                    Some(gas) => gas,
+                    None => self.preflight(&tx, project, batch.len()).await?,
+                };
+                if self.export.is_none() {
+                    return self.send_and_track(project, batch, tx, gas).await;
+                }
+                let raw = RawTransaction {
+                    to: tx.to.and_then(|to| to.to().copied()).unwrap_or_default(),
+                    value: tx.value.unwrap_or_default(),
+                    data: tx.input.input().cloned().unwrap_or_default(),
+                };
+                self.export_batch(vec![raw], project, batch, None).await
+            }
+            RunMode::Shadow => {
+                // 影子模式只模拟执行，不广播
+                match simulate(self.provider.as_ref(), &tx).await {
+                    Ok(gas) => println!("[shadow] 模拟更新成功, gas估算: {}", gas),
+                    Err(err) => eprintln!("[shadow] 模拟更新失败: {}", err),
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 广播或导出前模拟更新交易。回滚时拒绝发送并告警，同一目标的相同错误只告警一次，
+    /// 地址保留在待同步队列中，修正配置后重试
+    async fn preflight(
+        &self,
+        tx: &TransactionRequest,
+        project: Address,
+        addresses: usize,
+    ) -> Result<u64> {
+        match simulate(self.provider.as_ref(), tx).await {
+            Ok(gas) => Ok(gas),
+            Err(err) => {
+                let alert = self.mode == RunMode::Production
+                    && self
+                        .sync_backoff
+                        .lock()
+                        .await
+                        .should_alert(project, &err.to_string());
+                if alert {
+                    self.alerter
+                        .alert(&format!(
+                            "发往 {:?} 的更新交易预检失败，已停止发送 (目标 {}, {} 个地址): {}",
+                            tx.to.and_then(|to| to.to().copied()),
+                            project,
+                            addresses,
+                            err
+                        ))
+                        .await;
+                }
+                Err(err)
+            }
+        }
+    }
+
+    // 构造直接调用注册表的交易，注册表入口与代理地址相同
+    fn build_registry_tx(&self, input: Vec<u8>) -> TransactionRequest {
+        TransactionRequest::default()
+            .with_to(self.proxy)
+            .with_input(input)
+            .with_from(self.nonce_manager.sender())
+            .with_chain_id(self.chain_id)
+            .with_value(U256::ZERO)
+    }
+
+    /// 对严重发现发起暂停，受频率限制，已暂停的目标跳过
+    async fn escalate(&self, target: PauseTarget, attacker: Address) -> Result<()> {
+        let allowed =
+            self.pause_limiter
+                .lock()
+                .await
+                .allow(&self.escalation, target, Instant::now());
+        if !allowed {
+            println!("{:?} 已暂停或超出暂停频率限制，跳过", target);
+            return Ok(());
+        }
+        println!(
+            "检测到针对 {:?} 的攻击 (攻击者 {})，发起暂停",
+            target, attacker
+        );
+        let tx = self.build_registry_tx(target.pause_calldata());
+        if let Err(err) = self.submit_tx(tx, target.project(), &[]).await {
+            self.pause_limiter.lock().await.revoke(&target);
+            return Err(err);
+        }
+        if self.mode == RunMode::Production {
+            self.store.record_pause(&target, attacker).await?;
+        }
+        Ok(())
+    }
+
+    /// 人工确认后恢复被自动暂停的项目或函数
+    pub async fn unpause(&self, target: PauseTarget) -> Result<()> {
+        let tx = self.build_registry_tx(target.unpause_calldata());
+        self.submit_tx(tx, target.project(), &[]).await?;
+        if self.mode == RunMode::Production {
+            self.store.record_unpause(&target).await?;
+        }
+        self.pause_limiter.lock().await.unpaused(&target);
+        Ok(())
+    }
+
+    // 构造 RegulatedToken 的调用交易，发送方须持有 REGULATOR_ROLE
+    fn build_token_tx(&self, token: Address, input: Vec<u8>) -> TransactionRequest {
+        TransactionRequest::default()
+            .with_to(token)
+            .with_input(input)
+            .with_from(self.nonce_manager.sender())
+            .with_chain_id(self.chain_id)
+            .with_value(U256::ZERO)
+    }
+
+    /// 将地址逐个加入代币黑名单。addToBlacklist 对已拉黑的地址会回滚，
+    /// 因此先读取链上状态，已拉黑的直接标记同步完成
+    async fn sync_token(&self, token: Address, addresses: &[Address]) -> Result<()> {
+        for address in &self.without_allowlisted(token, addresses).await? {
+            if is_blacklisted(self.provider.as_ref(), token, *address).await? {
+                if self.mode == RunMode::Production {
+                    mark_in_sync(self.store.as_ref(), token, &[*address]).await?;
+                }
+            } else {
+                let tx = self.build_token_tx(token, add_calldata(*address));
+                self.submit_tx(tx, token, &[*address]).await?;
+            }
+            if let Some(queue) = self.black_list.lock().await.get_mut(&token) {
+                queue.retain(|addr| addr != address);
+            }
+        }
+        Ok(())
+    }
+
+    async fn remove_from_token(&self, token: Address, address: Address) -> Result<()> {
+        if !is_blacklisted(self.provider.as_ref(), token, address).await? {
+            return Ok(());
+        }
+        let tx = self.build_token_tx(token, remove_calldata(address));
+        self.submit_tx(tx, token, &[]).await
+    }
+
+    /// 在被滥用的函数上拉黑地址；项目已开启全局模式但未开启函数模式时，
+    /// setInfo 只会写入全局黑名单，需要先通过 changeMode 开启函数模式。
+    /// Registry V1 不转发 changeMode，此时地址按合约逻辑进入全局黑名单
+    async fn sync_function_scope(&self, scope: &FunctionScope) -> Result<()> {
+        // 白名单地址直接丢弃，返回成功以移出待同步列表
+        if self.allowlisted().await?.contains(&scope.address) {
+            eprintln!("{} 在白名单中，不推送函数级黑名单", scope.address);
+            self.store.delete_scopes(&scope.address).await?;
+            return Ok(());
+        }
+        let (function_mode, global_mode) =
+            black_list_mode(self.provider.as_ref(), self.auth_module, scope.project).await?;
+        let mut calls = vec![];
+        if global_mode && !function_mode {
+            if self.registry_v2 {
+                calls.push(AuthModuleCall::ChangeMode(ChangeModePayload::new(
+                    scope.project,
+                    true,
+                    true,
+                )));
+            } else {
+                eprintln!(
+                    "项目 {} 未开启函数模式且注册表不支持 changeMode，{} 将进入全局黑名单",
+                    scope.project, scope.address
+                );
+            }
+        }
+        calls.push(AuthModuleCall::SetInfo(SetInfoPayload::function(
+            scope.project,
+            scope.selector,
+            scope.address,
+            true,
+        )));
+
+        // 导出模式下执行后再标记完成
+        let exporting = self.mode == RunMode::Production && self.export.is_some();
+        self.submit_calls(&calls, scope.project, &[], Some(*scope), None)
+            .await?;
+        if self.mode == RunMode::Production && !exporting {
+            self.store.mark_scope_synced(scope).await?;
+        }
+        Ok(())
+    }
+
+    fn raw_tx(&self, call: &AuthModuleCall) -> RawTransaction {
+        RawTransaction {
+            to: self.proxy,
+            value: U256::ZERO,
+            data: call.proxy_calldata(self.auth_module),
+        }
+    }
+
+    // 导出未签名的多签批次，等待签名人执行
+    async fn export_batch(
+        &self,
+        transactions: Vec<RawTransaction>,
+        project: Address,
+        addresses: &[Address],
+        scope: Option<FunctionScope>,
+    ) -> Result<()> {
+        let config = self
+            .export
+            .as_ref()
+            .ok_or_else(|| anyhow!("未开启多签导出"))?;
+        let batch = ExportedBatch {
+            id: format!(
+                "{}-{:04}",
+                Utc::now().timestamp_millis(),
+                self.export_seq.fetch_add(1, Ordering::Relaxed)
+            ),
+            transactions,
+            project,
+            addresses: addresses.to_vec(),
+            scope,
+        };
+        write_batch(config, self.chain_id, &batch)?;
+        mark_exported(self.store.as_ref(), project, addresses).await?;
+        println!("已导出多签批次 {}，等待签名执行", batch.id);
+        self.exported.lock().await.push(batch);
+        Ok(())
+    }
+
+    /// 按链上状态确认已导出的批次，已生效的标记同步完成并归档；
+    /// 查询失败的批次留待下次检查
+    async fn check_exported_onchain(&self) -> Result<()> {
+        let Some(config) = &self.export else {
+            return Ok(());
+        };
+        let mut exported = self.exported.lock().await;
+        let mut remaining = vec![];
+        for batch in exported.drain(..) {
+            match self.exported_onchain(&batch).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    remaining.push(batch);
+                    continue;
+                }
+                Err(err) => {
+                    eprintln!("查询多签批次 {} 的链上状态失败: {}", batch.id, err);
+                    remaining.push(batch);
+                    continue;
+                }
+            }
+            if let Err(err) = self.settle_exported(config, &batch).await {
+                eprintln!("多签批次 {} 标记完成失败: {}", batch.id, err);
+                remaining.push(batch);
+                continue;
+            }
+            println!("多签批次 {} 已在链上生效", batch.id);
+        }
+        *exported = remaining;
+        Ok(())
+    }
+
+    // 批次中的地址是否都已在链上拉黑；不记录地址的批次（如移除）只能通过调用树确认
+    async fn exported_onchain(&self, batch: &ExportedBatch) -> Result<bool> {
+        let provider = self.provider.as_ref();
+        if let Some(scope) = &batch.scope {
+            let (function_mode, _) =
+                black_list_mode(provider, self.auth_module, scope.project).await?;
+            let onchain =
+                onchain_function_blacklist(provider, self.auth_module, function_mode, [*scope])
+                    .await?;
+            return Ok(!onchain.is_empty());
+        }
+        if batch.addresses.is_empty() {
+            return Ok(false);
+        }
+        for address in &batch.addresses {
+            let listed = if self.tokens.contains(&batch.project) {
+                is_blacklisted(provider, batch.project, *address).await?
+            } else {
+                is_globally_blacklisted(provider, self.auth_module, batch.project, *address).await?
+            };
+            if !listed {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // 链上已生效但不知道执行交易时标记同步完成并归档
+    async fn settle_exported(&self, config: &ExportConfig, batch: &ExportedBatch) -> Result<()> {
+        mark_in_sync(self.store.as_ref(), batch.project, &batch.addresses).await?;
+        if let Some(scope) = &batch.scope {
+            self.store.mark_scope_synced(scope).await?;
+        }
+        archive_batch(config, batch)
+    }
+
+    /// 在区块调用树中查找已导出批次的执行交易，确认项目已开启全局模式后标记同步完成并归档
+    async fn observe_exported(&self, traces: &[TraceResult]) -> Result<()> {
+        let Some(config) = &self.export else {
+            return Ok(());
+        };
+        let mut exported = self.exported.lock().await;
+        let mut remaining = vec![];
+        let mut requeue = vec![];
+        for batch in exported.drain(..) {
+            let Some(tx_hash) = batch.executed_in(traces) else {
+                remaining.push(batch);
+                continue;
+            };
+            if !self.takes_effect(batch.project, &batch.addresses).await? {
+                // 多签执行了但地址未生效，回退为待同步并重新入队
+                mark_failed(self.store.as_ref(), batch.project, &batch.addresses).await?;
+                eprintln!(
+                    "多签批次 {} 已执行，但项目 {} 未开启全局模式，地址重新加入待同步队列",
+                    batch.id, batch.project
+                );
+                archive_batch(config, &batch)?;
+                requeue.push((batch.project, batch.addresses));
+                continue;
+            }
+            mark_synced(
+                self.store.as_ref(),
+                batch.project,
+                &batch.addresses,
+                tx_hash,
+            )
+            .await?;
+            if let Some(scope) = &batch.scope {
+                self.store.mark_scope_synced(scope).await?;
+            }
+            archive_batch(config, &batch)?;
+            println!("多签批次 {} 已在交易 {:?} 中执行", batch.id, tx_hash);
+        }
+        *exported = remaining;
+        drop(exported);
+
+        let mut black_list = self.black_list.lock().await;
+        for (project, addresses) in requeue {
+            let queue = black_list.entry(project).or_default();
+            for address in addresses {
+                queue.push(address, false);
+            }
+        }
+        Ok(())
+    }
+
+    /// 对比数据库有效记录与各项目的链上黑名单，`apply` 为 true 时发送修复交易。
+    /// 全局拦截只对接收全局拦截的项目对账，函数级拦截对所有推送的项目对账
+    pub async fn reconcile(&self, from_block: u64, apply: bool) -> Result<Vec<ReconcilePlan>> {
+        self.refresh_projects().await?;
+        let active: HashSet<Address> = self
+            .store
+            .list_active_addresses()
+            .await?
+            .iter()
+            .filter_map(|addr| addr.parse().ok())
+            .collect();
+        let scopes: Vec<FunctionScope> = self
+            .store
+            .list_all_scopes()
+            .await?
+            .into_iter()
+            .map(|(scope, _)| scope)
+            .collect();
+        // 函数级拦截的地址不参与全局对账，否则会被 batchSetInfo 推送为全局拦截
+        let scoped: HashSet<Address> = scopes.iter().map(|scope| scope.address).collect();
+        let db: HashSet<Address> = active.difference(&scoped).copied().collect();
+
+        let mut plans = vec![];
+        for (project, mode) in self.targets().await {
+            let events = index_blacklist_events(
+                self.provider.as_ref(),
+                self.auth_module,
+                project,
+                from_block,
+            )
+            .await?;
+            let (function_mode, _) =
+                black_list_mode(self.provider.as_ref(), self.auth_module, project).await?;
+            let known: Vec<FunctionScope> = scopes
+                .iter()
+                .filter(|scope| scope.project == project)
+                .copied()
+                .collect();
+
+            let mut plan = ReconcilePlan {
+                project,
+                ..Default::default()
+            };
+            if mode == ProjectMode::All {
+                // 项目未开启函数模式时本项目的函数级拦截写在全局黑名单中，交给函数级对账
+                let own: HashSet<Address> = known.iter().map(|scope| scope.address).collect();
+                let candidates = events
+                    .iter()
+                    .chain(&db)
+                    .filter(|addr| !own.contains(*addr))
+                    .copied()
+                    .collect::<HashSet<_>>();
+                let onchain = onchain_blacklist(
+                    self.provider.as_ref(),
+                    self.auth_module,
+                    project,
+                    candidates,
+                )
+                .await?;
+                plan = ReconcilePlan::diff(project, &db, &onchain);
+            }
+            let wanted: HashSet<FunctionScope> = known
+                .iter()
+                .filter(|scope| active.contains(&scope.address))
+                .copied()
+                .collect();
+            let onchain = onchain_function_blacklist(
+                self.provider.as_ref(),
+                self.auth_module,
+                function_mode,
+                scope_candidates(project, &events, &known),
+            )
+            .await?;
+            let plan = plan.with_scopes(&wanted, &onchain);
+
+            if apply {
+                if !plan.to_add.is_empty() {
+                    if self.mode == RunMode::Production {
+                        self.store.mark_pending(project, &plan.to_add).await?;
+                    }
+                    self.sync_chunked(project, &plan.to_add).await?;
+                }
+                for scope in &plan.scopes_to_add {
+                    self.sync_function_scope(scope).await?;
+                }
+                for call in plan.calls() {
+                    if let AuthModuleCall::RemoveInfo(_) = call {
+                        self.submit(&call, project, &[]).await?;
+                    }
+                }
+            }
+            plans.push(plan);
+        }
+
+        // 代币只对账选中原因的记录
+        if !self.tokens.tokens.is_empty() {
+            let mut selected: HashSet<Address> = HashSet::new();
+            let mut query = BlacklistQuery::new()
+                .status(EntryStatus::Active)
+                .limit(MAX_PAGE_SIZE);
+            loop {
+                let page = self.store.query(&query).await?;
+                selected.extend(
+                    page.entries
+                        .iter()
+                        .filter(|entry| self.tokens.selects(&entry.reason))
+                        .filter_map(|entry| entry.address.parse::<Address>().ok()),
+                );
+                match page.next {
+                    Some(cursor) => query = query.after(cursor),
+                    None => break,
+                }
+            }
+            for token in &self.tokens.tokens {
+                let onchain =
+                    onchain_token_blacklist(self.provider.as_ref(), *token, from_block, &selected)
+                        .await?;
+                let plan = ReconcilePlan::diff(*token, &selected, &onchain);
+                if apply {
+                    if !plan.to_add.is_empty() {
+                        if self.mode == RunMode::Production {
+                            self.store.mark_pending(*token, &plan.to_add).await?;
+                        }
+                        self.sync_token(*token, &plan.to_add).await?;
+                    }
+                    for address in &plan.to_remove {
+                        self.remove_from_token(*token, *address).await?;
+                    }
+                }
+                plans.push(plan);
+            }
+        }
+        Ok(plans)
+    }
+
+    // 发送更新交易并跟踪回执，超时后以相同 nonce 提高手续费重发；gas 为预检时的估算
+    async fn send_and_track(
+        &self,
+        project: Address,
+        batch: &[Address],
+        mut tx: TransactionRequest,
+        gas: u64,
+    ) -> Result<()> {
+        self.fee_config
+            .apply(self.provider.as_ref(), &mut tx, gas)
+            .await?;
+        let nonce = self.nonce_manager.reserve(self.provider.as_ref()).await?;
+        tx.set_nonce(nonce);
+
+        let mut update = UpdateTx {
+            updater: self,
+            project,
+            batch,
+            tx,
+            nonce,
+        };
+        let receipt = match track_receipt(&mut update, self.sync_config.max_retries).await {
+            Ok(receipt) => receipt,
+            Err(err) => {
+                mark_failed(self.store.as_ref(), project, batch).await?;
+                return Err(err);
+            }
+        };
+
+        let Some(receipt) = receipt else {
+            mark_failed(self.store.as_ref(), project, batch).await?;
+            return Err(anyhow!(
+                "更新交易重试 {} 次后仍未确认",
+                self.sync_config.max_retries
+            ));
+        };
+        if !receipt.status() {
+            mark_failed(self.store.as_ref(), project, batch).await?;
+            return Err(anyhow!("更新交易 {} 执行失败", receipt.transaction_hash));
+        }
+        if !self.takes_effect(project, batch).await? {
+            mark_failed(self.store.as_ref(), project, batch).await?;
+            return Err(anyhow!(
+                "更新交易 {} 已确认，但项目 {} 未开启全局模式，地址未生效",
+                receipt.transaction_hash,
+                project
+            ));
+        }
+        mark_synced(
+            self.store.as_ref(),
+            project,
+            batch,
+            receipt.transaction_hash,
+        )
+        .await?;
+        println!("更新交易已确认: {:?}", receipt.transaction_hash);
+        Ok(())
+    }
+
+    // 已确认的交易是否让 batch 在目标上生效：项目只有开启全局模式后黑名单才会拦截，
+    // 代币与不记录地址的调用无需检查
+    async fn takes_effect(&self, target: Address, batch: &[Address]) -> Result<bool> {
+        if batch.is_empty() || self.tokens.contains(&target) {
+            return Ok(true);
+        }
+        let (_, global_mode) =
+            black_list_mode(self.provider.as_ref(), self.auth_module, target).await?;
+        Ok(global_mode)
+    }
+
+    // 依次查询已发送交易的回执
+    async fn find_receipt(&self, hashes: &[TxHash]) -> Result<Option<TransactionReceipt>> {
+        for hash in hashes {
+            if let Some(receipt) = self.provider.get_transaction_receipt(*hash).await? {
+                return Ok(Some(receipt));
+            }
+        }
+        Ok(None)
+    }
+
+    // 重启后恢复未完成的同步：已上链的直接标记完成，其余重新加入待同步队列
+    async fn resume_unsynced(&self) -> Result<()> {
+        let mut black_list = self.black_list.lock().await;
+        for unsynced in self.store.load_unsynced().await? {
+            // 已导出的地址等待多签执行，不重复导出
+            if unsynced.status == SyncStatus::Exported && self.export.is_some() {
+                continue;
+            }
+            if let (SyncStatus::Submitted, Some(tx_hash)) = (unsynced.status, unsynced.tx_hash) {
+                if let Some(receipt) = self.find_receipt(&[tx_hash]).await? {
+                    if receipt.status()
+                        && self
+                            .takes_effect(unsynced.project, &[unsynced.address])
+                            .await?
+                    {
+                        mark_synced(
+                            self.store.as_ref(),
+                            unsynced.project,
+                            &[unsynced.address],
+                            tx_hash,
+                        )
+                        .await?;
+                        continue;
+                    }
+                }
+            }
+            black_list
+                .entry(unsynced.project)
+                .or_default()
+                .push(unsynced.address, false);
+        }
+        let restored: usize = black_list.values().map(|queue| queue.len()).sum();
+        if restored > 0 {
+            println!("恢复 {} 个未同步的地址", restored);
+        }
+
+        let exported = self.exported.lock().await;
+        let scopes: Vec<_> = self
+            .store
+            .load_unsynced_scopes()
+            .await?
+            .into_iter()
+            .filter(|scope| !exported.iter().any(|batch| batch.scope == Some(*scope)))
+            .collect();
+        if !scopes.is_empty() {
+            println!("恢复 {} 个未同步的函数级黑名单", scopes.len());
+        }
+        self.function_list.lock().await.extend(scopes);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SdnSync for BlackListUpdater {
+    async fn listed(&self, address: Address, reason: &str) -> Result<()> {
+        self.refresh_projects().await?;
+        self.add_on_chain(address, reason, true).await;
+        self.flush_address(address).await
+    }
+
+    async fn revoke(&self, entry: &BlacklistEntry, context: &ChangeContext) -> Result<()> {
+        self.update_status(entry, EntryStatus::Revoked, context)
+            .await
+            .map(|_| ())
+    }
+}
+
+/// 检测结果写入数据库后的分类
+#[derive(Debug, Default)]
+pub struct RecordedFindings {
+    /// 新写入或重新激活的地址及原因
+    pub inserted: Vec<(Address, String)>,
+    /// 处于人工处理中或在白名单中、不应推送上链的地址
+    pub held: HashSet<Address>,
 }
 
-fn detect(call_trace_vec: Vec<TraceResult>) -> Result<Vec<Address>> {
-    let mut black_list = vec![];
+/// 将检测得到的黑名单地址及其证据写入数据库，原因由证据生成，有效期按严重程度计算；
+/// 已存在的地址合并原因并追加证据
+pub async fn record_findings(
+    store: &dyn BlacklistStore,
+    policy: &ExpiryPolicy,
+    evidence: &[Evidence],
+) -> RecordedFindings {
+    let mut recorded = RecordedFindings::default();
+    for evidence in evidence {
+        let address = &evidence.attacker;
+        let reason = evidence.reason();
+        let entry = NewBlacklistEntry {
+            address: *address,
+            reason: reason.clone(),
+            status: EntryStatus::Active,
+            expires_at: policy.expires_for(&evidence.detector, evidence.severity, Utc::now()),
+            chain_type: evidence.chain_type.clone(),
+            created_by: None,
+        };
+
+        // 唯一键保证并发写入时只有一条记录，已存在的地址合并原因
+        let context = ChangeContext::new(ChangeSource::Detector).with_actor(&evidence.detector);
+        match store.upsert(&entry, &context).await {
+            Ok(UpsertOutcome::Inserted | UpsertOutcome::Reactivated) => {
+                recorded.inserted.push((*address, reason))
+            }
+            Ok(UpsertOutcome::Merged) => eprintln!(
+                "Address {} is already in the blacklist. Merged reason: {}",
+                address, reason
+            ),
+            Ok(UpsertOutcome::Held) => {
+                recorded.held.insert(*address);
+            }
+            Ok(UpsertOutcome::Allowlisted) => {
+                eprintln!(
+                    "Suppressed {} finding for allowlisted address {}",
+                    evidence.detector, address
+                );
+                if let Err(err) = store.record_suppressed(address).await {
+                    eprintln!(
+                        "Failed to count suppressed finding for {}: {}",
+                        address, err
+                    );
+                }
+                recorded.held.insert(*address);
+                continue;
+            }
+            Err(err) => {
+                eprintln!(
+                    "Failed to insert blacklist entry for address {}: {}",
+                    address, err
+                );
+                continue;
+            }
+        }
+        if let Err(err) = store.add_evidence(evidence).await {
+            eprintln!("Failed to insert evidence for address {}: {}", address, err);
+        }
+    }
+    recorded
+}
+
+/// 检测调用树中的重入，返回攻击地址以及被重入的合约与函数
+pub fn detect(call_trace_vec: Vec<TraceResult>) -> Result<Vec<Finding>> {
+    let mut findings = vec![];
     for tx_call_trace in call_trace_vec {
-        match tx_call_trace {
-            TraceResult::Success { result, tx_hash } => {
-                println!("{:?}", tx_hash);
-                let calltrace = result.try_into_call_frame()?;
-                let to = calltrace.from.clone();
-                if is_reentrancy(calltrace) {
-                    black_list.push(to);
+        if let TraceResult::Success { result, tx_hash } = tx_call_trace {
+            let calltrace = result.try_into_call_frame()?;
+            if let Some((target, selector, mut call_path)) = find_reentrancy(&calltrace) {
+                if call_path.len() > MAX_CALL_PATH {
+                    call_path.drain(..call_path.len() - MAX_CALL_PATH);
                 }
+                findings.push(Finding {
+                    address: calltrace.from,
+                    target,
+                    selector,
+                    tx_hash,
+                    detector: REENTRANCY_DETECTOR,
+                    loss: value_sent(&calltrace, target),
+                    call_path,
+                });
             }
-            _ => {}
         }
     }
-    Ok(black_list)
+    Ok(findings)
 }
 
-fn is_reentrancy(calltrace: CallFrame) -> bool {
+type Reentrancy = (Address, Option<FixedBytes<4>>, Vec<String>);
+
+fn find_reentrancy(calltrace: &CallFrame) -> Option<Reentrancy> {
     // dfs calltrace
     let mut msg_list = HashSet::<String>::new();
-    return _dfs(calltrace, &mut msg_list);
+    let mut path = vec![];
+    let (to, selector) = _dfs(calltrace, &mut msg_list, &mut path)?;
+    Some((to, selector, path))
 }
 
-fn _dfs(calltrace: CallFrame, msg_list: &mut HashSet<String>) -> bool {
+// 合约在调用树中转出的原生代币总额
+fn value_sent(calltrace: &CallFrame, from: Address) -> U256 {
+    let own = match calltrace.value {
+        Some(value) if calltrace.from == from => value,
+        _ => U256::ZERO,
+    };
+    calltrace.calls.iter().fold(own, |total, call| {
+        total.saturating_add(value_sent(call, from))
+    })
+}
+
+// 返回第一次被重复进入的合约及函数，path 记录从入口到重入点的调用
+fn _dfs(
+    calltrace: &CallFrame,
+    msg_list: &mut HashSet<String>,
+    path: &mut Vec<String>,
+) -> Option<(Address, Option<FixedBytes<4>>)> {
     // 初步过滤
     if calltrace.typ.eq("STATICCALL") || calltrace.to.is_none() {
-        return false;
+        return None;
     }
+    let to = calltrace.to.unwrap();
 
-    let msg = if calltrace.input.len() >= 10 {
-        calltrace.to.unwrap().to_string() + "|" + calltrace.input.to_string().split_at(10).0
-    } else if calltrace.input.len() == 0 {
-        calltrace.to.unwrap().to_string() + "|fallback"
+    let (msg, selector) = if calltrace.input.len() >= 10 {
+        (
+            to.to_string() + "|" + calltrace.input.to_string().split_at(10).0,
+            Some(FixedBytes::from_slice(&calltrace.input[..4])),
+        )
+    } else if calltrace.input.is_empty() {
+        (to.to_string() + "|fallback", None)
     } else {
-        "".to_string()
+        ("".to_string(), None)
     };
 
     // println!("MSG:{:?}", msg);
 
     // 构造msg = address | selector
     // 无法插入，说明已经存在调用
+    path.push(msg.clone());
     if !msg_list.insert(msg) {
-        return true;
+        return Some((to, selector));
     }
     // 有子调用，继续处理
-    if !calltrace.calls.is_empty() {
-        for call_trace in calltrace.calls {
-            if _dfs(call_trace, msg_list) {
-                return true;
+    for call_trace in &calltrace.calls {
+        if let Some(found) = _dfs(call_trace, msg_list, path) {
+            return Some(found);
+        }
+    }
+    path.pop();
+    None
+}
+
+/// 一笔更新交易的发送与回执跟踪，重发时沿用同一个 nonce
+struct UpdateTx<'a> {
+    updater: &'a BlackListUpdater,
+    project: Address,
+    batch: &'a [Address],
+    tx: TransactionRequest,
+    nonce: u64,
+}
+
+#[async_trait]
+impl ReceiptTracker for UpdateTx<'_> {
+    type Receipt = TransactionReceipt;
+
+    async fn send(&mut self, attempt: u32) -> Result<TxHash> {
+        println!("等待发送交易");
+        let updater = self.updater;
+        let pending_tx = match updater.provider.send_transaction(self.tx.clone()).await {
+            Ok(pending_tx) => pending_tx,
+            Err(err) => {
+                // 首次发送失败说明该 nonce 未被占用，归还给 nonce 管理器
+                if attempt == 0 {
+                    updater.nonce_manager.release(self.nonce).await?;
+                }
+                return Err(err.into());
+            }
+        };
+        let tx_hash = *pending_tx.tx_hash();
+        mark_submitted(
+            updater.store.as_ref(),
+            self.project,
+            self.batch,
+            tx_hash,
+            self.nonce,
+        )
+        .await?;
+        println!("更新交易已发送: {:?} (第 {} 次)", tx_hash, attempt + 1);
+        Ok(tx_hash)
+    }
+
+    async fn wait(
+        &mut self,
+        tx_hash: TxHash,
+        sent: &[TxHash],
+    ) -> Result<Option<TransactionReceipt>> {
+        let updater = self.updater;
+        let result = PendingTransactionBuilder::new(updater.provider.root().clone(), tx_hash)
+            .with_required_confirmations(updater.sync_config.confirmations)
+            .with_timeout(Some(updater.sync_config.receipt_timeout))
+            .get_receipt()
+            .await;
+        match result {
+            Ok(receipt) => Ok(Some(receipt)),
+            // 被替换掉的旧交易也可能已经上链
+            Err(PendingTransactionError::TxWatcher(WatchTxError::Timeout)) => {
+                updater.find_receipt(sent).await
             }
+            Err(err) => Err(err.into()),
         }
     }
-    return false;
+
+    async fn bump(&mut self) -> Result<()> {
+        self.updater
+            .fee_config
+            .bump(self.updater.provider.as_ref(), &mut self.tx)
+            .await?;
+        eprintln!(
+            "更新交易等待超时，提高手续费后重发: max_fee={:?} priority_fee={:?}",
+            self.tx.max_fee_per_gas, self.tx.max_priority_fee_per_gas
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,159 @@
+use alloy::primitives::Address;
+use anyhow::Result;
+use std::{env, future::Future, time::Duration};
+use tokio::time::Instant;
+
+use crate::auth::{evidence::Severity, sync::env_or};
+
+/// 默认达到该严重程度的检测结果立即上链
+const DEFAULT_FLUSH_SEVERITY: Severity = Severity::High;
+
+/// 链上更新的批处理策略
+#[derive(Debug, Clone)]
+pub struct BatchPolicy {
+    /// 单批最多包含的地址数
+    pub max_batch_size: usize,
+    /// 第一个待同步地址最多等待的时间
+    pub max_latency: Duration,
+    /// 单次调用的 gas 上限，超过时拆分批次
+    pub gas_budget: u64,
+    /// 达到该严重程度的检测结果立即上链，为 None 时不立即上链
+    pub flush_severity: Option<Severity>,
+}
+
+impl BatchPolicy {
+    /// `BATCH_FLUSH_SEVERITY` 为立即上链的最低严重程度，设置为空时关闭立即上链
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            max_batch_size: env_or("BATCH_MAX_SIZE", 20usize).max(1),
+            max_latency: Duration::from_secs(env_or("BATCH_MAX_LATENCY_SECS", 30)),
+            gas_budget: env_or("BATCH_GAS_BUDGET", 3_000_000),
+            flush_severity: Self::parse_flush_severity(
+                env::var("BATCH_FLUSH_SEVERITY").ok().as_deref(),
+            )?,
+        })
+    }
+
+    /// 未设置时使用默认值，设置为空时返回 None，无法识别的严重程度返回错误
+    pub fn parse_flush_severity(value: Option<&str>) -> Result<Option<Severity>> {
+        match value.map(str::trim) {
+            None => Ok(Some(DEFAULT_FLUSH_SEVERITY)),
+            Some("") => Ok(None),
+            Some(severity) => Ok(Some(severity.parse()?)),
+        }
+    }
+
+    pub fn is_urgent(&self, severity: Severity) -> bool {
+        self.flush_severity
+            .is_some_and(|threshold| severity >= threshold)
+    }
+
+    /// 队列中有紧急地址、达到批次大小或等待超时时发送
+    pub fn should_flush(&self, queue: &PendingQueue, now: Instant) -> bool {
+        if queue.is_empty() {
+            return false;
+        }
+        queue.len() >= self.max_batch_size
+            || queue.has_urgent()
+            || queue
+                .oldest()
+                .is_some_and(|since| now.duration_since(since) >= self.max_latency)
+    }
+
+    /// 按批次大小切分待发送的地址
+    pub fn chunks(&self, addresses: &[Address]) -> Vec<Vec<Address>> {
+        addresses
+            .chunks(self.max_batch_size)
+            .map(|chunk| chunk.to_vec())
+            .collect()
+    }
+
+    /// 估算的 gas 超过预算时对半拆分，直到每批都在预算内；
+    /// 单个地址仍超出预算时不发送，放入 `oversized`
+    pub async fn split_by_gas<F, Fut>(
+        &self,
+        chunk: Vec<Address>,
+        mut estimate: F,
+    ) -> Result<GasSplit>
+    where
+        F: FnMut(Vec<Address>) -> Fut,
+        Fut: Future<Output = Result<u64>>,
+    {
+        let mut split = GasSplit::default();
+        let mut stack = vec![chunk];
+        while let Some(batch) = stack.pop() {
+            let gas = estimate(batch.clone()).await?;
+            if gas <= self.gas_budget {
+                split.batches.push((batch, gas));
+            } else if batch.len() == 1 {
+                split.oversized.push((batch[0], gas));
+            } else {
+                let (left, right) = batch.split_at(batch.len() / 2);
+                // 先处理前半部分，保持入队顺序
+                stack.push(right.to_vec());
+                stack.push(left.to_vec());
+            }
+        }
+        Ok(split)
+    }
+}
+
+/// 按 gas 预算拆分的结果
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GasSplit {
+    /// 保持原顺序的各批地址及其 gas 估算
+    pub batches: Vec<(Vec<Address>, u64)>,
+    /// 单独发送也超出预算的地址及其 gas 估算
+    pub oversized: Vec<(Address, u64)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PendingAddress {
+    address: Address,
+    since: Instant,
+    urgent: bool,
+}
+
+/// 等待上链的地址队列，记录每个地址的入队时间
+#[derive(Debug, Default)]
+pub struct PendingQueue {
+    pending: Vec<PendingAddress>,
+}
+
+impl PendingQueue {
+    /// 地址已在队列中时只更新紧急标记
+    pub fn push(&mut self, address: Address, urgent: bool) {
+        match self.pending.iter_mut().find(|p| p.address == address) {
+            Some(pending) => pending.urgent |= urgent,
+            None => self.pending.push(PendingAddress {
+                address,
+                since: Instant::now(),
+                urgent,
+            }),
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&Address) -> bool) {
+        self.pending.retain(|p| keep(&p.address));
+    }
+
+    pub fn addresses(&self) -> Vec<Address> {
+        self.pending.iter().map(|p| p.address).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    fn has_urgent(&self) -> bool {
+        self.pending.iter().any(|p| p.urgent)
+    }
+
+    fn oldest(&self) -> Option<Instant> {
+        self.pending.iter().map(|p| p.since).min()
+    }
+}
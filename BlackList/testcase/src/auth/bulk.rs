@@ -0,0 +1,501 @@
+use alloy::primitives::Address;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::auth::{
+    data::{canonical_address, BlacklistEntry, EntryStatus, NewBlacklistEntry, UpsertOutcome},
+    evidence::EvidenceRecord,
+    expiry::ExpiryPolicy,
+    history::ChangeContext,
+    query::{BlacklistQuery, MAX_PAGE_SIZE},
+    store::BlacklistStore,
+};
+
+/// 导入导出的文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    /// 一个 JSON 数组
+    Json,
+    /// 每行一个 JSON 对象
+    Jsonl,
+}
+
+impl Format {
+    /// 按扩展名推断格式
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .ok_or_else(|| anyhow!("无法从 {} 推断文件格式", path.display()))?;
+        extension.to_lowercase().parse()
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            _ => Err(anyhow!("不支持的文件格式: {}", s)),
+        }
+    }
+}
+
+/// 文件中的一条记录，三种格式使用相同的字段。
+/// 导入时只读取地址、链、原因、状态、到期时间与创建人，其余字段由数据库生成
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BlacklistRecord {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub address: String,
+    #[serde(default)]
+    pub chain_type: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub added_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub status_changed_at: Option<DateTime<Utc>>,
+    /// 证据引用，每一项为 `检测器:交易哈希`，以 `;` 分隔。
+    /// 证据依赖区块数据，导入时不重建
+    #[serde(default)]
+    pub evidence: Option<String>,
+}
+
+impl BlacklistRecord {
+    pub fn from_entry(entry: &BlacklistEntry, evidence: &[EvidenceRecord]) -> Self {
+        let references: Vec<String> = evidence
+            .iter()
+            .filter_map(|record| {
+                record
+                    .tx_hash
+                    .as_ref()
+                    .map(|tx_hash| format!("{}:{}", record.detector, tx_hash))
+            })
+            .collect();
+        Self {
+            id: Some(entry.id),
+            address: entry.address.clone(),
+            chain_type: Some(entry.chain_type.clone()),
+            reason: Some(entry.reason.clone()),
+            status: Some(entry.status.clone()),
+            expires_at: entry.expires_at,
+            created_by: entry.created_by.clone(),
+            added_at: Some(entry.added_at),
+            updated_at: Some(entry.updated_at),
+            status_changed_at: entry.status_changed_at,
+            evidence: (!references.is_empty()).then(|| references.join(";")),
+        }
+    }
+}
+
+/// 导入时对缺省字段的处理
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// 只校验并统计，不写入数据库
+    pub dry_run: bool,
+    pub default_chain: String,
+    /// 记录没有原因时使用
+    pub default_reason: String,
+    /// 记录没有状态时使用
+    pub default_status: EntryStatus,
+    /// 记录没有到期时间时的有效期，未设置时按原因使用到期策略
+    pub default_ttl: Option<Duration>,
+    pub created_by: Option<String>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            default_chain: "ETH".to_string(),
+            default_reason: "Imported".to_string(),
+            default_status: EntryStatus::Active,
+            default_ttl: None,
+            created_by: None,
+        }
+    }
+}
+
+/// 无法导入的记录，`line` 为 CSV、JSONL 的行号或 JSON 数组中的序号
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// 导入结果统计，试运行时按数据库现状预估
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub total: usize,
+    pub inserted: usize,
+    /// 地址已存在，合并了原因与到期时间
+    pub merged: usize,
+    /// 文件内重复的地址，只导入第一条
+    pub duplicates: usize,
+    /// 处于人工处理中或在白名单中而未生效的地址
+    pub held: usize,
+    pub errors: Vec<ImportError>,
+}
+
+/// 逐条读取并导入黑名单，格式错误或字段无效的记录计入报告，不中断导入。
+/// 没有到期时间的记录按 `policy` 计算
+pub async fn import<R: Read>(
+    store: &dyn BlacklistStore,
+    reader: R,
+    format: Format,
+    options: &ImportOptions,
+    policy: &ExpiryPolicy,
+    context: &ChangeContext,
+) -> Result<ImportReport> {
+    let allowlisted: HashSet<(String, String)> = store
+        .list_allowlist()
+        .await?
+        .into_iter()
+        .map(|entry| (entry.chain_type, entry.address))
+        .collect();
+    let mut report = ImportReport::default();
+    let mut seen = HashSet::new();
+    for (line, record) in read_records(reader, format)? {
+        report.total += 1;
+        let entry = match record.and_then(|record| to_entry(record, options, policy)) {
+            Ok(entry) => entry,
+            Err(err) => {
+                report.errors.push(ImportError {
+                    line,
+                    message: err.to_string(),
+                });
+                continue;
+            }
+        };
+        let key = (entry.chain_type.clone(), canonical_address(&entry.address));
+        if !seen.insert(key.clone()) {
+            report.duplicates += 1;
+            continue;
+        }
+
+        if allowlisted.contains(&key) {
+            if options.dry_run {
+                report.held += 1;
+                continue;
+            }
+        } else if let Some(existing) = store
+            .find_in_chain(&entry.chain_type, &entry.address)
+            .await?
+        {
+            let existing: EntryStatus = existing.status.parse()?;
+            let merged = match merged_status(existing, entry.status) {
+                Ok(merged) => merged,
+                Err(err) => {
+                    report.errors.push(ImportError {
+                        line,
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            // 按 upsert 的合并规则预估结果
+            if options.dry_run {
+                match (existing.is_listed(), merged.is_listed()) {
+                    (_, false) => report.held += 1,
+                    (false, true) => report.inserted += 1,
+                    (true, true) => report.merged += 1,
+                }
+                continue;
+            }
+        }
+        if options.dry_run {
+            report.inserted += 1;
+            continue;
+        }
+        match store.upsert(&entry, context).await {
+            Ok(UpsertOutcome::Inserted | UpsertOutcome::Reactivated) => report.inserted += 1,
+            Ok(UpsertOutcome::Merged) => report.merged += 1,
+            Ok(UpsertOutcome::Held | UpsertOutcome::Allowlisted) => report.held += 1,
+            Err(err) => report.errors.push(ImportError {
+                line,
+                message: err.to_string(),
+            }),
+        }
+    }
+    Ok(report)
+}
+
+/// 导出满足条件的全部记录及其证据引用，按页读取，返回导出条数
+pub async fn export<W: Write>(
+    store: &dyn BlacklistStore,
+    writer: W,
+    format: Format,
+    query: &BlacklistQuery,
+) -> Result<usize> {
+    let mut writer = RecordWriter::new(writer, format)?;
+    let mut query = query.clone().limit(MAX_PAGE_SIZE);
+    query.after = None;
+    loop {
+        let page = store.query(&query).await?;
+        for entry in &page.entries {
+            let evidence = store.list_evidence(entry.id).await?;
+            writer.write(&BlacklistRecord::from_entry(entry, &evidence))?;
+        }
+        match page.next {
+            Some(cursor) => query = query.after(cursor),
+            None => break,
+        }
+    }
+    writer.finish()
+}
+
+// 按格式逐条解析记录，附带行号。三种格式都边读边解析，不把整个文件读入内存
+fn read_records<'a, R: Read + 'a>(
+    reader: R,
+    format: Format,
+) -> Result<Box<dyn Iterator<Item = (usize, Result<BlacklistRecord>)> + 'a>> {
+    Ok(match format {
+        Format::Csv => Box::new(
+            csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(reader)
+                .into_deserialize()
+                .enumerate()
+                // 第一行为表头
+                .map(|(index, record)| (index + 2, record.map_err(anyhow::Error::from))),
+        ),
+        Format::Json => Box::new(JsonArray::new(reader)),
+        Format::Jsonl => Box::new(
+            BufReader::new(reader)
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+                .map(|(index, line)| {
+                    let record = line
+                        .map_err(anyhow::Error::from)
+                        .and_then(|line| serde_json::from_str(&line).map_err(Into::into));
+                    (index + 1, record)
+                }),
+        ),
+    })
+}
+
+// 逐个读取 JSON 数组的元素。元素先解析为 JSON 值再转换为记录，
+// 字段无效只影响该条；数组本身格式错误时无法定位下一条，报告后停止
+struct JsonArray<R: Read> {
+    reader: BufReader<R>,
+    index: usize,
+    started: bool,
+    done: bool,
+}
+
+impl<R: Read> JsonArray<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            index: 0,
+            started: false,
+            done: false,
+        }
+    }
+
+    // 跳过空白并返回下一个字节，不消费该字节
+    fn peek(&mut self) -> Result<Option<u8>> {
+        loop {
+            let buf = self.reader.fill_buf()?;
+            match buf.iter().position(|byte| !byte.is_ascii_whitespace()) {
+                Some(position) => {
+                    let byte = buf[position];
+                    self.reader.consume(position);
+                    return Ok(Some(byte));
+                }
+                None if buf.is_empty() => return Ok(None),
+                None => {
+                    let len = buf.len();
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    // 读到下一个元素的开头，数组结束时返回 false
+    fn advance(&mut self) -> Result<bool> {
+        let expected = if self.started { b',' } else { b'[' };
+        match self.peek()? {
+            Some(b']') if self.started => return Ok(false),
+            Some(byte) if byte == expected => self.reader.consume(1),
+            Some(byte) => return Err(anyhow!("JSON 数组格式错误: 意外的字符 {:?}", byte as char)),
+            None => return Err(anyhow!("JSON 数组格式错误: 文件提前结束")),
+        }
+        if !self.started {
+            self.started = true;
+            if self.peek()? == Some(b']') {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn next_value(&mut self) -> Result<serde_json::Value> {
+        let mut deserializer = serde_json::Deserializer::from_reader(&mut self.reader);
+        Ok(serde_json::Value::deserialize(&mut deserializer)?)
+    }
+}
+
+impl<R: Read> Iterator for JsonArray<R> {
+    type Item = (usize, Result<BlacklistRecord>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        self.index += 1;
+        let value = match self.advance() {
+            Ok(true) => self.next_value(),
+            Ok(false) => {
+                self.done = true;
+                return None;
+            }
+            Err(err) => Err(err),
+        };
+        let record = match value {
+            Ok(value) => serde_json::from_value(value).map_err(anyhow::Error::from),
+            Err(err) => {
+                self.done = true;
+                Err(err)
+            }
+        };
+        Some((self.index, record))
+    }
+}
+
+// 导入已有地址时 upsert 合并后的状态，须符合状态转换表；
+// 导入不能让已上链的记录下架，下架需同时从链上移除，只能通过 status 命令
+fn merged_status(existing: EntryStatus, incoming: EntryStatus) -> Result<EntryStatus> {
+    let merged = existing.transition(existing.on_redetect().unwrap_or(incoming))?;
+    if existing.is_listed() && !merged.is_listed() {
+        return Err(anyhow!(
+            "{} 的记录不能通过导入改为 {}，请使用 status 命令",
+            existing,
+            merged
+        ));
+    }
+    Ok(merged)
+}
+
+// 校验记录并补全缺省字段
+fn to_entry(
+    record: BlacklistRecord,
+    options: &ImportOptions,
+    policy: &ExpiryPolicy,
+) -> Result<NewBlacklistEntry> {
+    let address: Address = record
+        .address
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("无效的地址: {}", record.address))?;
+    let reason = record
+        .reason
+        .filter(|reason| !reason.trim().is_empty())
+        .unwrap_or_else(|| options.default_reason.clone());
+    let status = match record.status.filter(|status| !status.trim().is_empty()) {
+        Some(status) => status.trim().parse()?,
+        None => options.default_status,
+    };
+    let now = Utc::now();
+    let expires_at = match (record.expires_at, options.default_ttl) {
+        (Some(expires_at), _) => Some(expires_at),
+        (None, Some(ttl)) => Some(now + ttl),
+        (None, None) => policy.expires_at(&reason, now),
+    };
+    if expires_at.is_some_and(|expires_at| expires_at <= now) && status.is_listed() {
+        return Err(anyhow!("到期时间 {:?} 已过", expires_at));
+    }
+    Ok(NewBlacklistEntry {
+        address,
+        reason,
+        status,
+        expires_at,
+        chain_type: record
+            .chain_type
+            .filter(|chain_type| !chain_type.trim().is_empty())
+            .unwrap_or_else(|| options.default_chain.clone()),
+        created_by: record.created_by.or_else(|| options.created_by.clone()),
+    })
+}
+
+// JSON 数组逐条写出，不在内存中拼接整个数组
+enum RecordWriter<W: Write> {
+    Csv(Box<csv::Writer<W>>, usize),
+    Json(W, usize),
+    Jsonl(W, usize),
+}
+
+impl<W: Write> RecordWriter<W> {
+    fn new(mut writer: W, format: Format) -> Result<Self> {
+        Ok(match format {
+            Format::Csv => Self::Csv(Box::new(csv::Writer::from_writer(writer)), 0),
+            Format::Json => {
+                writer.write_all(b"[")?;
+                Self::Json(writer, 0)
+            }
+            Format::Jsonl => Self::Jsonl(writer, 0),
+        })
+    }
+
+    fn write(&mut self, record: &BlacklistRecord) -> Result<()> {
+        match self {
+            Self::Csv(writer, count) => {
+                writer.serialize(record)?;
+                *count += 1;
+            }
+            Self::Json(writer, count) => {
+                if *count > 0 {
+                    writer.write_all(b",")?;
+                }
+                writer.write_all(b"\n")?;
+                serde_json::to_writer(&mut *writer, record)?;
+                *count += 1;
+            }
+            Self::Jsonl(writer, count) => {
+                serde_json::to_writer(&mut *writer, record)?;
+                writer.write_all(b"\n")?;
+                *count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<usize> {
+        match self {
+            Self::Csv(mut writer, count) => {
+                writer.flush()?;
+                Ok(count)
+            }
+            Self::Json(mut writer, count) => {
+                writer.write_all(b"\n]\n")?;
+                writer.flush()?;
+                Ok(count)
+            }
+            Self::Jsonl(mut writer, count) => {
+                writer.flush()?;
+                Ok(count)
+            }
+        }
+    }
+}
@@ -1,173 +1,189 @@
-use chrono::{DateTime, Duration, Utc};
-use dotenv::dotenv;
-use sqlx::{mysql::MySqlPoolOptions, FromRow, MySql, Pool};
-use std::env;
-
-#[derive(Debug, FromRow)]
-pub struct BlacklistEntry {
-    id: i64,
-    address: String,
-    reason: String,
-    added_at: DateTime<Utc>,
-    status: String,
-    expires_at: Option<DateTime<Utc>>,
-    chain_type: String,
-    created_by: Option<String>,
-    updated_at: DateTime<Utc>,
+use alloy::{hex::FromHexError, primitives::Address};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use std::{fmt, str::FromStr};
+
+/// 生产环境黑名单表
+pub const BLACKLIST_TABLE: &str = "blockchain_blacklist";
+/// 影子模式黑名单表，结构与生产表一致，用于试运行新的检测器
+pub const SHADOW_BLACKLIST_TABLE: &str = "blockchain_blacklist_shadow";
+
+/// 合并多个原因时的分隔符
+pub const REASON_SEPARATOR: &str = "; ";
+
+/// 地址统一以小写、带 0x 前缀的十六进制存储
+pub fn canonical_address(address: &Address) -> String {
+    format!("{:#x}", address)
 }
 
-pub async fn create_blacklist_entry(
-    pool: &Pool<MySql>,
-    address: &str,
-    reason: &str,
-    status: &str,
-    expires_at: Option<DateTime<Utc>>,
-    chain_type: &str,
-    created_by: Option<&str>,
-) -> Result<(), sqlx::Error> {
-    let query = r#"
-        INSERT INTO blockchain_blacklist (address, reason, status, expires_at, chain_type, created_by)
-        VALUES (?, ?, ?, ?, ?, ?)
-    "#;
-
-    sqlx::query(query)
-        .bind(address)
-        .bind(reason)
-        .bind(status)
-        .bind(expires_at)
-        .bind(chain_type)
-        .bind(created_by)
-        .execute(pool)
-        .await?;
-
-    Ok(())
+/// 校验外部输入的地址并转换为存储格式
+pub fn normalize_address(address: &str) -> Result<String, FromHexError> {
+    Ok(canonical_address(&address.trim().parse()?))
 }
 
-// 检查地址是否已经在黑名单中
-pub async fn address_exists_in_blacklist(
-    pool: &Pool<MySql>,
-    address: &str,
-) -> Result<bool, sqlx::Error> {
-    let query = r#"
-        SELECT COUNT(*) FROM blockchain_blacklist WHERE address = ?
-    "#;
-
-    let count: (i64,) = sqlx::query_as(query).bind(address).fetch_one(pool).await?;
-
-    Ok(count.0 > 0) // 如果有记录，返回 true
+/// 黑名单记录的生命周期状态，只有 active 的地址会推送上链
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryStatus {
+    /// 等待人工审核
+    PendingReview,
+    Active,
+    /// 暂时停止拦截，可恢复
+    Suspended,
+    /// 到期自动下架
+    Expired,
+    /// 确认误判，撤销
+    Revoked,
+    /// 地址所有者提出申诉，审核期间停止拦截
+    Appealed,
 }
 
-pub async fn delete_blacklist_entry(pool: &Pool<MySql>, id: i64) -> Result<(), sqlx::Error> {
-    let query = "DELETE FROM blockchain_blacklist WHERE id = ?";
+impl EntryStatus {
+    pub const ALL: [EntryStatus; 6] = [
+        Self::PendingReview,
+        Self::Active,
+        Self::Suspended,
+        Self::Expired,
+        Self::Revoked,
+        Self::Appealed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PendingReview => "pending_review",
+            Self::Active => "active",
+            Self::Suspended => "suspended",
+            Self::Expired => "expired",
+            Self::Revoked => "revoked",
+            Self::Appealed => "appealed",
+        }
+    }
 
-    sqlx::query(query).bind(id).execute(pool).await?;
+    /// 是否在链上拦截
+    pub fn is_listed(&self) -> bool {
+        *self == Self::Active
+    }
 
-    Ok(())
-}
+    /// 允许的状态转换，保持原状态视为允许
+    pub fn can_transition_to(&self, to: EntryStatus) -> bool {
+        use EntryStatus::*;
+        *self == to
+            || matches!(
+                (self, to),
+                (PendingReview, Active | Revoked)
+                    | (Active, Suspended | Expired | Revoked | Appealed)
+                    | (Suspended, Active | Expired | Revoked)
+                    | (Expired, Active | Revoked)
+                    | (Appealed, Active | Revoked)
+                    | (Revoked, PendingReview)
+            )
+    }
 
-pub async fn update_blacklist_entry(
-    pool: &Pool<MySql>,
-    id: i64,
-    status: &str,
-    expires_at: Option<DateTime<Utc>>,
-) -> Result<(), sqlx::Error> {
-    let query = r#"
-        UPDATE blockchain_blacklist
-        SET status = ?, expires_at = ?
-        WHERE id = ?
-    "#;
-
-    sqlx::query(query)
-        .bind(status)
-        .bind(expires_at)
-        .bind(id)
-        .execute(pool)
-        .await?;
-
-    Ok(())
-}
+    pub fn transition(&self, to: EntryStatus) -> Result<EntryStatus, InvalidTransition> {
+        if self.can_transition_to(to) {
+            Ok(to)
+        } else {
+            Err(InvalidTransition { from: *self, to })
+        }
+    }
 
-pub async fn get_blacklist_entry(
-    pool: &Pool<MySql>,
-    id: i64,
-) -> Result<Option<BlacklistEntry>, sqlx::Error> {
-    let query = r#"
-        SELECT id, address, reason, added_at, status, expires_at, chain_type, created_by, updated_at
-        FROM blockchain_blacklist WHERE id = ?
-    "#;
-
-    let entry = sqlx::query_as::<_, BlacklistEntry>(query)
-        .bind(id)
-        .fetch_optional(pool)
-        .await?;
-
-    Ok(entry)
+    /// 已有记录再次被检测到时的状态，None 表示采用新记录的状态。
+    /// 人工处理中的记录保持原状态，已撤销的记录重新进入审核
+    pub fn on_redetect(&self) -> Option<EntryStatus> {
+        match self {
+            Self::Active | Self::Expired => None,
+            Self::PendingReview | Self::Suspended | Self::Appealed => Some(*self),
+            Self::Revoked => Some(Self::PendingReview),
+        }
+    }
 }
 
-pub async fn list_blacklist(pool: &Pool<MySql>) -> Result<Vec<BlacklistEntry>, sqlx::Error> {
-    let query = r#"
-        SELECT id, address, reason, added_at, status, expires_at, chain_type, created_by, updated_at
-        FROM blockchain_blacklist
-    "#;
+impl fmt::Display for EntryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
-    let entries = sqlx::query_as::<_, BlacklistEntry>(query)
-        .fetch_all(pool)
-        .await?;
+impl FromStr for EntryStatus {
+    type Err = anyhow::Error;
 
-    Ok(entries)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| anyhow!("未知的黑名单状态: {}", s))
+    }
 }
 
-// 示例插入函数
-async fn insert_blacklist_entries(pool: &Pool<MySql>, addresses: Vec<String>) {
-    for address in addresses {
-        // 检查数据库中是否已存在该地址
-        if let Ok(exists) = address_exists_in_blacklist(&pool, &address).await {
-            if exists {
-                eprintln!(
-                    "Address {} is already in the blacklist. Skipping insertion.",
-                    address
-                );
-                continue; // 如果存在，则跳过插入
-            }
-        }
+/// 不允许的状态转换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidTransition {
+    pub from: EntryStatus,
+    pub to: EntryStatus,
+}
 
-        let reason = generate_random_reason();
-        let status = "active";
-        // 计算 expires_at 为当前时间后30天
-        let expires_at = Some(Utc::now() + Duration::days(30));
-        let chain_type = "ETH";
-        let created_by = None;
-
-        let result = create_blacklist_entry(
-            &pool, &address, &reason, status, expires_at, chain_type, created_by,
-        )
-        .await;
-
-        if let Err(err) = result {
-            eprintln!(
-                "Failed to insert blacklist entry for address {}: {}",
-                address, err
-            );
-        }
+impl fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "不允许从 {} 转换为 {}", self.from, self.to)
     }
 }
 
-// 假设有一个生成随机理由的函数
-fn generate_random_reason() -> String {
-    // 实现您的逻辑
-    "Violation of terms".to_string()
+impl std::error::Error for InvalidTransition {}
+
+/// upsert 合并时状态列的 SQL 表达式，`incoming` 为新记录状态的占位符
+pub fn merged_status_sql(incoming: &str) -> String {
+    let arms: String = EntryStatus::ALL
+        .iter()
+        .filter_map(|status| {
+            let to = status.on_redetect()?;
+            Some(format!(
+                "WHEN '{}' THEN '{}' ",
+                status.as_str(),
+                to.as_str()
+            ))
+        })
+        .collect();
+    format!("CASE status {arms}ELSE {incoming} END")
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    dotenv().ok();
-    let database_url = env::var("DATABASE_URL")?;
-    let pool = MySqlPoolOptions::new().connect(&database_url).await?;
-
-    // 示例地址列表
-    let addresses = vec!["0x123...".to_string(), "0x456...".to_string()];
+/// upsert 的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    /// 新增记录
+    Inserted,
+    /// 地址已存在，合并原因并刷新到期时间
+    Merged,
+    /// 地址已存在且不在 active 状态，合并后重新激活
+    Reactivated,
+    /// 地址已存在且处于人工处理中的状态，合并后仍不拦截
+    Held,
+    /// 地址在白名单中，未写入
+    Allowlisted,
+}
 
-    insert_blacklist_entries(&pool, addresses).await;
+#[derive(Debug, Serialize, FromRow)]
+pub struct BlacklistEntry {
+    pub id: i64,
+    pub address: String,
+    pub reason: String,
+    pub added_at: DateTime<Utc>,
+    pub status: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub chain_type: String,
+    pub created_by: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    /// 最近一次状态变化的时间，每次转换的时间见变更历史
+    pub status_changed_at: Option<DateTime<Utc>>,
+}
 
-    Ok(())
+/// 新增或合并的黑名单记录
+#[derive(Debug, Clone)]
+pub struct NewBlacklistEntry {
+    pub address: Address,
+    pub reason: String,
+    pub status: EntryStatus,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub chain_type: String,
+    pub created_by: Option<String>,
 }
@@ -0,0 +1,151 @@
+use alloy::primitives::{Address, BlockHash, FixedBytes, TxHash, U256};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::{fmt, str::FromStr};
+
+use crate::auth::{auth::Finding, data::SHADOW_BLACKLIST_TABLE, pause::EscalationPolicy};
+
+/// 黑名单记录的证据表，与黑名单表一对多
+pub const EVIDENCE_TABLE: &str = "blacklist_evidence";
+/// 影子模式下的证据表
+pub const SHADOW_EVIDENCE_TABLE: &str = "blacklist_evidence_shadow";
+
+/// 重入检测器的名称
+pub const REENTRANCY_DETECTOR: &str = "reentrancy";
+
+/// 黑名单表对应的证据表
+pub fn evidence_table(table: &str) -> &'static str {
+    if table == SHADOW_BLACKLIST_TABLE {
+        SHADOW_EVIDENCE_TABLE
+    } else {
+        EVIDENCE_TABLE
+    }
+}
+
+/// 检测结果的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// 发生重入，但被攻击合约没有转出资金
+    Medium,
+    /// 被攻击合约在交易中转出了资金
+    High,
+    /// 目标是加入自动暂停的项目，且损失达到暂停阈值
+    Critical,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Self::Medium, Self::High, Self::Critical];
+
+    pub fn of(finding: &Finding, escalation: &EscalationPolicy) -> Self {
+        if escalation.target(finding).is_some() {
+            Self::Critical
+        } else if finding.loss > U256::ZERO {
+            Self::High
+        } else {
+            Self::Medium
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|severity| severity.as_str() == s)
+            .ok_or_else(|| anyhow!("未知的严重程度: {}", s))
+    }
+}
+
+/// 一次检测结果的证据，写入时按攻击地址关联到黑名单记录
+#[derive(Debug, Clone)]
+pub struct Evidence {
+    pub attacker: Address,
+    pub tx_hash: Option<TxHash>,
+    /// 查询区块头失败时为空
+    pub block_number: Option<u64>,
+    pub block_hash: BlockHash,
+    pub chain_type: String,
+    pub detector: String,
+    pub severity: Severity,
+    /// 被攻击的合约与函数
+    pub project: Address,
+    pub selector: Option<FixedBytes<4>>,
+    /// 被攻击合约在该交易中转出的原生代币，单位 wei
+    pub loss: U256,
+    /// 调用路径节选，每一项为 `合约|selector`
+    pub call_path: Vec<String>,
+}
+
+impl Evidence {
+    pub fn new(
+        finding: &Finding,
+        block_number: Option<u64>,
+        block_hash: BlockHash,
+        severity: Severity,
+    ) -> Self {
+        Self {
+            attacker: finding.address,
+            tx_hash: finding.tx_hash,
+            block_number,
+            block_hash,
+            // 目前只监控以太坊
+            chain_type: "ETH".to_string(),
+            detector: finding.detector.to_string(),
+            severity,
+            project: finding.target,
+            selector: finding.selector,
+            loss: finding.loss,
+            call_path: finding.call_path.clone(),
+        }
+    }
+
+    /// 写入黑名单的原因，由检测器与被攻击的合约、函数组成，
+    /// 同一攻击的回放得到相同的原因，例如 `reentrancy on 0x…ab12 0x2e1a7d4d`
+    pub fn reason(&self) -> String {
+        let mut reason = format!("{} on {:#x}", self.detector, self.project);
+        if let Some(selector) = self.selector {
+            reason.push_str(&format!(" {}", selector));
+        }
+        reason
+    }
+
+    /// 调用路径以 JSON 数组存储
+    pub fn call_path_json(&self) -> String {
+        serde_json::to_string(&self.call_path).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+/// 证据表中的一行
+#[derive(Debug, FromRow)]
+pub struct EvidenceRecord {
+    pub id: i64,
+    pub entry_id: i64,
+    pub tx_hash: Option<String>,
+    pub block_number: Option<i64>,
+    pub block_hash: Option<String>,
+    pub chain_type: String,
+    pub detector: String,
+    pub severity: String,
+    pub project: String,
+    pub func_sig: Option<String>,
+    pub loss_wei: String,
+    pub call_path: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,161 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use std::{collections::HashMap, env};
+
+use crate::auth::evidence::Severity;
+
+/// 各类原因默认的拉黑天数，0 表示永久有效
+const DEFAULT_REASON_DAYS: &[(&str, i64)] = &[
+    ("Fraudulent activity", 180),
+    ("Money laundering", 0),
+    ("Scam", 180),
+    ("Phishing attempt", 90),
+    ("Malicious behavior", 90),
+    ("Suspicious transaction", 30),
+];
+
+/// 检测结果按严重程度默认的拉黑天数；检测结果不会按严重程度永久拉黑
+const DEFAULT_SEVERITY_DAYS: &[(Severity, i64)] = &[
+    (Severity::Medium, 30),
+    (Severity::High, 180),
+    (Severity::Critical, 180),
+];
+
+/// 未列出原因的默认拉黑天数
+const DEFAULT_DAYS: i64 = 30;
+
+/// 黑名单过期策略：检测结果按严重程度、导入与人工添加的记录按原因决定有效期。
+/// 有效期为 None 表示永久有效
+#[derive(Debug, Clone)]
+pub struct ExpiryPolicy {
+    reason_days: HashMap<String, Option<Duration>>,
+    // EXPIRY_REASON_DAYS 中显式配置的原因，检测器名称在此列出时覆盖严重程度
+    configured_reasons: HashMap<String, Option<Duration>>,
+    severity_days: HashMap<Severity, Option<Duration>>,
+    default_days: Option<Duration>,
+}
+
+impl ExpiryPolicy {
+    /// `EXPIRY_DEFAULT_DAYS` 为未列出原因的有效期；
+    /// `EXPIRY_REASON_DAYS` 覆盖单个原因，格式如 `Scam=90,Phishing attempt=30`；
+    /// `EXPIRY_SEVERITY_DAYS` 覆盖检测结果的有效期，格式如 `medium=7,high=90`，天数必须大于 0；
+    /// 检测结果需要永久拉黑时在 `EXPIRY_REASON_DAYS` 中为检测器名称设置 0，如 `reentrancy=0`
+    pub fn from_env() -> Result<Self> {
+        Self::parse(
+            env::var("EXPIRY_DEFAULT_DAYS").ok().as_deref(),
+            env::var("EXPIRY_REASON_DAYS").ok().as_deref(),
+            env::var("EXPIRY_SEVERITY_DAYS").ok().as_deref(),
+        )
+    }
+
+    /// 按 `from_env` 中各变量的格式解析，未设置的使用默认值；
+    /// 格式错误、负数或超出范围的天数返回错误
+    pub fn parse(
+        default_days: Option<&str>,
+        reason_days: Option<&str>,
+        severity_days: Option<&str>,
+    ) -> Result<Self> {
+        let mut reasons = DEFAULT_REASON_DAYS
+            .iter()
+            .map(|(reason, days)| Ok((reason.to_string(), validity(*days)?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+        let configured = overrides(reason_days.unwrap_or_default())?
+            .into_iter()
+            .map(|(reason, days)| (reason.to_string(), days))
+            .collect::<HashMap<_, _>>();
+        reasons.extend(configured.clone());
+
+        let mut severities = DEFAULT_SEVERITY_DAYS
+            .iter()
+            .map(|(severity, days)| Ok((*severity, validity(*days)?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+        for (severity, days) in overrides(severity_days.unwrap_or_default())? {
+            if days.is_none() {
+                return Err(anyhow!(
+                    "严重程度 {} 的拉黑天数必须大于 0，永久拉黑请按检测器原因配置",
+                    severity
+                ));
+            }
+            severities.insert(severity.parse()?, days);
+        }
+
+        let default_days = match default_days {
+            Some(days) => parse_days(days)?,
+            None => validity(DEFAULT_DAYS)?,
+        };
+        Ok(Self {
+            reason_days: reasons,
+            configured_reasons: configured,
+            severity_days: severities,
+            default_days,
+        })
+    }
+
+    /// 计算以 `from` 为起点的过期时间，永久有效时返回 None
+    pub fn expires_at(&self, reason: &str, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let days = self
+            .reason_days
+            .get(reason)
+            .copied()
+            .unwrap_or(self.default_days);
+        expires(days, from)
+    }
+
+    /// 检测结果的过期时间：检测器名称在 `EXPIRY_REASON_DAYS` 中显式配置时按该配置，
+    /// 否则按严重程度；永久有效时返回 None
+    pub fn expires_for(
+        &self,
+        detector: &str,
+        severity: Severity,
+        from: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let days = match self.configured_reasons.get(detector) {
+            Some(days) => *days,
+            None => self
+                .severity_days
+                .get(&severity)
+                .copied()
+                .unwrap_or(self.default_days),
+        };
+        expires(days, from)
+    }
+}
+
+// 天数转换为有效期，0 表示永久
+fn validity(days: i64) -> Result<Option<Duration>> {
+    if days < 0 {
+        return Err(anyhow!("拉黑天数不能为负数: {}", days));
+    }
+    if days == 0 {
+        return Ok(None);
+    }
+    Duration::try_days(days)
+        .map(Some)
+        .ok_or_else(|| anyhow!("拉黑天数超出范围: {}", days))
+}
+
+fn parse_days(days: &str) -> Result<Option<Duration>> {
+    let days = days
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("无效的拉黑天数: {:?}", days))?;
+    validity(days)
+}
+
+// 解析 `键=天数` 的逗号分隔列表，忽略空项
+fn overrides(list: &str) -> Result<Vec<(&str, Option<Duration>)>> {
+    list.split(',')
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| {
+            let (key, days) = item
+                .split_once('=')
+                .ok_or_else(|| anyhow!("过期配置应为 键=天数: {:?}", item))?;
+            Ok((key.trim(), parse_days(days)?))
+        })
+        .collect()
+}
+
+// 超出可表示的时间范围时视为永久有效
+fn expires(days: Option<Duration>, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    from.checked_add_signed(days?)
+}
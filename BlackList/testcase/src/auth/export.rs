@@ -0,0 +1,334 @@
+use alloy::{
+    primitives::{Address, Bytes, TxHash, U256},
+    rpc::types::trace::geth::{CallFrame, TraceResult},
+};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+use crate::auth::{scope::FunctionScope, sync::env_or};
+
+/// 多签导出配置：同步阶段不再使用热钱包签名，而是把更新交易导出给 Safe 签名人
+#[derive(Debug, Clone)]
+pub struct ExportConfig {
+    pub dir: PathBuf,
+    /// 持有注册表管理权限的 Safe 地址
+    pub safe: Address,
+    /// 通过 HTTP 提供导出文件
+    pub http: Option<HttpExport>,
+    /// 按链上状态确认已导出批次的间隔
+    pub check_interval: Duration,
+}
+
+/// 导出文件的 HTTP 服务配置
+#[derive(Debug, Clone)]
+pub struct HttpExport {
+    pub addr: String,
+    /// 请求需携带 `Authorization: Bearer <token>`
+    pub token: String,
+}
+
+impl HttpExport {
+    /// `SAFE_EXPORT_HTTP` 只给出端口（`8080` 或 `:8080`）时只监听本机，
+    /// 开启 HTTP 时必须设置 `SAFE_EXPORT_TOKEN`
+    pub fn parse(addr: &str, token: Option<String>) -> Result<Self> {
+        let addr = match addr.trim_start_matches(':').parse::<u16>() {
+            Ok(port) => format!("127.0.0.1:{}", port),
+            Err(_) => addr.to_string(),
+        };
+        let token = token
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| anyhow!("开启 SAFE_EXPORT_HTTP 时必须设置 SAFE_EXPORT_TOKEN"))?;
+        Ok(Self { addr, token })
+    }
+}
+
+impl ExportConfig {
+    /// 设置 `SAFE_EXPORT_DIR` 时开启导出模式，此时必须提供 `SAFE_ADDRESS`
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(dir) = env::var("SAFE_EXPORT_DIR") else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            dir: PathBuf::from(dir),
+            safe: env::var("SAFE_ADDRESS")?.parse()?,
+            http: env::var("SAFE_EXPORT_HTTP")
+                .ok()
+                .map(|addr| HttpExport::parse(&addr, env::var("SAFE_EXPORT_TOKEN").ok()))
+                .transpose()?,
+            check_interval: Duration::from_secs(env_or("SAFE_EXPORT_CHECK_SECS", 300u64).max(1)),
+        }))
+    }
+
+    fn pending_dir(&self) -> PathBuf {
+        self.dir.join("pending")
+    }
+
+    fn executed_dir(&self) -> PathBuf {
+        self.dir.join("executed")
+    }
+}
+
+/// 未签名的原始交易
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RawTransaction {
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+/// 一个导出批次，对应 Safe 中的一笔多签交易
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedBatch {
+    pub id: String,
+    pub transactions: Vec<RawTransaction>,
+    /// 批次更新的项目
+    pub project: Address,
+    /// 批次执行后标记为已同步的地址
+    pub addresses: Vec<Address>,
+    /// 函数级拦截的批次
+    pub scope: Option<FunctionScope>,
+}
+
+impl ExportedBatch {
+    /// Safe Transaction Builder 可直接导入的 JSON
+    pub fn transaction_builder_json(&self, chain_id: u64, safe: Address) -> serde_json::Value {
+        let transactions: Vec<_> = self
+            .transactions
+            .iter()
+            .map(|tx| {
+                json!({
+                    "to": tx.to.to_string(),
+                    "value": tx.value.to_string(),
+                    "data": tx.data.to_string(),
+                    "contractMethod": null,
+                    "contractInputsValues": null,
+                })
+            })
+            .collect();
+        json!({
+            "version": "1.0",
+            "chainId": chain_id.to_string(),
+            "createdAt": Utc::now().timestamp_millis(),
+            "meta": {
+                "name": format!("blacklist-{}", self.id),
+                "description": format!("Blacklist update for {} address(es)", self.addresses.len()),
+                "txBuilderVersion": "1.16.5",
+                "createdFromSafeAddress": safe.to_string(),
+                "createdFromOwnerAddress": "",
+            },
+            "transactions": transactions,
+        })
+    }
+
+    /// 多签执行后调用树中会出现对同一目标、相同 calldata 的调用，
+    /// 以批次最后一笔交易为准，返回执行它的交易哈希
+    pub fn executed_in(&self, traces: &[TraceResult]) -> Option<TxHash> {
+        let last = self.transactions.last()?;
+        traces.iter().find_map(|trace| match trace {
+            TraceResult::Success { result, tx_hash } => {
+                let frame = result.clone().try_into_call_frame().ok()?;
+                // 外层交易回滚时内部调用同样无效
+                if frame.error.is_none() && contains_call(&frame, last) {
+                    *tx_hash
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+    }
+}
+
+fn contains_call(frame: &CallFrame, tx: &RawTransaction) -> bool {
+    if frame.error.is_some() {
+        return false;
+    }
+    if frame.to == Some(tx.to) && frame.input == tx.data {
+        return true;
+    }
+    frame.calls.iter().any(|call| contains_call(call, tx))
+}
+
+/// 写入待签名目录：`<id>.json` 为 Safe Transaction Builder 格式，
+/// `<id>.raw.json` 为 to/data/value 列表，`<id>.batch.json` 用于重启后恢复
+pub fn write_batch(config: &ExportConfig, chain_id: u64, batch: &ExportedBatch) -> Result<()> {
+    let dir = config.pending_dir();
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join(format!("{}.json", batch.id)),
+        serde_json::to_vec_pretty(&batch.transaction_builder_json(chain_id, config.safe))?,
+    )?;
+    fs::write(
+        dir.join(format!("{}.raw.json", batch.id)),
+        serde_json::to_vec_pretty(&batch.transactions)?,
+    )?;
+    fs::write(
+        dir.join(format!("{}.batch.json", batch.id)),
+        serde_json::to_vec_pretty(batch)?,
+    )?;
+    Ok(())
+}
+
+/// 读取所有尚未执行的批次
+pub fn load_pending(config: &ExportConfig) -> Result<Vec<ExportedBatch>> {
+    let dir = config.pending_dir();
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut batches = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.to_string_lossy().ends_with(".batch.json") {
+            batches.push(serde_json::from_slice(&fs::read(&path)?)?);
+        }
+    }
+    batches.sort_by(|a: &ExportedBatch, b| a.id.cmp(&b.id));
+    Ok(batches)
+}
+
+/// 批次执行后将其文件移动到 executed 目录
+pub fn archive_batch(config: &ExportConfig, batch: &ExportedBatch) -> Result<()> {
+    let executed = config.executed_dir();
+    fs::create_dir_all(&executed)?;
+    for suffix in ["json", "raw.json", "batch.json"] {
+        let name = format!("{}.{}", batch.id, suffix);
+        let from = config.pending_dir().join(&name);
+        if from.exists() {
+            fs::rename(from, executed.join(name))?;
+        }
+    }
+    Ok(())
+}
+
+/// 请求头的最大长度
+const MAX_REQUEST_HEADER: usize = 8 * 1024;
+
+/// 读取请求头的超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 以只读 HTTP 提供待签名的批次：`GET /` 返回文件列表，`GET /<文件名>` 返回文件内容
+pub async fn serve(config: ExportConfig, http: HttpExport) -> Result<()> {
+    let listener = TcpListener::bind(&http.addr).await?;
+    println!("导出批次通过 http://{} 提供", http.addr);
+    serve_listener(config, http.token, listener).await
+}
+
+/// 在已绑定的监听器上提供导出文件
+pub async fn serve_listener(
+    config: ExportConfig,
+    token: String,
+    listener: TcpListener,
+) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let dir = config.pending_dir();
+        let token = token.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_request(stream, &dir, &token).await {
+                eprintln!("导出服务请求处理失败: {}", err);
+            }
+        });
+    }
+}
+
+// 读到请求头结束为止，超长或超时返回 None
+async fn read_header(stream: &mut TcpStream) -> Result<Option<String>> {
+    let mut request = vec![];
+    let mut buf = [0u8; 1024];
+    let read = async {
+        loop {
+            if request.windows(4).any(|w| w == b"\r\n\r\n") {
+                return Ok(true);
+            }
+            if request.len() > MAX_REQUEST_HEADER {
+                return Ok(false);
+            }
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                return Ok(false);
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+    };
+    match timeout(REQUEST_TIMEOUT, read).await {
+        Ok(Ok(true)) => Ok(Some(String::from_utf8_lossy(&request).into_owned())),
+        Ok(Ok(false)) | Err(_) => Ok(None),
+        Ok(Err(err)) => Err(err),
+    }
+}
+
+// 逐字节比较全部内容，避免按前缀泄露令牌
+fn token_matches(request: &str, token: &str) -> bool {
+    let provided = request.lines().skip(1).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("authorization")
+            .then(|| value.trim().strip_prefix("Bearer "))
+            .flatten()
+    });
+    let Some(provided) = provided else {
+        return false;
+    };
+    provided.len() == token.len()
+        && provided
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+async fn handle_request(mut stream: TcpStream, dir: &Path, token: &str) -> Result<()> {
+    let Some(request) = read_header(&mut stream).await? else {
+        return respond(&mut stream, "400 Bad Request", b"bad request".to_vec()).await;
+    };
+    if !token_matches(&request, token) {
+        return respond(&mut stream, "401 Unauthorized", b"unauthorized".to_vec()).await;
+    }
+    let mut parts = request.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+
+    let (status, body) = match (method, path.trim_start_matches('/')) {
+        ("GET", "") => {
+            let mut names = vec![];
+            if dir.exists() {
+                for entry in fs::read_dir(dir)? {
+                    names.push(entry?.file_name().to_string_lossy().into_owned());
+                }
+            }
+            names.sort();
+            ("200 OK", serde_json::to_vec(&names)?)
+        }
+        // 只允许访问目录下的文件
+        ("GET", name) if !name.contains('/') && !name.contains("..") => {
+            match fs::read(dir.join(name)) {
+                Ok(body) => ("200 OK", body),
+                Err(_) => ("404 Not Found", b"not found".to_vec()),
+            }
+        }
+        _ => ("404 Not Found", b"not found".to_vec()),
+    };
+    respond(&mut stream, status, body).await
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: Vec<u8>) -> Result<()> {
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use alloy::{
+    network::TransactionBuilder,
+    providers::{utils::Eip1559Estimation, Provider},
+    rpc::types::TransactionRequest,
+    transports::Transport,
+};
+use anyhow::{anyhow, Result};
+
+use crate::auth::sync::env_or;
+
+const GWEI: u128 = 1_000_000_000;
+
+/// 更新交易的手续费与 gas 配置
+#[derive(Debug, Clone)]
+pub struct FeeConfig {
+    /// maxFeePerGas 上限（wei）
+    pub max_fee_cap: u128,
+    /// maxPriorityFeePerGas 上限（wei）
+    pub priority_fee_cap: u128,
+    /// 在 gas 估算值基础上增加的余量百分比
+    pub gas_margin_percent: u64,
+    /// gas limit 上限
+    pub gas_limit_cap: u64,
+    /// 替换交易时手续费提高的百分比，节点要求至少 10%
+    pub bump_percent: u128,
+}
+
+impl FeeConfig {
+    pub fn from_env() -> Self {
+        Self {
+            max_fee_cap: env_or("MAX_FEE_CAP_GWEI", 200u128) * GWEI,
+            priority_fee_cap: env_or("PRIORITY_FEE_CAP_GWEI", 5u128) * GWEI,
+            gas_margin_percent: env_or("GAS_MARGIN_PERCENT", 20),
+            gas_limit_cap: env_or("GAS_LIMIT_CAP", 10_000_000),
+            bump_percent: env_or("FEE_BUMP_PERCENT", 15u128).max(10),
+        }
+    }
+
+    /// 按预检得到的 gas 估算设置 gas limit，并估算 EIP-1559 手续费写入交易，
+    /// 超出上限时拒绝发送
+    pub async fn apply<P, T>(
+        &self,
+        provider: &P,
+        tx: &mut TransactionRequest,
+        estimated: u64,
+    ) -> Result<()>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        self.set_gas_limit(tx, estimated)?;
+        let fees = provider.estimate_eip1559_fees(None).await?;
+        self.set_fees(tx, fees)
+    }
+
+    /// 在 gas 估算值上增加余量作为 gas limit，超过上限时返回错误
+    pub fn set_gas_limit(&self, tx: &mut TransactionRequest, estimated: u64) -> Result<()> {
+        let gas_limit = estimated + estimated * self.gas_margin_percent / 100;
+        if gas_limit > self.gas_limit_cap {
+            return Err(anyhow!(
+                "gas 估算 {} 超过上限 {}",
+                gas_limit,
+                self.gas_limit_cap
+            ));
+        }
+        tx.set_gas_limit(gas_limit);
+        Ok(())
+    }
+
+    /// 按当前估算设置手续费，小费不超过上限，maxFeePerGas 超过上限时返回错误
+    pub fn set_fees(&self, tx: &mut TransactionRequest, fees: Eip1559Estimation) -> Result<()> {
+        let priority_fee = fees.max_priority_fee_per_gas.min(self.priority_fee_cap);
+        if fees.max_fee_per_gas > self.max_fee_cap {
+            return Err(anyhow!(
+                "当前手续费 {} wei 超过上限 {} wei",
+                fees.max_fee_per_gas,
+                self.max_fee_cap
+            ));
+        }
+        tx.set_max_fee_per_gas(fees.max_fee_per_gas.max(priority_fee));
+        tx.set_max_priority_fee_per_gas(priority_fee);
+        Ok(())
+    }
+
+    /// 为替换交易提高手续费，见 `bump_fees`
+    pub async fn bump<P, T>(&self, provider: &P, tx: &mut TransactionRequest) -> Result<()>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let current = provider.estimate_eip1559_fees(None).await?;
+        self.bump_fees(tx, current)
+    }
+
+    /// 取提高后的原手续费与当前估算值中的较大者，
+    /// 已经达到上限无法再提高时返回错误
+    pub fn bump_fees(&self, tx: &mut TransactionRequest, current: Eip1559Estimation) -> Result<()> {
+        let max_fee = self
+            .bumped(tx.max_fee_per_gas.unwrap_or_default())
+            .max(current.max_fee_per_gas);
+        let priority_fee = self
+            .bumped(tx.max_priority_fee_per_gas.unwrap_or_default())
+            .max(current.max_priority_fee_per_gas.min(self.priority_fee_cap));
+        if max_fee > self.max_fee_cap || priority_fee > self.priority_fee_cap {
+            return Err(anyhow!(
+                "替换交易所需手续费 {} / {} wei 超过上限",
+                max_fee,
+                priority_fee
+            ));
+        }
+        tx.set_max_fee_per_gas(max_fee.max(priority_fee));
+        tx.set_max_priority_fee_per_gas(priority_fee);
+        Ok(())
+    }
+
+    fn bumped(&self, fee: u128) -> u128 {
+        fee + (fee * self.bump_percent / 100).max(1)
+    }
+}
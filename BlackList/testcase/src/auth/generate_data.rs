@@ -1,23 +1,25 @@
-use chrono::{DateTime, Duration, Utc};
+use alloy::primitives::Address;
+use chrono::Utc;
 use dotenv::dotenv;
 use rand::seq::SliceRandom; // 导入 SliceRandom trait
-use rand::{distributions::Alphanumeric, Rng};
-use sqlx::{mysql::MySqlPoolOptions, MySql, Pool};
-use std::env;
+use rand::Rng;
+use std::sync::Arc;
 
-use super::data::create_blacklist_entry;
+use super::{
+    data::{EntryStatus, NewBlacklistEntry, BLACKLIST_TABLE},
+    expiry::ExpiryPolicy,
+    history::ChangeContext,
+    store::{connect, database_url, BlacklistStore},
+};
 
-// 用于生成随机区块链地址，地址以 '0x' 开头，后面是 40 个字符
-pub fn generate_random_address() -> String {
+// 用于生成随机区块链地址，20 个随机字节
+pub fn generate_random_address() -> Address {
     let mut rng = rand::thread_rng();
-    let random_address: String = (0..40) // 区块链地址通常是40个十六进制字符
-        .map(|_| rng.sample(Alphanumeric) as char) // 随机生成字母数字字符
-        .collect();
-    format!("0x{}", random_address) // 以 '0x' 开头
+    Address::from(rng.gen::<[u8; 20]>())
 }
 
 pub fn generate_random_reason() -> String {
-    let reasons = vec![
+    let reasons = [
         "Fraudulent activity",
         "Money laundering",
         "Scam",
@@ -30,21 +32,22 @@ pub fn generate_random_reason() -> String {
 }
 
 // 插入50条黑名单数据
-pub async fn insert_random_blacklist_entries(pool: &Pool<MySql>) -> Result<(), sqlx::Error> {
+pub async fn insert_random_blacklist_entries(
+    store: &dyn BlacklistStore,
+    policy: &ExpiryPolicy,
+) -> Result<(), sqlx::Error> {
     for _ in 0..50 {
-        let address = generate_random_address();
         let reason = generate_random_reason();
-        let status = "active";
-        let expires_at = Some(Utc::now());
-        let chain_type = "ETH";
-        let created_by = None;
+        let entry = NewBlacklistEntry {
+            address: generate_random_address(),
+            expires_at: policy.expires_at(&reason, Utc::now()),
+            reason,
+            status: EntryStatus::Active,
+            chain_type: "ETH".to_string(),
+            created_by: None,
+        };
 
-        let result = create_blacklist_entry(
-            &pool, &address, &reason, status, expires_at, chain_type, created_by,
-        )
-        .await;
-
-        if let Err(err) = result {
+        if let Err(err) = store.create(&entry, &ChangeContext::cli()).await {
             eprintln!("Failed to insert blacklist entry: {}", err);
         }
     }
@@ -52,13 +55,10 @@ pub async fn insert_random_blacklist_entries(pool: &Pool<MySql>) -> Result<(), s
     Ok(())
 }
 
-pub async fn setup_test_db() -> Pool<MySql> {
+pub async fn setup_test_db() -> Arc<dyn BlacklistStore> {
     dotenv().ok();
-    let db_url = env::var("MYSQL_URL").expect("MYSQL_URL must be set");
-    let pool = MySqlPoolOptions::new()
-        .max_connections(5)
-        .connect(&db_url)
+    let db_url = database_url().expect("DATABASE_URL must be set");
+    connect(&db_url, BLACKLIST_TABLE)
         .await
-        .expect("Failed to create pool");
-    pool
+        .expect("Failed to connect database")
 }
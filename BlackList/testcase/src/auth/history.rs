@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use std::env;
+
+use crate::auth::data::{BlacklistEntry, SHADOW_BLACKLIST_TABLE};
+
+/// 黑名单变更历史表，只追加，程序不提供修改或删除历史的接口
+pub const HISTORY_TABLE: &str = "blacklist_history";
+/// 影子模式下的变更历史表
+pub const SHADOW_HISTORY_TABLE: &str = "blacklist_history_shadow";
+
+/// 黑名单表对应的历史表
+pub fn history_table(table: &str) -> &'static str {
+    if table == SHADOW_BLACKLIST_TABLE {
+        SHADOW_HISTORY_TABLE
+    } else {
+        HISTORY_TABLE
+    }
+}
+
+/// 变更的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeSource {
+    /// 检测器发现攻击
+    Detector,
+    /// 命令行人工操作
+    Cli,
+    Api,
+    /// 从文件批量导入
+    Import,
+    /// 与链上状态对账
+    Reconciler,
+    /// 到期自动下架
+    Expiry,
+}
+
+impl ChangeSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Detector => "detector",
+            Self::Cli => "cli",
+            Self::Api => "api",
+            Self::Import => "import",
+            Self::Reconciler => "reconciler",
+            Self::Expiry => "expiry",
+        }
+    }
+}
+
+/// 变更的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeAction {
+    Create,
+    /// 地址再次被检测到，合并原因并延长期限
+    Merge,
+    Update,
+    /// 状态发生变化的更新
+    StatusChange,
+    Delete,
+}
+
+impl ChangeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Merge => "merge",
+            Self::Update => "update",
+            Self::StatusChange => "status_change",
+            Self::Delete => "delete",
+        }
+    }
+
+    /// 按新旧记录区分普通更新与状态变化
+    pub fn for_update(old: &BlacklistEntry, new: &BlacklistEntry) -> Self {
+        if old.status != new.status {
+            Self::StatusChange
+        } else {
+            Self::Update
+        }
+    }
+}
+
+/// 发起变更的来源与操作人，随每次写操作记入历史
+#[derive(Debug, Clone)]
+pub struct ChangeContext {
+    pub source: ChangeSource,
+    pub actor: Option<String>,
+}
+
+impl ChangeContext {
+    pub fn new(source: ChangeSource) -> Self {
+        Self {
+            source,
+            actor: None,
+        }
+    }
+
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// 命令行操作，操作人取当前系统用户
+    pub fn cli() -> Self {
+        Self {
+            source: ChangeSource::Cli,
+            actor: env::var("USER").ok(),
+        }
+    }
+}
+
+/// 记录内容没有变化时不写历史，避免重复检测产生大量无意义的合并记录
+pub fn is_unchanged(old: &BlacklistEntry, new: &BlacklistEntry) -> bool {
+    old.reason == new.reason
+        && old.status == new.status
+        && old.expires_at == new.expires_at
+        && old.chain_type == new.chain_type
+        && old.created_by == new.created_by
+}
+
+/// 记录的 JSON 快照
+pub fn snapshot(entry: Option<&BlacklistEntry>) -> Option<String> {
+    entry.and_then(|entry| serde_json::to_string(entry).ok())
+}
+
+/// 历史表中的一行，记录删除后仍然保留
+#[derive(Debug, FromRow)]
+pub struct HistoryRecord {
+    pub id: i64,
+    pub entry_id: i64,
+    pub address: String,
+    pub chain_type: String,
+    pub action: String,
+    pub source: String,
+    pub actor: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
@@ -1,3 +1,29 @@
+pub mod alert;
+pub mod allowlist;
+#[allow(clippy::module_inception)]
 pub mod auth;
+pub mod batch;
+pub mod bulk;
 pub mod data;
+pub mod evidence;
+pub mod expiry;
+pub mod export;
+pub mod fees;
 pub mod generate_data;
+pub mod history;
+pub mod nonce;
+pub mod ofac;
+pub mod pause;
+pub mod payload;
+pub mod preflight;
+pub mod projects;
+pub mod query;
+pub mod reconcile;
+pub mod replay;
+pub mod retry;
+pub mod schema;
+pub mod scope;
+pub mod signer;
+pub mod store;
+pub mod sync;
+pub mod token;
@@ -0,0 +1,57 @@
+use alloy::{primitives::Address, providers::Provider, transports::Transport};
+use anyhow::Result;
+use std::sync::Arc;
+
+use crate::auth::store::BlacklistStore;
+
+/// 发送账户的 nonce 表，多个发送方共享同一账户时通过行锁串行分配
+pub const NONCE_TABLE: &str = "tx_nonce";
+
+/// 基于数据库的 nonce 管理器，进程重启后从数据库与链上状态中较大者继续分配
+#[derive(Clone)]
+pub struct NonceManager {
+    store: Arc<dyn BlacklistStore>,
+    chain_id: u64,
+    sender: Address,
+}
+
+impl NonceManager {
+    pub fn new(store: Arc<dyn BlacklistStore>, chain_id: u64, sender: Address) -> Self {
+        Self {
+            store,
+            chain_id,
+            sender,
+        }
+    }
+
+    pub fn sender(&self) -> Address {
+        self.sender
+    }
+
+    /// 分配下一个 nonce
+    pub async fn reserve<P, T>(&self, provider: &P) -> Result<u64>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        // 链上 pending nonce 在加锁前获取，其他发送方同时分配时以数据库记录为准
+        let chain_nonce = provider
+            .get_transaction_count(self.sender)
+            .pending()
+            .await?;
+
+        Ok(self
+            .store
+            .reserve_nonce(self.chain_id, self.sender, chain_nonce)
+            .await?)
+    }
+
+    /// 交易未能广播时归还 nonce，避免留下空洞；之后已有新的分配则不回退
+    pub async fn release(&self, nonce: u64) -> Result<()> {
+        self.store
+            .release_nonce(self.chain_id, self.sender, nonce)
+            .await?;
+
+        Ok(())
+    }
+}
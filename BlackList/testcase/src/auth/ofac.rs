@@ -0,0 +1,414 @@
+use alloy::primitives::{Address, BlockHash, U256};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use quick_xml::events::Event;
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufRead, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::auth::{
+    data::{canonical_address, BlacklistEntry, EntryStatus, NewBlacklistEntry, UpsertOutcome},
+    evidence::{Evidence, Severity},
+    history::ChangeContext,
+    query::{BlacklistQuery, MAX_PAGE_SIZE},
+    store::BlacklistStore,
+};
+
+/// OFAC SDN 名单的来源名称，用作新记录的创建人与证据的检测器。
+/// 记录是否由名单列入以该证据为准，与创建人无关
+pub const OFAC_SDN_SOURCE: &str = "ofac-sdn";
+
+// SDN 名单中数字货币地址的标识前缀，后接币种代码
+const DIGITAL_CURRENCY_ADDRESS: &str = "Digital Currency Address - ";
+
+/// SDN 名单的发布格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SdnFormat {
+    /// sdn.xml
+    Xml,
+    /// sdn.csv，数字货币地址位于备注列
+    Csv,
+}
+
+impl SdnFormat {
+    /// 按扩展名推断格式
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .ok_or_else(|| anyhow!("无法从 {} 推断名单格式", path.display()))?;
+        extension.to_lowercase().parse()
+    }
+}
+
+impl FromStr for SdnFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "xml" => Ok(Self::Xml),
+            "csv" => Ok(Self::Csv),
+            _ => Err(anyhow!("不支持的名单格式: {}", s)),
+        }
+    }
+}
+
+/// 名单中的一个受制裁地址
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdnAddress {
+    pub address: Address,
+    pub chain_type: String,
+    /// SDN 条目编号
+    pub uid: String,
+    /// 受制裁实体或个人的名称
+    pub name: String,
+    /// 制裁项目，例如 CYBER2、DPRK3
+    pub programs: Vec<String>,
+}
+
+impl SdnAddress {
+    /// 写入黑名单的原因
+    pub fn reason(&self) -> String {
+        format!("OFAC SDN: {} [{}]", self.name, self.programs.join(", "))
+    }
+
+    /// 标记记录由名单列入的证据，没有交易与被攻击项目
+    pub fn evidence(&self) -> Evidence {
+        Evidence {
+            attacker: self.address,
+            tx_hash: None,
+            block_number: None,
+            block_hash: BlockHash::ZERO,
+            chain_type: self.chain_type.clone(),
+            detector: OFAC_SDN_SOURCE.to_string(),
+            severity: Severity::High,
+            project: Address::ZERO,
+            selector: None,
+            loss: U256::ZERO,
+            call_path: vec![],
+        }
+    }
+}
+
+/// 解析后的一版名单
+#[derive(Debug, Default)]
+pub struct SdnList {
+    /// 名单的发布日期
+    pub published: Option<String>,
+    /// 可拦截的 EVM 地址，同一链上的地址只保留第一次出现
+    pub addresses: Vec<SdnAddress>,
+    /// 其他链的地址，例如 `XBT 1Abc...`，黑名单只支持 EVM 地址
+    pub unsupported: Vec<String>,
+}
+
+impl SdnList {
+    pub fn parse<R: BufRead>(reader: R, format: SdnFormat) -> Result<Self> {
+        match format {
+            SdnFormat::Xml => parse_xml(reader),
+            SdnFormat::Csv => parse_csv(reader),
+        }
+    }
+
+    // 加入一个数字货币地址，币种不受支持或地址不是 EVM 格式时计入 unsupported
+    fn push(&mut self, currency: &str, address: &str, uid: &str, name: &str, programs: &[String]) {
+        let address = address.trim().trim_end_matches('.');
+        let chain_type = chain_type(currency.trim());
+        match (chain_type, address.parse::<Address>()) {
+            (Some(chain_type), Ok(address)) => {
+                let duplicate = self
+                    .addresses
+                    .iter()
+                    .any(|sdn| sdn.address == address && sdn.chain_type == chain_type);
+                if !duplicate {
+                    self.addresses.push(SdnAddress {
+                        address,
+                        chain_type: chain_type.to_string(),
+                        uid: uid.to_string(),
+                        name: name.to_string(),
+                        programs: programs.to_vec(),
+                    });
+                }
+            }
+            _ => self
+                .unsupported
+                .push(format!("{} {}", currency.trim(), address)),
+        }
+    }
+}
+
+// OFAC 币种代码对应的链类型，稳定币按以太坊上的合约处理
+fn chain_type(currency: &str) -> Option<&'static str> {
+    match currency {
+        "ETH" | "USDT" | "USDC" | "DAI" => Some("ETH"),
+        "ETC" => Some("ETC"),
+        "BSC" => Some("BSC"),
+        "ARB" => Some("ARB"),
+        _ => None,
+    }
+}
+
+// 逐个读取 sdnEntry，地址位于 idList 中 idType 为数字货币地址的 id
+fn parse_xml<R: BufRead>(reader: R) -> Result<SdnList> {
+    let mut reader = quick_xml::Reader::from_reader(reader);
+    reader.config_mut().trim_text(true);
+    let mut list = SdnList::default();
+    let mut path: Vec<String> = vec![];
+    let mut buf = vec![];
+
+    let (mut uid, mut first_name, mut last_name) = (String::new(), String::new(), String::new());
+    let mut programs: Vec<String> = vec![];
+    // 当前条目中的 (币种, 地址)
+    let mut ids: Vec<(String, String)> = vec![];
+    let mut id_type = String::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).to_string();
+                if name == "sdnEntry" {
+                    uid.clear();
+                    first_name.clear();
+                    last_name.clear();
+                    programs.clear();
+                    ids.clear();
+                } else if name == "id" {
+                    id_type.clear();
+                }
+                path.push(name);
+            }
+            Event::End(_) => {
+                let closed = path.pop();
+                if closed.as_deref() == Some("sdnEntry") {
+                    let name = [first_name.as_str(), last_name.as_str()]
+                        .iter()
+                        .filter(|part| !part.is_empty())
+                        .copied()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    for (currency, address) in &ids {
+                        list.push(currency, address, &uid, &name, &programs);
+                    }
+                }
+            }
+            Event::Text(text) => {
+                let text = text.unescape()?.to_string();
+                let parents: Vec<&str> = path.iter().rev().take(2).map(String::as_str).collect();
+                match parents.as_slice() {
+                    ["uid", "sdnEntry"] => uid = text,
+                    ["firstName", "sdnEntry"] => first_name = text,
+                    ["lastName", "sdnEntry"] => last_name = text,
+                    ["program", "programList"] => programs.push(text),
+                    ["Publish_Date", _] => list.published = Some(text),
+                    ["idType", "id"] => id_type = text,
+                    ["idNumber", "id"] => {
+                        if let Some(currency) = id_type.strip_prefix(DIGITAL_CURRENCY_ADDRESS) {
+                            ids.push((currency.to_string(), text));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(list)
+}
+
+// sdn.csv 没有表头，依次为编号、名称、类型、项目……备注，空值为 `-0-`。
+// 地址写在备注中，例如 `Digital Currency Address - ETH 0x...;`
+fn parse_csv<R: Read>(reader: R) -> Result<SdnList> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader);
+    let mut list = SdnList::default();
+    for record in reader.byte_records() {
+        let record = record?;
+        // 文件末尾可能有结束符等不完整的行
+        if record.len() < 12 {
+            continue;
+        }
+        let field = |index: usize| String::from_utf8_lossy(&record[index]).trim().to_string();
+        let remarks = field(11);
+        if !remarks.contains(DIGITAL_CURRENCY_ADDRESS) {
+            continue;
+        }
+        let programs: Vec<String> = field(3)
+            .split("] [")
+            .map(|program| {
+                program
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .trim()
+                    .to_string()
+            })
+            .filter(|program| !program.is_empty() && program != "-0-")
+            .collect();
+        let (uid, name) = (field(0), field(1));
+        for remark in remarks.split(';') {
+            let Some((_, rest)) = remark.split_once(DIGITAL_CURRENCY_ADDRESS) else {
+                continue;
+            };
+            if let Some((currency, address)) = rest.trim().split_once(char::is_whitespace) {
+                list.push(currency, address, &uid, &name, &programs);
+            }
+        }
+    }
+    Ok(list)
+}
+
+/// 新一版名单与已导入记录的差异
+#[derive(Debug, Default)]
+pub struct SdnDiff {
+    /// 新列入名单的地址，包括已由其他来源拉黑、尚无名单证据的地址
+    pub to_add: Vec<SdnAddress>,
+    /// 已从名单中移除、需要撤销的记录
+    pub to_revoke: Vec<BlacklistEntry>,
+    /// 已从名单中移除，但还有检测器等其他证据而保留的记录
+    pub retained: Vec<BlacklistEntry>,
+}
+
+/// 按 (链类型, 地址) 对比名单与带有名单证据且尚未撤销或到期的记录。
+/// 移出名单的记录只有名单证据时撤销，还有其他证据时保留
+pub async fn diff(store: &dyn BlacklistStore, list: &SdnList) -> Result<SdnDiff> {
+    let mut query = BlacklistQuery::new()
+        .detector(OFAC_SDN_SOURCE)
+        .status(EntryStatus::PendingReview)
+        .status(EntryStatus::Active)
+        .status(EntryStatus::Suspended)
+        .status(EntryStatus::Appealed)
+        .limit(MAX_PAGE_SIZE);
+    let mut previous = HashMap::new();
+    loop {
+        let page = store.query(&query).await?;
+        for entry in page.entries {
+            previous.insert((entry.chain_type.clone(), entry.address.clone()), entry);
+        }
+        match page.next {
+            Some(cursor) => query = query.after(cursor),
+            None => break,
+        }
+    }
+
+    let listed: HashSet<(String, String)> = list
+        .addresses
+        .iter()
+        .map(|sdn| (sdn.chain_type.clone(), canonical_address(&sdn.address)))
+        .collect();
+    let to_add = list
+        .addresses
+        .iter()
+        .filter(|sdn| {
+            !previous.contains_key(&(sdn.chain_type.clone(), canonical_address(&sdn.address)))
+        })
+        .cloned()
+        .collect();
+    let mut delisted: Vec<BlacklistEntry> = previous
+        .into_iter()
+        .filter(|(key, _)| !listed.contains(key))
+        .map(|(_, entry)| entry)
+        .collect();
+    delisted.sort_by_key(|entry| entry.id);
+
+    let (mut to_revoke, mut retained) = (vec![], vec![]);
+    for entry in delisted {
+        let other_evidence = store
+            .list_evidence(entry.id)
+            .await?
+            .iter()
+            .any(|evidence| evidence.detector != OFAC_SDN_SOURCE);
+        if other_evidence {
+            retained.push(entry);
+        } else {
+            to_revoke.push(entry);
+        }
+    }
+    Ok(SdnDiff {
+        to_add,
+        to_revoke,
+        retained,
+    })
+}
+
+/// 差异写入数据库后的统计
+#[derive(Debug, Default)]
+pub struct SdnApplied {
+    pub inserted: usize,
+    /// 地址已由其他来源拉黑，合并了原因
+    pub merged: usize,
+    /// 处于人工处理中或在白名单中而未生效
+    pub held: usize,
+    pub revoked: usize,
+    /// 链上同步失败的地址：新增的记录已写入，由常驻进程继续同步；撤销失败的记录保持原状态
+    pub failed: usize,
+}
+
+/// 名单变更的链上同步，由更新器实现
+#[async_trait]
+pub trait SdnSync: Send + Sync {
+    /// 新拉黑的地址立即推送上链
+    async fn listed(&self, address: Address, reason: &str) -> Result<()>;
+
+    /// 撤销记录，已上链的地址先从链上移除，移除失败时不修改记录
+    async fn revoke(&self, entry: &BlacklistEntry, context: &ChangeContext) -> Result<()>;
+}
+
+/// 新列入的地址永久拉黑、记录名单证据并推送上链，移出名单的记录从链上移除后置为 revoked。
+/// 单个地址同步失败时计入 `failed`，不中断其余地址
+pub async fn apply(
+    store: &dyn BlacklistStore,
+    sync: &dyn SdnSync,
+    diff: &SdnDiff,
+    context: &ChangeContext,
+) -> Result<SdnApplied> {
+    let mut applied = SdnApplied::default();
+    for sdn in &diff.to_add {
+        let entry = NewBlacklistEntry {
+            address: sdn.address,
+            reason: sdn.reason(),
+            status: EntryStatus::Active,
+            expires_at: None,
+            chain_type: sdn.chain_type.clone(),
+            created_by: Some(OFAC_SDN_SOURCE.to_string()),
+        };
+        let listed = match store.upsert(&entry, context).await? {
+            UpsertOutcome::Inserted | UpsertOutcome::Reactivated => {
+                applied.inserted += 1;
+                true
+            }
+            UpsertOutcome::Merged => {
+                applied.merged += 1;
+                false
+            }
+            UpsertOutcome::Held => {
+                applied.held += 1;
+                false
+            }
+            // 白名单地址没有记录，无法关联证据
+            UpsertOutcome::Allowlisted => {
+                applied.held += 1;
+                continue;
+            }
+        };
+        store.add_evidence(&sdn.evidence()).await?;
+        if listed {
+            if let Err(err) = sync.listed(sdn.address, &entry.reason).await {
+                eprintln!("{} 上链失败: {}", sdn.address, err);
+                applied.failed += 1;
+            }
+        }
+    }
+    for entry in &diff.to_revoke {
+        match sync.revoke(entry, context).await {
+            Ok(()) => applied.revoked += 1,
+            Err(err) => {
+                eprintln!("撤销 {} 失败: {}", entry.address, err);
+                applied.failed += 1;
+            }
+        }
+    }
+    Ok(applied)
+}
@@ -0,0 +1,226 @@
+use alloy::{
+    primitives::{Address, FixedBytes, U256},
+    sol_types::SolCall,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::{
+    collections::{HashSet, VecDeque},
+    env,
+    time::Duration,
+};
+use tokio::time::Instant;
+
+use crate::auth::{
+    auth::{Finding, Registry},
+    sync::env_or,
+};
+
+/// 记录自动暂停及人工恢复的表
+pub const PAUSE_TABLE: &str = "registry_pause";
+
+/// 暂停的范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PauseTarget {
+    /// 注册表的 pauseFunction，项目管理员或注册表 owner 可调用
+    Function {
+        project: Address,
+        selector: FixedBytes<4>,
+    },
+    /// 注册表的 pauseProject，只有注册表 owner 可调用
+    Project(Address),
+}
+
+impl PauseTarget {
+    pub fn project(&self) -> Address {
+        match self {
+            Self::Function { project, .. } => *project,
+            Self::Project(project) => *project,
+        }
+    }
+
+    pub fn pause_calldata(&self) -> Vec<u8> {
+        match *self {
+            Self::Function { project, selector } => Registry::pauseFunctionCall {
+                project,
+                funcSig: selector,
+            }
+            .abi_encode(),
+            Self::Project(project) => Registry::pauseProjectCall { project }.abi_encode(),
+        }
+    }
+
+    pub fn unpause_calldata(&self) -> Vec<u8> {
+        match *self {
+            Self::Function { project, selector } => Registry::unpauseFunctionCall {
+                project,
+                funcSig: selector,
+            }
+            .abi_encode(),
+            Self::Project(project) => Registry::unpauseProjectCall { project }.abi_encode(),
+        }
+    }
+
+    // 表中以空字符串表示暂停整个项目
+    pub(crate) fn func_sig(&self) -> String {
+        match self {
+            Self::Function { selector, .. } => selector.to_string(),
+            Self::Project(_) => String::new(),
+        }
+    }
+}
+
+/// 严重发现的升级策略：只对主动加入的项目自动暂停，并限制暂停频率
+#[derive(Debug, Clone)]
+pub struct EscalationPolicy {
+    /// 同意被自动暂停的项目
+    projects: HashSet<Address>,
+    /// 暂停整个项目而不是被攻击的函数
+    whole_project: bool,
+    /// 一个时间窗口内最多发起的暂停次数
+    pub max_pauses: usize,
+    pub window: Duration,
+    /// 被攻击合约至少转出这么多原生代币才暂停，至少为 1 wei
+    pub min_loss: U256,
+}
+
+impl EscalationPolicy {
+    /// `PAUSE_PROJECTS` 以逗号分隔列出加入自动暂停的项目，未设置时不自动暂停；
+    /// `PAUSE_WHOLE_PROJECT` 为 true 时调用 pauseProject；
+    /// `PAUSE_MIN_LOSS_WEI` 为触发暂停的最小损失
+    pub fn from_env() -> Result<Self> {
+        let projects = match env::var("PAUSE_PROJECTS") {
+            Ok(projects) => projects
+                .split(',')
+                .filter(|p| !p.trim().is_empty())
+                .map(|p| p.trim().parse())
+                .collect::<Result<Vec<Address>, _>>()?,
+            Err(_) => vec![],
+        };
+        Ok(Self::new(
+            projects,
+            env_or("PAUSE_WHOLE_PROJECT", false),
+            env_or("PAUSE_MAX_PER_WINDOW", 3usize),
+            Duration::from_secs(env_or("PAUSE_WINDOW_SECS", 3600)),
+            env_or("PAUSE_MIN_LOSS_WEI", U256::from(1)),
+        ))
+    }
+
+    pub fn new(
+        projects: impl IntoIterator<Item = Address>,
+        whole_project: bool,
+        max_pauses: usize,
+        window: Duration,
+        min_loss: U256,
+    ) -> Self {
+        Self {
+            projects: projects.into_iter().collect(),
+            whole_project,
+            max_pauses,
+            window,
+            min_loss: min_loss.max(U256::from(1)),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.projects.is_empty()
+    }
+
+    /// 重入攻击的目标是加入自动暂停的项目、且确实转出了资金时视为严重发现。
+    /// 识别出被攻击的函数时暂停该函数，否则暂停整个项目
+    pub fn target(&self, finding: &Finding) -> Option<PauseTarget> {
+        if !self.projects.contains(&finding.target) || finding.loss < self.min_loss {
+            return None;
+        }
+        match finding.selector {
+            Some(selector) if !self.whole_project => Some(PauseTarget::Function {
+                project: finding.target,
+                selector,
+            }),
+            _ => Some(PauseTarget::Project(finding.target)),
+        }
+    }
+}
+
+/// 暂停频率限制，已暂停的目标在人工恢复前不再重复暂停
+#[derive(Debug, Default)]
+pub struct PauseLimiter {
+    recent: VecDeque<(Instant, PauseTarget)>,
+    paused: HashSet<PauseTarget>,
+}
+
+impl PauseLimiter {
+    pub fn new(paused: impl IntoIterator<Item = PauseTarget>) -> Self {
+        Self {
+            recent: VecDeque::new(),
+            paused: paused.into_iter().collect(),
+        }
+    }
+
+    /// 允许时登记本次暂停
+    pub fn allow(&mut self, policy: &EscalationPolicy, target: PauseTarget, now: Instant) -> bool {
+        let project_paused = self
+            .paused
+            .contains(&PauseTarget::Project(target.project()));
+        if project_paused || self.paused.contains(&target) {
+            return false;
+        }
+        while self
+            .recent
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) >= policy.window)
+        {
+            self.recent.pop_front();
+        }
+        if self.recent.len() >= policy.max_pauses {
+            return false;
+        }
+        self.recent.push_back((now, target));
+        self.paused.insert(target);
+        true
+    }
+
+    /// 暂停交易失败时撤销登记并退还频率额度，允许下次重试
+    pub fn revoke(&mut self, target: &PauseTarget) {
+        self.paused.remove(target);
+        if let Some(index) = self.recent.iter().rposition(|(_, t)| t == target) {
+            self.recent.remove(index);
+        }
+    }
+
+    /// 人工恢复后允许再次暂停，已发起的暂停仍计入频率限制
+    pub fn unpaused(&mut self, target: &PauseTarget) {
+        self.paused.remove(target);
+    }
+}
+
+/// 仍处于暂停状态的记录
+#[derive(Debug, Clone)]
+pub struct PauseRecord {
+    pub target: PauseTarget,
+    /// 触发暂停的攻击者
+    pub attacker: Address,
+    pub paused_at: DateTime<Utc>,
+}
+
+impl PauseRecord {
+    // 读取 (project, func_sig, attacker, paused_at) 行，无法解析时返回 None
+    pub(crate) fn from_row(
+        (project, func_sig, attacker, paused_at): (String, String, String, DateTime<Utc>),
+    ) -> Option<Self> {
+        let project = project.parse().ok()?;
+        let target = if func_sig.is_empty() {
+            PauseTarget::Project(project)
+        } else {
+            PauseTarget::Function {
+                project,
+                selector: func_sig.parse().ok()?,
+            }
+        };
+        Some(Self {
+            target,
+            attacker: attacker.parse().ok()?,
+            paused_at,
+        })
+    }
+}
@@ -0,0 +1,171 @@
+use alloy::{
+    primitives::{Address, Bytes, FixedBytes},
+    sol,
+    sol_types::{SolCall, SolValue},
+};
+
+use crate::auth::auth::{Registry, RegistryProxy};
+
+sol! {
+    /// `AuthModule.setInfo` 解码的参数 `(address,bool,bytes4,address,bool,bool)`
+    #[derive(Debug, PartialEq, Eq)]
+    struct SetInfoPayload {
+        address project;
+        bool isFuctionAccessBlacklist;
+        bytes4 funcSig;
+        address blackAddr;
+        bool isblack;
+        bool useFunctionAccess;
+    }
+
+    /// `AuthModule.batchSetInfo` 解码的参数 `(address,address[])`
+    #[derive(Debug, PartialEq, Eq)]
+    struct BatchSetInfoPayload {
+        address project;
+        address[] blackAddr;
+    }
+
+    /// `AuthModule.changeMode` 解码的参数 `(address,bool,bool)`
+    #[derive(Debug, PartialEq, Eq)]
+    struct ChangeModePayload {
+        address project;
+        bool isFuctionAccessBlacklist;
+        bool isGlobalAccessBlacklist;
+    }
+
+    /// `AuthModule.removeInfo` 解码的参数 `(address,bytes4,address)`
+    #[derive(Debug, PartialEq, Eq)]
+    struct RemoveInfoPayload {
+        address project;
+        bytes4 funcSig;
+        address blackAddr;
+    }
+
+    /// 注册表中转发到鉴权模块的入口，`abi/registry.json` 中缺少这两个函数；
+    /// changeMode 只有 RegistryV2 提供，V1 上调用会回滚
+    #[sol(rpc)]
+    interface IRegistryAuth {
+        function batchSetInfo(address module_address, bytes memory data) external;
+        function changeMode(address module_address, bytes memory data) external;
+    }
+}
+
+impl SetInfoPayload {
+    /// 对项目全局拉黑/解除拉黑一个地址
+    pub fn global(project: Address, black_addr: Address, is_black: bool) -> Self {
+        Self {
+            project,
+            isFuctionAccessBlacklist: false,
+            funcSig: FixedBytes::ZERO,
+            blackAddr: black_addr,
+            isblack: is_black,
+            useFunctionAccess: false,
+        }
+    }
+
+    /// 只对项目的某个函数拉黑/解除拉黑一个地址
+    pub fn function(
+        project: Address,
+        func_sig: FixedBytes<4>,
+        black_addr: Address,
+        is_black: bool,
+    ) -> Self {
+        Self {
+            project,
+            isFuctionAccessBlacklist: true,
+            funcSig: func_sig,
+            blackAddr: black_addr,
+            isblack: is_black,
+            useFunctionAccess: true,
+        }
+    }
+}
+
+impl BatchSetInfoPayload {
+    pub fn new(project: Address, black_addrs: Vec<Address>) -> Self {
+        Self {
+            project,
+            blackAddr: black_addrs,
+        }
+    }
+}
+
+impl ChangeModePayload {
+    pub fn new(project: Address, function_access: bool, global_access: bool) -> Self {
+        Self {
+            project,
+            isFuctionAccessBlacklist: function_access,
+            isGlobalAccessBlacklist: global_access,
+        }
+    }
+}
+
+impl RemoveInfoPayload {
+    /// 全局黑名单移除时 `func_sig` 传 0
+    pub fn new(project: Address, func_sig: FixedBytes<4>, black_addr: Address) -> Self {
+        Self {
+            project,
+            funcSig: func_sig,
+            blackAddr: black_addr,
+        }
+    }
+}
+
+/// 对鉴权模块的一次调用，经注册表转发
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthModuleCall {
+    SetInfo(SetInfoPayload),
+    BatchSetInfo(BatchSetInfoPayload),
+    ChangeMode(ChangeModePayload),
+    RemoveInfo(RemoveInfoPayload),
+}
+
+impl AuthModuleCall {
+    /// 模块函数 `abi.decode(data, (...))` 所期望的 `data`
+    pub fn payload(&self) -> Bytes {
+        match self {
+            Self::SetInfo(p) => p.abi_encode_params(),
+            Self::BatchSetInfo(p) => p.abi_encode_params(),
+            Self::ChangeMode(p) => p.abi_encode_params(),
+            Self::RemoveInfo(p) => p.abi_encode_params(),
+        }
+        .into()
+    }
+
+    /// 调用注册表对应入口的 calldata
+    pub fn registry_calldata(&self, auth_module: Address) -> Bytes {
+        let data = self.payload();
+        match self {
+            Self::SetInfo(_) => Registry::updataModuleInfoCall {
+                module_address: auth_module,
+                data,
+            }
+            .abi_encode(),
+            Self::BatchSetInfo(_) => IRegistryAuth::batchSetInfoCall {
+                module_address: auth_module,
+                data,
+            }
+            .abi_encode(),
+            Self::ChangeMode(_) => IRegistryAuth::changeModeCall {
+                module_address: auth_module,
+                data,
+            }
+            .abi_encode(),
+            Self::RemoveInfo(_) => Registry::removeModuleInfoCall {
+                module_address: auth_module,
+                data,
+            }
+            .abi_encode(),
+        }
+        .into()
+    }
+
+    /// 经 `RegistryProxy.CallOn` 转发的 calldata，即更新交易的 input
+    pub fn proxy_calldata(&self, auth_module: Address) -> Bytes {
+        RegistryProxy::CallOnCall {
+            _data: self.registry_calldata(auth_module),
+        }
+        .abi_encode()
+        .into()
+    }
+}
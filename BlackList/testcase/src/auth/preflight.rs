@@ -0,0 +1,42 @@
+use alloy::{
+    hex,
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    sol_types::decode_revert_reason,
+    transports::{Transport, TransportError},
+};
+use anyhow::{anyhow, Result};
+
+/// 广播前用 eth_call 与 eth_estimateGas 模拟更新交易，返回估算的 gas。
+/// 回滚时解码原因，例如模块 check 修饰器拒绝调用方时的 "ParamModule:permission denied"
+pub async fn simulate<P, T>(provider: &P, tx: &TransactionRequest) -> Result<u64>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    provider
+        .call(tx)
+        .await
+        .map_err(|err| anyhow!("模拟执行回滚: {}", revert_reason(&err)))?;
+    provider
+        .estimate_gas(tx)
+        .await
+        .map_err(|err| anyhow!("gas 估算失败: {}", revert_reason(&err)))
+}
+
+/// 从节点返回的错误中提取回滚原因，无法解码时返回原始数据
+pub fn revert_reason(err: &TransportError) -> String {
+    let Some(payload) = err.as_error_resp() else {
+        return err.to_string();
+    };
+    match payload.as_revert_data() {
+        Some(data) => decode_revert_reason(&data).unwrap_or_else(|| {
+            format!(
+                "{} (data: {})",
+                payload.message,
+                hex::encode_prefixed(&data)
+            )
+        }),
+        None => payload.message.to_string(),
+    }
+}
@@ -0,0 +1,176 @@
+use alloy::{
+    primitives::{Address, FixedBytes},
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+    transports::Transport,
+};
+use anyhow::{anyhow, Result};
+use std::{
+    collections::{BTreeSet, HashMap},
+    env,
+    str::FromStr,
+};
+
+use crate::auth::{auth::Registry, reconcile::get_logs_chunked};
+
+/// 项目接收黑名单更新的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectMode {
+    /// 接收全局拦截与函数级拦截
+    All,
+    /// 只接收针对本项目函数的拦截
+    FunctionOnly,
+    /// 不推送
+    Skip,
+}
+
+impl FromStr for ProjectMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "all" => Ok(Self::All),
+            "function" => Ok(Self::FunctionOnly),
+            "skip" => Ok(Self::Skip),
+            other => Err(anyhow!("未知的项目模式: {}", other)),
+        }
+    }
+}
+
+/// 各项目的推送策略
+#[derive(Debug, Clone)]
+pub struct ProjectPolicy {
+    default_mode: ProjectMode,
+    overrides: HashMap<Address, ProjectMode>,
+    /// 未在注册表中登记、但仍需推送的项目
+    static_projects: Vec<Address>,
+}
+
+impl ProjectPolicy {
+    /// `PROJECT_DEFAULT_MODE` 为默认模式，`PROJECT_POLICY` 覆盖单个项目，
+    /// 格式如 `0xabc..=function,0xdef..=skip`；`PROJECT` 以逗号分隔列出固定推送的项目
+    pub fn from_env() -> Result<Self> {
+        Self::parse(
+            env::var("PROJECT_DEFAULT_MODE").ok().as_deref(),
+            env::var("PROJECT_POLICY").ok().as_deref(),
+            env::var("PROJECT").ok().as_deref(),
+        )
+    }
+
+    /// 按 `from_env` 的格式解析，参数为 None 时表示未设置
+    pub fn parse(
+        default_mode: Option<&str>,
+        policy: Option<&str>,
+        projects: Option<&str>,
+    ) -> Result<Self> {
+        let default_mode = match default_mode {
+            Some(mode) => mode.parse()?,
+            None => ProjectMode::All,
+        };
+        let mut overrides = HashMap::new();
+        if let Some(policy) = policy {
+            for item in policy.split(',').filter(|item| !item.trim().is_empty()) {
+                let (project, mode) = item
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("PROJECT_POLICY 格式错误: {}", item))?;
+                overrides.insert(project.trim().parse()?, mode.parse()?);
+            }
+        }
+        let static_projects = match projects {
+            Some(projects) => projects
+                .split(',')
+                .filter(|p| !p.trim().is_empty())
+                .map(|p| p.trim().parse())
+                .collect::<Result<_, _>>()?,
+            None => vec![],
+        };
+        Ok(Self {
+            default_mode,
+            overrides,
+            static_projects,
+        })
+    }
+
+    pub fn mode(&self, project: &Address) -> ProjectMode {
+        self.overrides
+            .get(project)
+            .copied()
+            .unwrap_or(self.default_mode)
+    }
+
+    /// 注册表发现的项目加上固定项目，去掉不推送的项目
+    pub fn targets(&self, discovered: &BTreeSet<Address>) -> Vec<(Address, ProjectMode)> {
+        let mut projects = discovered.clone();
+        projects.extend(self.static_projects.iter().copied());
+        projects
+            .into_iter()
+            .map(|project| (project, self.mode(&project)))
+            .filter(|(_, mode)| *mode != ProjectMode::Skip)
+            .collect()
+    }
+}
+
+/// 通过注册表的 RegisterInfo/RemoveModuleForProject 事件，
+/// 维护在至少一个受保护函数上启用了鉴权模块的项目
+#[derive(Debug, Clone)]
+pub struct ProjectDirectory {
+    auth_module: Address,
+    enabled: HashMap<(Address, FixedBytes<4>), bool>,
+    next_block: u64,
+}
+
+impl ProjectDirectory {
+    pub fn new(auth_module: Address, from_block: u64) -> Self {
+        Self {
+            auth_module,
+            enabled: HashMap::new(),
+            next_block: from_block,
+        }
+    }
+
+    /// 增量索引上次之后的事件
+    pub async fn refresh<P, T>(&mut self, provider: &P, registry: Address) -> Result<()>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let latest = provider.get_block_number().await?;
+        if self.next_block > latest {
+            return Ok(());
+        }
+        let filter = Filter::new().address(registry).event_signature(vec![
+            Registry::RegisterInfo::SIGNATURE_HASH,
+            Registry::RemoveModuleForProject::SIGNATURE_HASH,
+        ]);
+        for log in get_logs_chunked(provider, filter, self.next_block, latest).await? {
+            self.apply(&log);
+        }
+        self.next_block = latest + 1;
+        Ok(())
+    }
+
+    /// 按事件顺序更新：register 覆盖函数的模块列表，removeModuleForProject 移除单个模块
+    pub fn apply(&mut self, log: &Log) {
+        if let Ok(register) = log.log_decode::<Registry::RegisterInfo>() {
+            let event = register.inner.data;
+            self.enabled.insert(
+                (event.project, event.funcSig),
+                event.enableModules.contains(&self.auth_module),
+            );
+        } else if let Ok(remove) = log.log_decode::<Registry::RemoveModuleForProject>() {
+            let event = remove.inner.data;
+            if event.module_address == self.auth_module {
+                self.enabled.insert((event.project, event.funcSig), false);
+            }
+        }
+    }
+
+    pub fn projects(&self) -> BTreeSet<Address> {
+        self.enabled
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|((project, _), _)| *project)
+            .collect()
+    }
+}
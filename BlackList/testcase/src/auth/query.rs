@@ -0,0 +1,491 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{query::QueryAs, Database, Encode, Type};
+use std::{fmt, str::FromStr};
+
+use crate::auth::data::{BlacklistEntry, EntryStatus};
+
+/// 每页默认条数
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+/// 每页最多条数
+pub const MAX_PAGE_SIZE: u32 = 1000;
+
+/// LIKE 条件使用的转义字符，三种数据库都不把它当作默认转义字符
+pub(crate) const LIKE_ESCAPE: char = '!';
+
+/// 转义用户输入中的 `%`、`_` 与转义字符本身，使其按字面匹配，
+/// 条件中需带上 `ESCAPE '!'`
+pub(crate) fn escape_like(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '%' | '_' | LIKE_ESCAPE) {
+            escaped.push(LIKE_ESCAPE);
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 列表的排序字段，相同值之间再按 id 排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortField {
+    #[default]
+    Id,
+    AddedAt,
+    UpdatedAt,
+    Address,
+}
+
+impl SortField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::AddedAt => "added_at",
+            Self::UpdatedAt => "updated_at",
+            Self::Address => "address",
+        }
+    }
+
+    fn is_time(&self) -> bool {
+        matches!(self, Self::AddedAt | Self::UpdatedAt)
+    }
+
+    // 记录在该字段上的值，时间使用 RFC 3339
+    fn key(&self, entry: &BlacklistEntry) -> String {
+        match self {
+            Self::Id => entry.id.to_string(),
+            Self::AddedAt => entry.added_at.to_rfc3339(),
+            Self::UpdatedAt => entry.updated_at.to_rfc3339(),
+            Self::Address => entry.address.clone(),
+        }
+    }
+}
+
+impl FromStr for SortField {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "id" => Ok(Self::Id),
+            "added_at" => Ok(Self::AddedAt),
+            "updated_at" => Ok(Self::UpdatedAt),
+            "address" => Ok(Self::Address),
+            _ => Err(anyhow!("未知的排序字段: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl FromStr for SortOrder {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "asc" => Ok(Self::Asc),
+            "desc" => Ok(Self::Desc),
+            _ => Err(anyhow!("未知的排序方向: {}", s)),
+        }
+    }
+}
+
+/// 分页游标，记录上一页最后一条记录的排序值与 id，格式为 `id:排序值`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub id: i64,
+    pub key: String,
+}
+
+impl Cursor {
+    pub fn after(entry: &BlacklistEntry, sort: SortField) -> Self {
+        Self {
+            id: entry.id,
+            key: sort.key(entry),
+        }
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.id, self.key)
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (id, key) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("无效的分页游标: {}", s))?;
+        Ok(Self {
+            id: id.parse()?,
+            key: key.to_string(),
+        })
+    }
+}
+
+/// 一页查询结果
+#[derive(Debug)]
+pub struct BlacklistPage {
+    pub entries: Vec<BlacklistEntry>,
+    /// 还有下一页时的游标
+    pub next: Option<Cursor>,
+}
+
+/// 黑名单列表查询，各条件之间为 AND，未设置的条件不过滤。
+/// 分页使用游标而不是 OFFSET，翻页期间新增的记录不会导致重复或遗漏
+#[derive(Debug, Clone)]
+pub struct BlacklistQuery {
+    pub chain_type: Option<String>,
+    /// 为空时不按状态过滤
+    pub statuses: Vec<EntryStatus>,
+    /// 原因包含该关键字，例如 `Scam`
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    /// 有该检测器（或名单来源）的证据，例如 `reentrancy`、`ofac-sdn`
+    pub detector: Option<String>,
+    /// 加入时间不早于
+    pub added_after: Option<DateTime<Utc>>,
+    /// 加入时间早于
+    pub added_before: Option<DateTime<Utc>>,
+    /// 到期时间不早于，永久拉黑的记录不匹配
+    pub expires_after: Option<DateTime<Utc>>,
+    /// 到期时间早于，永久拉黑的记录不匹配
+    pub expires_before: Option<DateTime<Utc>>,
+    /// 地址前缀，不区分大小写，可省略 `0x`
+    pub address_prefix: Option<String>,
+    pub sort: SortField,
+    pub order: SortOrder,
+    pub limit: u32,
+    pub after: Option<Cursor>,
+}
+
+impl Default for BlacklistQuery {
+    fn default() -> Self {
+        Self {
+            chain_type: None,
+            statuses: vec![],
+            reason: None,
+            created_by: None,
+            detector: None,
+            added_after: None,
+            added_before: None,
+            expires_after: None,
+            expires_before: None,
+            address_prefix: None,
+            sort: SortField::default(),
+            order: SortOrder::default(),
+            limit: DEFAULT_PAGE_SIZE,
+            after: None,
+        }
+    }
+}
+
+impl BlacklistQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn chain_type(mut self, chain_type: &str) -> Self {
+        self.chain_type = Some(chain_type.to_string());
+        self
+    }
+
+    pub fn status(mut self, status: EntryStatus) -> Self {
+        self.statuses.push(status);
+        self
+    }
+
+    pub fn reason(mut self, keyword: &str) -> Self {
+        self.reason = Some(keyword.to_string());
+        self
+    }
+
+    pub fn created_by(mut self, created_by: &str) -> Self {
+        self.created_by = Some(created_by.to_string());
+        self
+    }
+
+    pub fn detector(mut self, detector: &str) -> Self {
+        self.detector = Some(detector.to_string());
+        self
+    }
+
+    pub fn added_between(
+        mut self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.added_after = after;
+        self.added_before = before;
+        self
+    }
+
+    pub fn expires_between(
+        mut self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.expires_after = after;
+        self.expires_before = before;
+        self
+    }
+
+    pub fn address_prefix(mut self, prefix: &str) -> Self {
+        self.address_prefix = Some(prefix.to_string());
+        self
+    }
+
+    pub fn sort_by(mut self, sort: SortField, order: SortOrder) -> Self {
+        self.sort = sort;
+        self.order = order;
+        self
+    }
+
+    /// 每页条数，限制在 1 到 `MAX_PAGE_SIZE` 之间
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn after(mut self, cursor: Cursor) -> Self {
+        self.after = Some(cursor);
+        self
+    }
+
+    fn page_size(&self) -> u32 {
+        self.limit.clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// WHERE 子句（含 `WHERE`，没有条件时为空）与按顺序绑定的参数；
+    /// `evidence_table` 为黑名单表对应的证据表，`paged` 为 true 时加入游标条件
+    pub(crate) fn filter(
+        &self,
+        dialect: Dialect,
+        evidence_table: &str,
+        paged: bool,
+    ) -> Result<(String, Vec<QueryArg>)> {
+        let mut filter = Filter::new(dialect);
+        if let Some(chain_type) = &self.chain_type {
+            let p = filter.bind(QueryArg::Text(chain_type.clone()));
+            filter.push(format!("chain_type = {p}"));
+        }
+        if !self.statuses.is_empty() {
+            let placeholders: Vec<String> = self
+                .statuses
+                .iter()
+                .map(|status| filter.bind(QueryArg::Text(status.as_str().to_string())))
+                .collect();
+            filter.push(format!("status IN ({})", placeholders.join(", ")));
+        }
+        if let Some(reason) = &self.reason {
+            let p = filter.bind(QueryArg::Text(format!("%{}%", escape_like(reason.trim()))));
+            filter.push(format!("reason LIKE {p} ESCAPE '{LIKE_ESCAPE}'"));
+        }
+        if let Some(created_by) = &self.created_by {
+            let p = filter.bind(QueryArg::Text(created_by.clone()));
+            filter.push(format!("created_by = {p}"));
+        }
+        if let Some(detector) = &self.detector {
+            let p = filter.bind(QueryArg::Text(detector.clone()));
+            filter.push(format!(
+                "id IN (SELECT entry_id FROM {evidence_table} WHERE detector = {p})"
+            ));
+        }
+        filter.time_range("added_at", self.added_after, self.added_before);
+        filter.time_range("expires_at", self.expires_after, self.expires_before);
+        if let Some(prefix) = &self.address_prefix {
+            let prefix = prefix.trim().to_lowercase();
+            let prefix = prefix.strip_prefix("0x").unwrap_or(&prefix);
+            let p = filter.bind(QueryArg::Text(format!("0x{}%", escape_like(prefix))));
+            filter.push(format!("address LIKE {p} ESCAPE '{LIKE_ESCAPE}'"));
+        }
+        if let (true, Some(cursor)) = (paged, &self.after) {
+            filter.keyset(self.sort, self.order, cursor)?;
+        }
+        Ok(filter.finish())
+    }
+
+    /// ORDER BY 与 LIMIT 子句，多取一条用于判断是否还有下一页
+    pub(crate) fn order_and_limit(&self, dialect: Dialect) -> String {
+        let direction = match self.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        let limit = self.page_size() + 1;
+        match self.sort {
+            SortField::Id => format!("ORDER BY id {direction} LIMIT {limit}"),
+            sort => format!(
+                "ORDER BY {} {direction}, id {direction} LIMIT {limit}",
+                dialect.column(sort)
+            ),
+        }
+    }
+
+    /// 截取一页结果并生成下一页的游标
+    pub(crate) fn page(&self, mut entries: Vec<BlacklistEntry>) -> BlacklistPage {
+        let size = self.page_size() as usize;
+        let next = if entries.len() > size {
+            entries.truncate(size);
+            entries.last().map(|entry| Cursor::after(entry, self.sort))
+        } else {
+            None
+        };
+        BlacklistPage { entries, next }
+    }
+}
+
+/// 查询条件中按顺序绑定的参数
+pub(crate) enum QueryArg {
+    Text(String),
+    Time(DateTime<Utc>),
+    Int(i64),
+}
+
+/// 各后端占位符与时间比较的差异
+#[derive(Debug, Clone, Copy)]
+pub enum Dialect {
+    MySql,
+    Postgres,
+    Sqlite,
+}
+
+impl Dialect {
+    fn placeholder(&self, index: usize) -> String {
+        match self {
+            Self::MySql => "?".to_string(),
+            Self::Postgres => format!("${index}"),
+            Self::Sqlite => format!("?{index}"),
+        }
+    }
+
+    // SQLite 的时间以文本存储，格式不统一，比较前先转换
+    pub(crate) fn time(&self, expr: &str) -> String {
+        match self {
+            Self::Sqlite => format!("datetime({expr})"),
+            _ => expr.to_string(),
+        }
+    }
+
+    fn column(&self, sort: SortField) -> String {
+        if sort.is_time() {
+            self.time(sort.as_str())
+        } else {
+            sort.as_str().to_string()
+        }
+    }
+}
+
+struct Filter {
+    dialect: Dialect,
+    clauses: Vec<String>,
+    args: Vec<QueryArg>,
+}
+
+impl Filter {
+    fn new(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            clauses: vec![],
+            args: vec![],
+        }
+    }
+
+    fn bind(&mut self, arg: QueryArg) -> String {
+        self.args.push(arg);
+        self.dialect.placeholder(self.args.len())
+    }
+
+    fn push(&mut self, clause: String) {
+        self.clauses.push(clause);
+    }
+
+    fn time_range(
+        &mut self,
+        column: &str,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) {
+        let expr = self.dialect.time(column);
+        if let Some(after) = after {
+            let p = self.bind(QueryArg::Time(after));
+            let p = self.dialect.time(&p);
+            self.push(format!("{expr} >= {p}"));
+        }
+        if let Some(before) = before {
+            let p = self.bind(QueryArg::Time(before));
+            let p = self.dialect.time(&p);
+            self.push(format!("{expr} < {p}"));
+        }
+    }
+
+    // 排序值与 id 组成的键严格位于游标之后
+    fn keyset(&mut self, sort: SortField, order: SortOrder, cursor: &Cursor) -> Result<()> {
+        let op = match order {
+            SortOrder::Asc => ">",
+            SortOrder::Desc => "<",
+        };
+        if sort == SortField::Id {
+            let p = self.bind(QueryArg::Int(cursor.id));
+            self.push(format!("id {op} {p}"));
+            return Ok(());
+        }
+        let key = |filter: &mut Self| -> Result<String> {
+            let p = if sort.is_time() {
+                let time = DateTime::parse_from_rfc3339(&cursor.key)
+                    .map_err(|_| anyhow!("无效的分页游标: {}", cursor))?;
+                filter.bind(QueryArg::Time(time.with_timezone(&Utc)))
+            } else {
+                filter.bind(QueryArg::Text(cursor.key.clone()))
+            };
+            Ok(if sort.is_time() {
+                filter.dialect.time(&p)
+            } else {
+                p
+            })
+        };
+        let column = self.dialect.column(sort);
+        let first = key(self)?;
+        let second = key(self)?;
+        let id = self.bind(QueryArg::Int(cursor.id));
+        self.push(format!(
+            "({column} {op} {first} OR ({column} = {second} AND id {op} {id}))"
+        ));
+        Ok(())
+    }
+
+    fn finish(self) -> (String, Vec<QueryArg>) {
+        if self.clauses.is_empty() {
+            (String::new(), self.args)
+        } else {
+            (format!("WHERE {}", self.clauses.join(" AND ")), self.args)
+        }
+    }
+}
+
+/// 按顺序绑定查询参数
+pub(crate) fn bind_args<'q, DB, O>(
+    mut query: QueryAs<'q, DB, O, <DB as Database>::Arguments<'q>>,
+    args: Vec<QueryArg>,
+) -> QueryAs<'q, DB, O, <DB as Database>::Arguments<'q>>
+where
+    DB: Database,
+    String: Encode<'q, DB> + Type<DB>,
+    DateTime<Utc>: Encode<'q, DB> + Type<DB>,
+    i64: Encode<'q, DB> + Type<DB>,
+{
+    for arg in args {
+        query = match arg {
+            QueryArg::Text(value) => query.bind(value),
+            QueryArg::Time(value) => query.bind(value),
+            QueryArg::Int(value) => query.bind(value),
+        };
+    }
+    query
+}
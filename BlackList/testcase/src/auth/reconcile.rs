@@ -0,0 +1,316 @@
+use alloy::{
+    primitives::{keccak256, Address, FixedBytes, B256, U256},
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    sol,
+    sol_types::{SolEvent, SolValue},
+    transports::Transport,
+};
+use anyhow::Result;
+use std::collections::{BTreeSet, HashSet};
+
+use crate::auth::{
+    payload::{AuthModuleCall, BatchSetInfoPayload, RemoveInfoPayload, SetInfoPayload},
+    scope::FunctionScope,
+    sync::env_or,
+};
+
+sol! {
+    /// 鉴权模块的黑名单变更事件
+    interface IAuthModuleEvents {
+        event AddBlackAddr(address project, address blackAddr);
+        event RemoveBlackAddr(address project, address blackAddr);
+    }
+}
+
+/// `AuthModule.functionAccessBlacklist` 所在的存储槽位
+const FUNCTION_BLACKLIST_SLOT: u64 = 0;
+/// `AuthModule.globalAccessBlacklist` 所在的存储槽位
+const GLOBAL_BLACKLIST_SLOT: u64 = 1;
+/// `AuthModule.blackListMode` 所在的存储槽位
+const BLACK_LIST_MODE_SLOT: u64 = 2;
+
+/// 单个项目的对账结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReconcilePlan {
+    pub project: Address,
+    /// 数据库中有效但链上缺失，需要补充拉黑
+    pub to_add: Vec<Address>,
+    /// 链上存在但数据库中已无有效记录，需要移除
+    pub to_remove: Vec<Address>,
+    /// 函数级拦截的差异，与全局拦截分开对账
+    pub scopes_to_add: Vec<FunctionScope>,
+    pub scopes_to_remove: Vec<FunctionScope>,
+}
+
+impl ReconcilePlan {
+    /// 根据数据库有效地址与链上实际状态计算差异
+    pub fn diff(project: Address, db: &HashSet<Address>, onchain: &HashSet<Address>) -> Self {
+        // 排序保证输出稳定
+        let to_add: BTreeSet<_> = db.difference(onchain).copied().collect();
+        let to_remove: BTreeSet<_> = onchain.difference(db).copied().collect();
+        Self {
+            project,
+            to_add: to_add.into_iter().collect(),
+            to_remove: to_remove.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// 加入函数级拦截的差异，按函数与地址排序
+    pub fn with_scopes(
+        mut self,
+        db: &HashSet<FunctionScope>,
+        onchain: &HashSet<FunctionScope>,
+    ) -> Self {
+        self.scopes_to_add = db.difference(onchain).copied().collect();
+        self.scopes_to_remove = onchain.difference(db).copied().collect();
+        for scopes in [&mut self.scopes_to_add, &mut self.scopes_to_remove] {
+            scopes.sort_by_key(|scope| (scope.selector, scope.address));
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_add.is_empty()
+            && self.to_remove.is_empty()
+            && self.scopes_to_add.is_empty()
+            && self.scopes_to_remove.is_empty()
+    }
+
+    /// 修复差异所需的最少调用：一次批量添加与逐个函数级添加，加上逐个移除
+    pub fn calls(&self) -> Vec<AuthModuleCall> {
+        let mut calls = vec![];
+        if !self.to_add.is_empty() {
+            calls.push(AuthModuleCall::BatchSetInfo(BatchSetInfoPayload::new(
+                self.project,
+                self.to_add.clone(),
+            )));
+        }
+        for scope in &self.scopes_to_add {
+            calls.push(AuthModuleCall::SetInfo(SetInfoPayload::function(
+                scope.project,
+                scope.selector,
+                scope.address,
+                true,
+            )));
+        }
+        for addr in &self.to_remove {
+            calls.push(AuthModuleCall::RemoveInfo(RemoveInfoPayload::new(
+                self.project,
+                Default::default(),
+                *addr,
+            )));
+        }
+        for scope in &self.scopes_to_remove {
+            calls.push(AuthModuleCall::RemoveInfo(RemoveInfoPayload::new(
+                scope.project,
+                scope.selector,
+                scope.address,
+            )));
+        }
+        calls
+    }
+}
+
+/// 通过 AddBlackAddr/RemoveBlackAddr 事件收集项目在链上出现过的黑名单地址
+pub async fn index_blacklist_events<P, T>(
+    provider: &P,
+    auth_module: Address,
+    project: Address,
+    from_block: u64,
+) -> Result<HashSet<Address>>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let latest = provider.get_block_number().await?;
+    let filter = Filter::new().address(auth_module).event_signature(vec![
+        IAuthModuleEvents::AddBlackAddr::SIGNATURE_HASH,
+        IAuthModuleEvents::RemoveBlackAddr::SIGNATURE_HASH,
+    ]);
+    let mut addresses = HashSet::new();
+
+    for log in get_logs_chunked(provider, filter, from_block, latest).await? {
+        let event = match log.log_decode::<IAuthModuleEvents::AddBlackAddr>() {
+            Ok(add) => (add.inner.project, add.inner.blackAddr),
+            Err(_) => match log.log_decode::<IAuthModuleEvents::RemoveBlackAddr>() {
+                Ok(remove) => (remove.inner.project, remove.inner.blackAddr),
+                Err(_) => continue,
+            },
+        };
+        if event.0 == project {
+            addresses.insert(event.1);
+        }
+    }
+
+    Ok(addresses)
+}
+
+/// 按 `RECONCILE_BLOCK_RANGE` 分段查询日志，避免节点限制单次查询的区块范围
+pub async fn get_logs_chunked<P, T>(
+    provider: &P,
+    filter: Filter,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<Log>>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let range: u64 = env_or::<u64>("RECONCILE_BLOCK_RANGE", 10_000).max(1);
+    let mut logs = vec![];
+
+    let mut start = from_block;
+    while start <= to_block {
+        let end = (start + range - 1).min(to_block);
+        let filter = filter.clone().from_block(start).to_block(end);
+        logs.extend(provider.get_logs(&filter).await?);
+        start = end + 1;
+    }
+
+    Ok(logs)
+}
+
+/// `globalAccessBlacklist[project][addr]` 的存储槽位
+pub fn global_blacklist_slot(project: Address, addr: Address) -> U256 {
+    let inner: B256 = keccak256((project, U256::from(GLOBAL_BLACKLIST_SLOT)).abi_encode());
+    keccak256((addr, inner).abi_encode()).into()
+}
+
+/// `functionAccessBlacklist[project][selector][addr]` 的存储槽位
+pub fn function_blacklist_slot(project: Address, selector: FixedBytes<4>, addr: Address) -> U256 {
+    let project_slot: B256 = keccak256((project, U256::from(FUNCTION_BLACKLIST_SLOT)).abi_encode());
+    let selector_slot: B256 = keccak256((selector, project_slot).abi_encode());
+    keccak256((addr, selector_slot).abi_encode()).into()
+}
+
+/// `blackListMode[project]` 的存储槽位
+pub fn black_list_mode_slot(project: Address) -> U256 {
+    keccak256((project, U256::from(BLACK_LIST_MODE_SLOT)).abi_encode()).into()
+}
+
+/// 解码 `(isFuctionAccessBlacklist, isGlobalAccessBlacklist)`：
+/// 两个 bool 打包在同一个槽位的最低两个字节
+pub fn decode_black_list_mode(value: U256) -> (bool, bool) {
+    (value.byte(0) != 0, value.byte(1) != 0)
+}
+
+pub async fn is_globally_blacklisted<P, T>(
+    provider: &P,
+    auth_module: Address,
+    project: Address,
+    addr: Address,
+) -> Result<bool>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let value = provider
+        .get_storage_at(auth_module, global_blacklist_slot(project, addr))
+        .await?;
+    Ok(!value.is_zero())
+}
+
+pub async fn is_function_blacklisted<P, T>(
+    provider: &P,
+    auth_module: Address,
+    scope: &FunctionScope,
+) -> Result<bool>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let slot = function_blacklist_slot(scope.project, scope.selector, scope.address);
+    let value = provider.get_storage_at(auth_module, slot).await?;
+    Ok(!value.is_zero())
+}
+
+/// 读取项目的拦截模式 `(isFuctionAccessBlacklist, isGlobalAccessBlacklist)`
+pub async fn black_list_mode<P, T>(
+    provider: &P,
+    auth_module: Address,
+    project: Address,
+) -> Result<(bool, bool)>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let value = provider
+        .get_storage_at(auth_module, black_list_mode_slot(project))
+        .await?;
+    Ok(decode_black_list_mode(value))
+}
+
+/// 函数级拦截的候选：项目在数据库中的函数级记录，加上事件中出现过的地址与这些函数的组合。
+/// 事件不带函数选择器，只能用已知的函数补全
+pub fn scope_candidates(
+    project: Address,
+    events: &HashSet<Address>,
+    known: &[FunctionScope],
+) -> HashSet<FunctionScope> {
+    let known: Vec<_> = known
+        .iter()
+        .filter(|scope| scope.project == project)
+        .collect();
+    let selectors: BTreeSet<_> = known.iter().map(|scope| scope.selector).collect();
+    let mut candidates: HashSet<_> = known.into_iter().copied().collect();
+    for address in events {
+        for selector in &selectors {
+            candidates.insert(FunctionScope {
+                address: *address,
+                project,
+                selector: *selector,
+            });
+        }
+    }
+    candidates
+}
+
+/// 重建项目的链上全局黑名单。事件只能给出候选地址：setInfo 以 isblack=false 调用时同样
+/// 触发 AddBlackAddr，batchSetInfo 则不触发事件，因此调用方传入事件中出现过的地址与
+/// 数据库地址，逐个读取存储确认实际状态
+pub async fn onchain_blacklist<P, T>(
+    provider: &P,
+    auth_module: Address,
+    project: Address,
+    candidates: impl IntoIterator<Item = Address>,
+) -> Result<HashSet<Address>>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let mut onchain = HashSet::new();
+    for addr in candidates {
+        if is_globally_blacklisted(provider, auth_module, project, addr).await? {
+            onchain.insert(addr);
+        }
+    }
+    Ok(onchain)
+}
+
+/// 重建项目的链上函数级黑名单。项目未开启函数模式时 setInfo 写入的是全局黑名单，
+/// 此时以全局黑名单的状态为准
+pub async fn onchain_function_blacklist<P, T>(
+    provider: &P,
+    auth_module: Address,
+    function_mode: bool,
+    candidates: impl IntoIterator<Item = FunctionScope>,
+) -> Result<HashSet<FunctionScope>>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let mut onchain = HashSet::new();
+    for scope in candidates {
+        let listed = if function_mode {
+            is_function_blacklisted(provider, auth_module, &scope).await?
+        } else {
+            is_globally_blacklisted(provider, auth_module, scope.project, scope.address).await?
+        };
+        if listed {
+            onchain.insert(scope);
+        }
+    }
+    Ok(onchain)
+}
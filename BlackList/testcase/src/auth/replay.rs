@@ -0,0 +1,105 @@
+use alloy::{
+    primitives::{Address, BlockHash},
+    providers::Provider,
+    rpc::types::{trace::geth::TraceResult, BlockTransactionsKind, Header},
+    transports::Transport,
+};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::auth::{
+    auth::{detect, record_findings},
+    evidence::{Evidence, Severity},
+    expiry::ExpiryPolicy,
+    pause::EscalationPolicy,
+    store::BlacklistStore,
+};
+
+/// 录制的区块：区块头以及 debug_traceBlockByHash 的原始返回
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockRecord {
+    pub header: Header,
+    pub traces: Vec<TraceResult>,
+}
+
+impl BlockRecord {
+    /// 文件名以补零的区块号开头，按文件名排序即为区块顺序
+    pub fn file_name(&self) -> String {
+        format!("{:012}-{}.json", self.header.number, self.header.hash)
+    }
+}
+
+// 拉取区块头，与 trace 一起写入录制目录
+pub async fn record_block<P, T>(
+    provider: &P,
+    dir: &Path,
+    block_hash: BlockHash,
+    traces: &[TraceResult],
+) -> Result<PathBuf>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let block = provider
+        .get_block_by_hash(block_hash, BlockTransactionsKind::Hashes)
+        .await?
+        .ok_or_else(|| anyhow!("block {} not found", block_hash))?;
+    let record = BlockRecord {
+        header: block.header,
+        traces: traces.to_vec(),
+    };
+    save_record(dir, &record)
+}
+
+pub fn save_record(dir: &Path, record: &BlockRecord) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(record.file_name());
+    fs::write(&path, serde_json::to_vec_pretty(record)?)?;
+    Ok(path)
+}
+
+// 读取目录下所有录制文件，按区块号排序
+pub fn load_records(dir: &Path) -> Result<Vec<BlockRecord>> {
+    let mut records = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let record: BlockRecord = serde_json::from_slice(&fs::read(&path)?)
+                .map_err(|err| anyhow!("{}: {}", path.display(), err))?;
+            records.push(record);
+        }
+    }
+    records.sort_by_key(|record| (record.header.number, record.header.hash));
+    Ok(records)
+}
+
+/// 离线回放录制的区块，走与在线监控相同的检测流程；
+/// 传入存储后端时检测结果同样写入黑名单表
+pub async fn replay(dir: &Path, store: Option<&dyn BlacklistStore>) -> Result<Vec<Address>> {
+    let policy = ExpiryPolicy::from_env()?;
+    let escalation = EscalationPolicy::from_env()?;
+    let mut black_list = vec![];
+    for record in load_records(dir)? {
+        let number = record.header.number;
+        let hash = record.header.hash;
+        let findings = detect(record.traces)?;
+        println!("区块 {} ({}): {:?}", number, hash, findings);
+        let found: Vec<Address> = findings.iter().map(|f| f.address).collect();
+        if let Some(store) = store {
+            let evidence: Vec<Evidence> = findings
+                .iter()
+                .map(|finding| {
+                    let severity = Severity::of(finding, &escalation);
+                    Evidence::new(finding, Some(number), hash, severity)
+                })
+                .collect();
+            record_findings(store, &policy, &evidence).await;
+        }
+        black_list.extend(found);
+    }
+    Ok(black_list)
+}
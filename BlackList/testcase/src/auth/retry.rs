@@ -0,0 +1,82 @@
+use std::{collections::HashMap, hash::Hash, time::Duration};
+use tokio::time::Instant;
+
+use crate::auth::sync::env_or;
+
+#[derive(Debug, Clone)]
+struct Failure {
+    /// 连续失败次数
+    count: u32,
+    retry_at: Instant,
+    /// 已告警的错误，相同错误不再重复告警
+    alerted: Option<String>,
+}
+
+/// 同步失败的目标按指数退避重试，成功后重置
+#[derive(Debug, Clone)]
+pub struct RetryBackoff<K> {
+    /// 第一次失败后的等待时间，之后每次翻倍
+    pub base: Duration,
+    /// 等待时间上限
+    pub max: Duration,
+    failures: HashMap<K, Failure>,
+}
+
+impl<K: Hash + Eq> RetryBackoff<K> {
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max: max.max(base),
+            failures: HashMap::new(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            Duration::from_secs(env_or("SYNC_BACKOFF_BASE_SECS", 5)),
+            Duration::from_secs(env_or("SYNC_BACKOFF_MAX_SECS", 600)),
+        )
+    }
+
+    /// 目标没有失败记录或已过退避时间
+    pub fn ready(&self, key: &K, now: Instant) -> bool {
+        self.failures
+            .get(key)
+            .is_none_or(|failure| now >= failure.retry_at)
+    }
+
+    /// 记录一次失败，返回下次重试前的等待时间
+    pub fn failed(&mut self, key: K, now: Instant) -> Duration {
+        let failure = self.failures.entry(key).or_insert(Failure {
+            count: 0,
+            retry_at: now,
+            alerted: None,
+        });
+        failure.count = failure.count.saturating_add(1);
+        let delay = self
+            .base
+            .saturating_mul(2u32.saturating_pow(failure.count - 1))
+            .min(self.max);
+        failure.retry_at = now + delay;
+        delay
+    }
+
+    /// 同步成功，清除退避与告警记录
+    pub fn succeeded(&mut self, key: &K) {
+        self.failures.remove(key);
+    }
+
+    /// 目标上首次出现该错误时返回 true；同一错误在目标恢复前只告警一次
+    pub fn should_alert(&mut self, key: K, error: &str) -> bool {
+        let failure = self.failures.entry(key).or_insert(Failure {
+            count: 0,
+            retry_at: Instant::now(),
+            alerted: None,
+        });
+        if failure.alerted.as_deref() == Some(error) {
+            return false;
+        }
+        failure.alerted = Some(error.to_string());
+        true
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::{anyhow, Result};
+use sqlx::migrate::Migrator;
+
+/// 编译进程序的数据库迁移，按后端分别位于 BlackList/migrations 下
+pub static MYSQL_MIGRATOR: Migrator = sqlx::migrate!("../migrations/mysql");
+pub static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("../migrations/postgres");
+pub static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("../migrations/sqlite");
+
+/// 查询已应用的最新迁移版本
+pub const APPLIED_VERSION_QUERY: &str =
+    "SELECT MAX(version) FROM _sqlx_migrations WHERE success = TRUE";
+
+/// 迁移记录表是否存在，不存在表示数据库尚未初始化
+pub const MYSQL_MIGRATIONS_TABLE_QUERY: &str = "SELECT COUNT(*) FROM information_schema.tables \
+     WHERE table_schema = DATABASE() AND table_name = '_sqlx_migrations'";
+pub const POSTGRES_MIGRATIONS_TABLE_QUERY: &str = "SELECT COUNT(*) FROM information_schema.tables \
+     WHERE table_schema = current_schema() AND table_name = '_sqlx_migrations'";
+pub const SQLITE_MIGRATIONS_TABLE_QUERY: &str =
+    "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'";
+
+/// 程序要求的 schema 版本，即最新迁移的版本号
+pub fn expected_version(migrator: &Migrator) -> i64 {
+    migrator
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0)
+}
+
+/// 比较数据库已应用的版本与程序要求的版本，`applied` 为 None 表示尚未迁移
+pub fn check_version(applied: Option<i64>, migrator: &Migrator) -> Result<()> {
+    let expected = expected_version(migrator);
+    match applied {
+        Some(version) if version == expected => Ok(()),
+        Some(version) if version < expected => Err(anyhow!(
+            "数据库 schema 版本 {} 低于程序要求的 {}，请先运行 migrate 命令",
+            version,
+            expected
+        )),
+        Some(version) => Err(anyhow!(
+            "数据库 schema 版本 {} 高于程序要求的 {}，请升级程序",
+            version,
+            expected
+        )),
+        None => Err(anyhow!("数据库尚未初始化，请先运行 migrate 命令")),
+    }
+}
@@ -0,0 +1,42 @@
+use alloy::primitives::{Address, FixedBytes};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::data::SHADOW_BLACKLIST_TABLE;
+
+/// 函数级黑名单表：一个黑名单地址可以只在某个项目的某些函数上被拦截
+pub const FUNCTION_SCOPE_TABLE: &str = "blacklist_function_scope";
+/// 影子模式下的函数级黑名单表
+pub const SHADOW_FUNCTION_SCOPE_TABLE: &str = "blacklist_function_scope_shadow";
+
+/// 地址在某个项目上被拦截的函数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FunctionScope {
+    pub address: Address,
+    pub project: Address,
+    pub selector: FixedBytes<4>,
+}
+
+/// 黑名单表对应的函数级黑名单表
+pub fn scope_table(table: &str) -> &'static str {
+    if table == SHADOW_BLACKLIST_TABLE {
+        SHADOW_FUNCTION_SCOPE_TABLE
+    } else {
+        FUNCTION_SCOPE_TABLE
+    }
+}
+
+// 读取 (address, project, func_sig, synced) 行，忽略无法解析的记录
+pub(crate) fn parse_rows(rows: Vec<(String, String, String, bool)>) -> Vec<(FunctionScope, bool)> {
+    rows.into_iter()
+        .filter_map(|(address, project, func_sig, synced)| {
+            Some((
+                FunctionScope {
+                    address: address.parse().ok()?,
+                    project: project.parse().ok()?,
+                    selector: func_sig.parse().ok()?,
+                },
+                synced,
+            ))
+        })
+        .collect()
+}
@@ -0,0 +1,187 @@
+use alloy::{
+    consensus::SignableTransaction,
+    hex,
+    network::{EthereumWallet, TxSigner},
+    primitives::{Address, PrimitiveSignature as Signature},
+    signers::{local::PrivateKeySigner, Error as SignerError},
+    transports::http::reqwest::{Client, Url},
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{env, fs, path::PathBuf};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixStream,
+};
+
+/// 签名方式。按 `REMOTE_SIGNER`、`KEYSTORE`、`PK` 的顺序选择第一个已配置的
+#[derive(Debug, Clone)]
+pub enum SignerConfig {
+    /// 明文私钥，仅用于测试环境
+    PrivateKey(String),
+    /// 加密的 JSON keystore，密码从 `KEYSTORE_PASSWORD_FILE` 读取，未设置时在终端输入
+    Keystore {
+        path: PathBuf,
+        password_file: Option<PathBuf>,
+    },
+    /// 委托给本地签名服务，私钥不进入更新程序的环境
+    Remote {
+        endpoint: SignerEndpoint,
+        address: Address,
+    },
+}
+
+/// 签名服务的地址：`http(s)://...` 或 `unix:///path/to/socket`
+#[derive(Debug, Clone)]
+pub enum SignerEndpoint {
+    Http(Url),
+    Unix(PathBuf),
+}
+
+impl SignerEndpoint {
+    pub fn parse(endpoint: &str) -> Result<Self> {
+        match endpoint.strip_prefix("unix://") {
+            Some(path) => Ok(Self::Unix(PathBuf::from(path))),
+            None => Ok(Self::Http(endpoint.parse()?)),
+        }
+    }
+}
+
+impl SignerConfig {
+    /// 未配置任何签名方式时返回 None
+    pub fn from_env() -> Result<Option<Self>> {
+        if let Ok(endpoint) = env::var("REMOTE_SIGNER") {
+            return Ok(Some(Self::Remote {
+                endpoint: SignerEndpoint::parse(&endpoint)?,
+                address: env::var("REMOTE_SIGNER_ADDRESS")?.parse()?,
+            }));
+        }
+        if let Ok(path) = env::var("KEYSTORE") {
+            return Ok(Some(Self::Keystore {
+                path: PathBuf::from(path),
+                password_file: env::var("KEYSTORE_PASSWORD_FILE").ok().map(PathBuf::from),
+            }));
+        }
+        Ok(env::var("PK").ok().map(Self::PrivateKey))
+    }
+
+    pub fn into_wallet(self) -> Result<EthereumWallet> {
+        match self {
+            Self::PrivateKey(pk) => {
+                let signer: PrivateKeySigner = pk.parse()?;
+                Ok(EthereumWallet::from(signer))
+            }
+            Self::Keystore {
+                path,
+                password_file,
+            } => {
+                let password = match password_file {
+                    Some(file) => fs::read_to_string(file)?.trim_end().to_string(),
+                    None => rpassword::prompt_password(format!("{} 的密码: ", path.display()))?,
+                };
+                let signer = PrivateKeySigner::decrypt_keystore(&path, password)
+                    .map_err(|err| anyhow!("解密 keystore {} 失败: {}", path.display(), err))?;
+                Ok(EthereumWallet::from(signer))
+            }
+            Self::Remote { endpoint, address } => {
+                Ok(EthereumWallet::new(RemoteSigner::new(endpoint, address)))
+            }
+        }
+    }
+}
+
+/// 发往签名服务的请求：待签名的编码以及其哈希
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignRequest {
+    pub address: Address,
+    pub chain_id: Option<u64>,
+    pub payload: String,
+    pub hash: String,
+}
+
+/// 签名服务返回 65 字节的 r || s || v
+#[derive(Debug, Deserialize)]
+pub struct SignResponse {
+    pub signature: String,
+}
+
+/// 通过 HTTP 或 Unix socket 请求外部服务签名。Unix socket 上每个请求与响应各占一行 JSON
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    endpoint: SignerEndpoint,
+    address: Address,
+    client: Client,
+}
+
+impl RemoteSigner {
+    pub fn new(endpoint: SignerEndpoint, address: Address) -> Self {
+        Self {
+            endpoint,
+            address,
+            client: Client::new(),
+        }
+    }
+
+    async fn request(&self, request: &SignRequest) -> Result<SignResponse> {
+        let body = serde_json::to_vec(request)?;
+        let response = match &self.endpoint {
+            SignerEndpoint::Http(url) => {
+                let response = self
+                    .client
+                    .post(url.clone())
+                    .header("Content-Type", "application/json")
+                    .body(body)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                response.bytes().await?.to_vec()
+            }
+            SignerEndpoint::Unix(path) => {
+                let mut stream = UnixStream::connect(path).await?;
+                stream.write_all(&body).await?;
+                stream.write_all(b"\n").await?;
+                let mut line = String::new();
+                BufReader::new(stream).read_line(&mut line).await?;
+                line.into_bytes()
+            }
+        };
+        Ok(serde_json::from_slice(&response)?)
+    }
+}
+
+#[async_trait]
+impl TxSigner<Signature> for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> alloy::signers::Result<Signature> {
+        let request = SignRequest {
+            address: self.address,
+            chain_id: tx.chain_id(),
+            payload: hex::encode_prefixed(tx.encoded_for_signing()),
+            hash: tx.signature_hash().to_string(),
+        };
+        let response = self.request(&request).await.map_err(SignerError::other)?;
+        let bytes = hex::decode(&response.signature).map_err(SignerError::other)?;
+        let signature = Signature::try_from(bytes.as_slice()).map_err(SignerError::other)?;
+
+        // 校验签名确实来自配置的地址
+        let recovered = signature
+            .recover_address_from_prehash(&tx.signature_hash())
+            .map_err(SignerError::other)?;
+        if recovered != self.address {
+            return Err(SignerError::other(anyhow!(
+                "签名服务返回的签名来自 {}，期望 {}",
+                recovered,
+                self.address
+            )));
+        }
+        Ok(signature)
+    }
+}
@@ -0,0 +1,206 @@
+use alloy::primitives::{Address, TxHash};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::{env, sync::Arc};
+
+use crate::auth::{
+    allowlist::{AllowlistEntry, NewAllowlistEntry},
+    data::{BlacklistEntry, EntryStatus, NewBlacklistEntry, UpsertOutcome},
+    evidence::{Evidence, EvidenceRecord},
+    history::{ChangeContext, HistoryRecord},
+    pause::{PauseRecord, PauseTarget},
+    query::{BlacklistPage, BlacklistQuery},
+    scope::FunctionScope,
+    sync::{SyncStatus, UnsyncedAddress},
+};
+
+mod mysql;
+mod postgres;
+mod sql;
+mod sqlite;
+
+pub use mysql::MySqlStore;
+pub use postgres::PostgresStore;
+pub use sql::{Backend, SqlStore};
+pub use sqlite::SqliteStore;
+
+/// 黑名单与更新程序各表的存储后端，每个实例对应一张黑名单表（生产表或影子表）
+/// 及其函数级黑名单表，同步状态、nonce 与暂停记录两种模式共用。
+/// 写操作与变更历史在同一事务中提交
+#[async_trait]
+pub trait BlacklistStore: Send + Sync {
+    /// 执行该后端尚未应用的迁移
+    async fn migrate(&self) -> Result<()>;
+
+    /// 检查数据库已迁移到程序要求的版本
+    async fn verify_schema(&self) -> Result<()>;
+
+    /// 新增记录，地址在白名单中时返回错误
+    async fn create(&self, entry: &NewBlacklistEntry, context: &ChangeContext) -> Result<()>;
+
+    /// 插入或合并记录，依赖 (chain_type, address) 唯一键保证并发写入时只有一条记录。
+    /// 地址已存在时追加新的原因，状态按 `EntryStatus::on_redetect` 处理，
+    /// 并把到期时间延长到两者中较晚的一个，任一方为永久拉黑时保持永久。
+    /// 地址在白名单中时不写入，返回 `UpsertOutcome::Allowlisted`
+    async fn upsert(
+        &self,
+        entry: &NewBlacklistEntry,
+        context: &ChangeContext,
+    ) -> Result<UpsertOutcome, sqlx::Error>;
+
+    async fn get(&self, id: i64) -> Result<Option<BlacklistEntry>, sqlx::Error>;
+
+    /// 按地址查找，不区分链
+    async fn find(&self, address: &Address) -> Result<Option<BlacklistEntry>, sqlx::Error>;
+
+    /// 按 (链类型, 地址) 查找
+    async fn find_in_chain(
+        &self,
+        chain_type: &str,
+        address: &Address,
+    ) -> Result<Option<BlacklistEntry>, sqlx::Error>;
+
+    async fn list(&self) -> Result<Vec<BlacklistEntry>, sqlx::Error>;
+
+    /// 按条件分页列出记录，下一页从 `BlacklistPage::next` 继续
+    async fn query(&self, query: &BlacklistQuery) -> Result<BlacklistPage>;
+
+    /// 满足条件的记录数，不受分页影响
+    async fn count(&self, query: &BlacklistQuery) -> Result<i64>;
+
+    /// 状态为 active 的黑名单地址
+    async fn list_active_addresses(&self) -> Result<Vec<String>, sqlx::Error>;
+
+    /// 已到期但仍为 active 的记录
+    async fn list_expired(&self, now: DateTime<Utc>) -> Result<Vec<BlacklistEntry>, sqlx::Error>;
+
+    /// 修改状态与到期时间，不允许的状态转换返回 `InvalidTransition`
+    async fn update(
+        &self,
+        id: i64,
+        status: EntryStatus,
+        expires_at: Option<DateTime<Utc>>,
+        context: &ChangeContext,
+    ) -> Result<()>;
+
+    async fn delete(&self, id: i64, context: &ChangeContext) -> Result<(), sqlx::Error>;
+
+    async fn exists(&self, address: &Address) -> Result<bool, sqlx::Error>;
+
+    /// 地址以关键字开头或原因包含关键字的记录
+    async fn search(&self, keyword: &str) -> Result<Vec<BlacklistEntry>, sqlx::Error>;
+
+    /// 为攻击地址对应的记录追加证据，同一交易同一检测器只记录一次；返回是否新增
+    async fn add_evidence(&self, evidence: &Evidence) -> Result<bool, sqlx::Error>;
+
+    /// 记录的全部证据，按写入顺序排列
+    async fn list_evidence(&self, entry_id: i64) -> Result<Vec<EvidenceRecord>, sqlx::Error>;
+
+    /// 地址的完整变更时间线，包括已删除的记录
+    async fn history(&self, address: &Address) -> Result<Vec<HistoryRecord>, sqlx::Error>;
+
+    /// 加入白名单，已存在时更新原因与负责人
+    async fn allow(&self, entry: &NewAllowlistEntry) -> Result<(), sqlx::Error>;
+
+    /// 移出白名单，返回地址原本是否在白名单中
+    async fn disallow(&self, address: &Address) -> Result<bool, sqlx::Error>;
+
+    async fn list_allowlist(&self) -> Result<Vec<AllowlistEntry>, sqlx::Error>;
+
+    /// 白名单地址的检测结果被抑制时计数
+    async fn record_suppressed(&self, address: &Address) -> Result<(), sqlx::Error>;
+
+    /// 新检测到的地址在 project 上记为待同步，已存在的记录保持原状态
+    async fn mark_pending(
+        &self,
+        project: Address,
+        addresses: &[Address],
+    ) -> Result<(), sqlx::Error>;
+
+    /// 更新地址在 project 上的同步状态，记为 submitted 时累加发送次数
+    async fn set_sync_status(
+        &self,
+        project: Address,
+        addresses: &[Address],
+        status: SyncStatus,
+        tx_hash: Option<TxHash>,
+        nonce: Option<u64>,
+    ) -> Result<(), sqlx::Error>;
+
+    /// 所有未确认的地址，重启时用来恢复同步队列
+    async fn load_unsynced(&self) -> Result<Vec<UnsyncedAddress>, sqlx::Error>;
+
+    /// 地址已推送过的项目：更新交易已发出、已导出或已确认
+    async fn pushed_projects(&self, address: &Address) -> Result<Vec<Address>, sqlx::Error>;
+
+    /// 地址已从链上移除，删除其在所有项目上的同步记录
+    async fn delete_sync_state(&self, address: &Address) -> Result<(), sqlx::Error>;
+
+    /// 记录检测到的被滥用函数，已存在时保持原状态
+    async fn record_function_scope(&self, scope: &FunctionScope) -> Result<(), sqlx::Error>;
+
+    async fn mark_scope_synced(&self, scope: &FunctionScope) -> Result<(), sqlx::Error>;
+
+    /// 地址的所有函数级记录，以及是否已推送上链
+    async fn list_scopes(
+        &self,
+        address: &Address,
+    ) -> Result<Vec<(FunctionScope, bool)>, sqlx::Error>;
+
+    /// 所有函数级记录，以及是否已推送上链
+    async fn list_all_scopes(&self) -> Result<Vec<(FunctionScope, bool)>, sqlx::Error>;
+
+    /// 尚未推送的函数级记录
+    async fn load_unsynced_scopes(&self) -> Result<Vec<FunctionScope>, sqlx::Error>;
+
+    async fn delete_scopes(&self, address: &Address) -> Result<(), sqlx::Error>;
+
+    /// 为发送账户分配下一个 nonce，取数据库记录与链上 pending nonce 中较大者；
+    /// 分配在事务中锁定该账户的记录，多个发送方共享账户时串行执行
+    async fn reserve_nonce(
+        &self,
+        chain_id: u64,
+        sender: Address,
+        chain_nonce: u64,
+    ) -> Result<u64, sqlx::Error>;
+
+    /// 归还未能广播的 nonce，之后已有新的分配则不回退
+    async fn release_nonce(
+        &self,
+        chain_id: u64,
+        sender: Address,
+        nonce: u64,
+    ) -> Result<(), sqlx::Error>;
+
+    /// 记录自动暂停，目标曾被恢复时重新记为暂停
+    async fn record_pause(
+        &self,
+        target: &PauseTarget,
+        attacker: Address,
+    ) -> Result<(), sqlx::Error>;
+
+    async fn record_unpause(&self, target: &PauseTarget) -> Result<(), sqlx::Error>;
+
+    /// 仍处于暂停状态的记录，按暂停时间排列
+    async fn list_active_pauses(&self) -> Result<Vec<PauseRecord>, sqlx::Error>;
+}
+
+/// 黑名单数据库连接串，`DATABASE_URL` 未设置时使用 `MYSQL_URL`
+pub fn database_url() -> Option<String> {
+    env::var("DATABASE_URL")
+        .or_else(|_| env::var("MYSQL_URL"))
+        .ok()
+}
+
+/// 按连接串的协议选择后端：`mysql://`、`postgres://` 或 `sqlite://`
+pub async fn connect(url: &str, table: &str) -> Result<Arc<dyn BlacklistStore>> {
+    let scheme = url.split_once(':').map(|(scheme, _)| scheme).unwrap_or("");
+    let store: Arc<dyn BlacklistStore> = match scheme {
+        "mysql" => Arc::new(MySqlStore::connect(url, table).await?),
+        "postgres" | "postgresql" => Arc::new(PostgresStore::connect(url, table).await?),
+        "sqlite" => Arc::new(SqliteStore::connect(url, table).await?),
+        _ => return Err(anyhow!("不支持的数据库连接串: {}", url)),
+    };
+    Ok(store)
+}
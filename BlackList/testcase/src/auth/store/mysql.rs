@@ -0,0 +1,57 @@
+use sqlx::{migrate::Migrator, mysql::MySqlQueryResult, MySql};
+
+use crate::auth::{
+    data::{merged_status_sql, REASON_SEPARATOR},
+    query::Dialect,
+    schema::{MYSQL_MIGRATIONS_TABLE_QUERY, MYSQL_MIGRATOR},
+    store::sql::{Backend, SqlStore},
+};
+
+pub type MySqlStore = SqlStore<MySql>;
+
+impl Backend for MySql {
+    const DIALECT: Dialect = Dialect::MySql;
+    const MIGRATIONS_TABLE_QUERY: &'static str = MYSQL_MIGRATIONS_TABLE_QUERY;
+    const INSERT_IGNORE: &'static str = "INSERT IGNORE INTO";
+    const ON_CONFLICT_IGNORE: &'static str = "";
+    const ILIKE: &'static str = "LIKE";
+    const FOR_UPDATE: &'static str = "FOR UPDATE";
+
+    fn migrator() -> &'static Migrator {
+        &MYSQL_MIGRATOR
+    }
+
+    fn rows_affected(result: &MySqlQueryResult) -> u64 {
+        result.rows_affected()
+    }
+
+    fn on_conflict_update(_keys: &str, columns: &[&str]) -> String {
+        let set: Vec<String> = columns
+            .iter()
+            .map(|column| format!("{column} = VALUES({column})"))
+            .collect();
+        format!("ON DUPLICATE KEY UPDATE {}", set.join(", "))
+    }
+
+    fn merge_entry(table: &str) -> String {
+        let merged = merged_status_sql("?");
+        format!(
+            r#"
+            UPDATE {table}
+            SET reason = IF(
+                    LOCATE(CONCAT('{REASON_SEPARATOR}', ?, '{REASON_SEPARATOR}'),
+                           CONCAT('{REASON_SEPARATOR}', reason, '{REASON_SEPARATOR}')) > 0,
+                    reason,
+                    CONCAT(reason, '{REASON_SEPARATOR}', ?)
+                ),
+                status_changed_at = CASE
+                    WHEN {merged} <> status THEN CURRENT_TIMESTAMP
+                    ELSE status_changed_at
+                END,
+                status = {merged},
+                expires_at = IF(expires_at IS NULL OR ? IS NULL, NULL, GREATEST(expires_at, ?))
+            WHERE chain_type = ? AND address = ?
+        "#
+        )
+    }
+}
@@ -0,0 +1,74 @@
+use sqlx::{migrate::Migrator, postgres::PgQueryResult, Postgres};
+
+use crate::auth::{
+    data::{merged_status_sql, REASON_SEPARATOR},
+    query::Dialect,
+    schema::{POSTGRES_MIGRATIONS_TABLE_QUERY, POSTGRES_MIGRATOR},
+    store::sql::{Backend, SqlStore},
+};
+
+pub type PostgresStore = SqlStore<Postgres>;
+
+impl Backend for Postgres {
+    const DIALECT: Dialect = Dialect::Postgres;
+    const MIGRATIONS_TABLE_QUERY: &'static str = POSTGRES_MIGRATIONS_TABLE_QUERY;
+    const INSERT_IGNORE: &'static str = "INSERT INTO";
+    const ON_CONFLICT_IGNORE: &'static str = "ON CONFLICT DO NOTHING";
+    const ILIKE: &'static str = "ILIKE";
+    const FOR_UPDATE: &'static str = "FOR UPDATE";
+
+    fn migrator() -> &'static Migrator {
+        &POSTGRES_MIGRATOR
+    }
+
+    fn rows_affected(result: &PgQueryResult) -> u64 {
+        result.rows_affected()
+    }
+
+    // 按出现顺序改写为 $1、$2 …，跳过字符串字面量中的问号
+    fn sql(query: String) -> String {
+        let mut sql = String::with_capacity(query.len());
+        let mut index = 0;
+        let mut quoted = false;
+        for c in query.chars() {
+            match c {
+                '\'' => {
+                    quoted = !quoted;
+                    sql.push(c);
+                }
+                '?' if !quoted => {
+                    index += 1;
+                    sql.push_str(&format!("${index}"));
+                }
+                _ => sql.push(c),
+            }
+        }
+        sql
+    }
+
+    fn merge_entry(table: &str) -> String {
+        let merged = merged_status_sql("?");
+        format!(
+            r#"
+            UPDATE {table}
+            SET reason = CASE
+                    WHEN POSITION('{REASON_SEPARATOR}' || ? || '{REASON_SEPARATOR}'
+                                  IN '{REASON_SEPARATOR}' || reason || '{REASON_SEPARATOR}') > 0
+                    THEN reason
+                    ELSE reason || '{REASON_SEPARATOR}' || ?
+                END,
+                status_changed_at = CASE
+                    WHEN {merged} <> status THEN CURRENT_TIMESTAMP
+                    ELSE status_changed_at
+                END,
+                status = {merged},
+                expires_at = CASE
+                    WHEN expires_at IS NULL OR ?::TIMESTAMPTZ IS NULL THEN NULL
+                    ELSE GREATEST(expires_at, ?)
+                END,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE chain_type = ? AND address = ?
+        "#
+        )
+    }
+}
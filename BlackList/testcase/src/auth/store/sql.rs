@@ -0,0 +1,1006 @@
+use alloy::primitives::{Address, TxHash};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{
+    migrate::{Migrate, Migrator},
+    pool::PoolOptions,
+    ColumnIndex, Database, Decode, Encode, Executor, FromRow, IntoArguments, Pool, Type,
+};
+
+use crate::auth::{
+    allowlist::{AllowlistEntry, NewAllowlistEntry, ALLOWLIST_TABLE},
+    data::{canonical_address, BlacklistEntry, EntryStatus, NewBlacklistEntry, UpsertOutcome},
+    evidence::{evidence_table, Evidence, EvidenceRecord},
+    history::{history_table, is_unchanged, snapshot, ChangeAction, ChangeContext, HistoryRecord},
+    nonce::NONCE_TABLE,
+    pause::{PauseRecord, PauseTarget, PAUSE_TABLE},
+    query::{bind_args, escape_like, BlacklistPage, BlacklistQuery, Dialect, LIKE_ESCAPE},
+    schema::{check_version, APPLIED_VERSION_QUERY},
+    scope::{parse_rows, scope_table, FunctionScope},
+    store::BlacklistStore,
+    sync::{SyncStatus, UnsyncedAddress, SYNC_TABLE},
+};
+
+/// 黑名单表的全部列
+const ENTRY_COLUMNS: &str = "id, address, reason, added_at, status, expires_at, chain_type, \
+     created_by, updated_at, status_changed_at";
+
+/// 各数据库在 SQL 方言与迁移上的差异，其余逻辑由 `SqlStore` 共用。
+/// 语句统一以 `?` 作为占位符，按出现顺序绑定
+pub trait Backend: Database {
+    /// 查询条件使用的方言
+    const DIALECT: Dialect;
+    /// 迁移记录表是否存在，不存在表示数据库尚未初始化
+    const MIGRATIONS_TABLE_QUERY: &'static str;
+    /// 唯一键冲突时跳过的插入，语句末尾接 `ON_CONFLICT_IGNORE`
+    const INSERT_IGNORE: &'static str;
+    const ON_CONFLICT_IGNORE: &'static str;
+    /// 不区分大小写的 LIKE
+    const ILIKE: &'static str;
+    /// 事务内锁定读取到的行，直到提交
+    const FOR_UPDATE: &'static str;
+
+    fn migrator() -> &'static Migrator;
+
+    fn rows_affected(result: &Self::QueryResult) -> u64;
+
+    /// 把 `?` 占位符改写为该数据库的格式
+    fn sql(query: String) -> String {
+        query
+    }
+
+    /// 唯一键 `keys` 冲突时以新插入的值更新 `columns`，末尾可以追加其他赋值
+    fn on_conflict_update(keys: &str, columns: &[&str]) -> String {
+        let set: Vec<String> = columns
+            .iter()
+            .map(|column| format!("{column} = excluded.{column}"))
+            .collect();
+        format!("ON CONFLICT ({keys}) DO UPDATE SET {}", set.join(", "))
+    }
+
+    /// upsert 时合并已存在记录的 UPDATE 语句：不重复地追加原因，状态按
+    /// `merged_status_sql` 合并，到期时间取较晚者，任一方永久时保持永久。
+    /// 依次绑定 reason、reason、status、status、expires_at、expires_at、chain_type、address
+    fn merge_entry(table: &str) -> String;
+}
+
+/// 三种数据库共用的存储实现，每个实例对应一张黑名单表（生产表或影子表）
+/// 及其证据、历史与函数级黑名单表
+#[derive(Debug)]
+pub struct SqlStore<DB: Database> {
+    pool: Pool<DB>,
+    table: String,
+    evidence_table: &'static str,
+    history_table: &'static str,
+    scope_table: &'static str,
+}
+
+impl<DB: Backend> SqlStore<DB> {
+    pub fn new(pool: Pool<DB>, table: &str) -> Self {
+        Self {
+            pool,
+            table: table.to_string(),
+            evidence_table: evidence_table(table),
+            history_table: history_table(table),
+            scope_table: scope_table(table),
+        }
+    }
+
+    pub async fn connect(url: &str, table: &str) -> Result<Self> {
+        let pool = PoolOptions::<DB>::new()
+            .max_connections(5)
+            .connect(url)
+            .await?;
+        Ok(Self::new(pool, table))
+    }
+
+    pub fn pool(&self) -> &Pool<DB> {
+        &self.pool
+    }
+}
+
+// `n` 个以逗号分隔的占位符
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+impl<DB> SqlStore<DB>
+where
+    DB: Backend,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> String: Encode<'q, DB> + Decode<'q, DB> + Type<DB>,
+    for<'q> i64: Encode<'q, DB> + Decode<'q, DB> + Type<DB>,
+    for<'q> DateTime<Utc>: Encode<'q, DB> + Decode<'q, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB> + Decode<'q, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB> + Type<DB>,
+    for<'r> BlacklistEntry: FromRow<'r, DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+{
+    // 事务内检查地址是否在白名单中，白名单地址不能写入黑名单
+    async fn is_allowlisted_in(
+        &self,
+        conn: &mut DB::Connection,
+        entry: &NewBlacklistEntry,
+    ) -> Result<bool, sqlx::Error> {
+        let query = DB::sql(format!(
+            "SELECT COUNT(*) FROM {ALLOWLIST_TABLE} WHERE chain_type = ? AND address = ?"
+        ));
+
+        let count: (i64,) = sqlx::query_as(&query)
+            .bind(entry.chain_type.as_str())
+            .bind(canonical_address(&entry.address))
+            .fetch_one(conn)
+            .await?;
+
+        Ok(count.0 > 0)
+    }
+
+    // 事务内按唯一键读取记录，用于生成历史快照
+    async fn entry_by_key(
+        &self,
+        conn: &mut DB::Connection,
+        entry: &NewBlacklistEntry,
+    ) -> Result<Option<BlacklistEntry>, sqlx::Error> {
+        let table = &self.table;
+        let query = DB::sql(format!(
+            "SELECT {ENTRY_COLUMNS} FROM {table} WHERE chain_type = ? AND address = ?"
+        ));
+
+        sqlx::query_as::<_, BlacklistEntry>(&query)
+            .bind(entry.chain_type.as_str())
+            .bind(canonical_address(&entry.address))
+            .fetch_optional(conn)
+            .await
+    }
+
+    async fn entry_by_id(
+        &self,
+        conn: &mut DB::Connection,
+        id: i64,
+    ) -> Result<Option<BlacklistEntry>, sqlx::Error> {
+        let table = &self.table;
+        let query = DB::sql(format!("SELECT {ENTRY_COLUMNS} FROM {table} WHERE id = ?"));
+
+        sqlx::query_as::<_, BlacklistEntry>(&query)
+            .bind(id)
+            .fetch_optional(conn)
+            .await
+    }
+
+    // 与变更在同一事务中写入历史
+    async fn append_history(
+        &self,
+        conn: &mut DB::Connection,
+        action: ChangeAction,
+        context: &ChangeContext,
+        old: Option<&BlacklistEntry>,
+        new: Option<&BlacklistEntry>,
+    ) -> Result<(), sqlx::Error> {
+        let Some(entry) = new.or(old) else {
+            return Ok(());
+        };
+        let history_table = self.history_table;
+        let query = DB::sql(format!(
+            r#"
+            INSERT INTO {history_table}
+                (entry_id, address, chain_type, action, source, actor, old_value, new_value)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+        ));
+
+        sqlx::query(&query)
+            .bind(entry.id)
+            .bind(entry.address.as_str())
+            .bind(entry.chain_type.as_str())
+            .bind(action.as_str())
+            .bind(context.source.as_str())
+            .bind(context.actor.clone())
+            .bind(snapshot(old))
+            .bind(snapshot(new))
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    // 插入新记录：`ignore` 为 true 时唯一键冲突不报错，返回是否插入
+    async fn insert_entry(
+        &self,
+        conn: &mut DB::Connection,
+        entry: &NewBlacklistEntry,
+        ignore: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let table = &self.table;
+        let (insert, on_conflict) = if ignore {
+            (DB::INSERT_IGNORE, DB::ON_CONFLICT_IGNORE)
+        } else {
+            ("INSERT INTO", "")
+        };
+        let query = DB::sql(format!(
+            r#"
+            {insert} {table}
+                (address, reason, status, expires_at, chain_type, created_by, status_changed_at)
+            VALUES (?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+            {on_conflict}
+        "#
+        ));
+
+        let result = sqlx::query(&query)
+            .bind(canonical_address(&entry.address))
+            .bind(entry.reason.as_str())
+            .bind(entry.status.as_str())
+            .bind(entry.expires_at)
+            .bind(entry.chain_type.as_str())
+            .bind(entry.created_by.clone())
+            .execute(conn)
+            .await?;
+
+        Ok(DB::rows_affected(&result) > 0)
+    }
+
+    // 更新 project 上一组地址的同步状态
+    async fn update_sync(
+        &self,
+        project: Address,
+        addresses: &[Address],
+        status: SyncStatus,
+        tx_hash: Option<TxHash>,
+        nonce: Option<u64>,
+    ) -> Result<(), sqlx::Error> {
+        if addresses.is_empty() {
+            return Ok(());
+        }
+        let attempts = if status == SyncStatus::Submitted {
+            ", attempts = attempts + 1"
+        } else {
+            ""
+        };
+        let query = DB::sql(format!(
+            r#"
+            UPDATE {SYNC_TABLE}
+            SET status = ?, tx_hash = ?, nonce = ?, updated_at = CURRENT_TIMESTAMP{attempts}
+            WHERE project = ? AND address IN ({})
+        "#,
+            placeholders(addresses.len())
+        ));
+
+        let mut query = sqlx::query(&query)
+            .bind(status.as_str())
+            .bind(tx_hash.map(|hash| hash.to_string()))
+            .bind(nonce.map(|nonce| nonce as i64))
+            .bind(canonical_address(&project));
+        for address in addresses {
+            query = query.bind(canonical_address(address));
+        }
+        query.execute(&self.pool).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<DB> BlacklistStore for SqlStore<DB>
+where
+    DB: Backend,
+    DB::Connection: Migrate,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> String: Encode<'q, DB> + Decode<'q, DB> + Type<DB>,
+    for<'q> i64: Encode<'q, DB> + Decode<'q, DB> + Type<DB>,
+    for<'q> bool: Decode<'q, DB> + Type<DB>,
+    for<'q> DateTime<Utc>: Encode<'q, DB> + Decode<'q, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB> + Decode<'q, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB> + Type<DB>,
+    for<'r> BlacklistEntry: FromRow<'r, DB::Row>,
+    for<'r> EvidenceRecord: FromRow<'r, DB::Row>,
+    for<'r> HistoryRecord: FromRow<'r, DB::Row>,
+    for<'r> AllowlistEntry: FromRow<'r, DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+{
+    async fn migrate(&self) -> Result<()> {
+        DB::migrator().run(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn verify_schema(&self) -> Result<()> {
+        let tables: i64 = sqlx::query_scalar(DB::MIGRATIONS_TABLE_QUERY)
+            .fetch_one(&self.pool)
+            .await?;
+        let applied: Option<i64> = if tables == 0 {
+            None
+        } else {
+            sqlx::query_scalar(APPLIED_VERSION_QUERY)
+                .fetch_one(&self.pool)
+                .await?
+        };
+        check_version(applied, DB::migrator())
+    }
+
+    async fn create(&self, entry: &NewBlacklistEntry, context: &ChangeContext) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        if self.is_allowlisted_in(&mut tx, entry).await? {
+            return Err(anyhow!("{} 在白名单中，不能加入黑名单", entry.address));
+        }
+        self.insert_entry(&mut tx, entry, false).await?;
+        let created = self.entry_by_key(&mut tx, entry).await?;
+        self.append_history(
+            &mut tx,
+            ChangeAction::Create,
+            context,
+            None,
+            created.as_ref(),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn upsert(
+        &self,
+        entry: &NewBlacklistEntry,
+        context: &ChangeContext,
+    ) -> Result<UpsertOutcome, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if self.is_allowlisted_in(&mut tx, entry).await? {
+            return Ok(UpsertOutcome::Allowlisted);
+        }
+        if self.insert_entry(&mut tx, entry, true).await? {
+            let created = self.entry_by_key(&mut tx, entry).await?;
+            self.append_history(
+                &mut tx,
+                ChangeAction::Create,
+                context,
+                None,
+                created.as_ref(),
+            )
+            .await?;
+            tx.commit().await?;
+            return Ok(UpsertOutcome::Inserted);
+        }
+
+        let old = self.entry_by_key(&mut tx, entry).await?;
+        let query = DB::sql(DB::merge_entry(&self.table));
+        sqlx::query(&query)
+            .bind(entry.reason.as_str())
+            .bind(entry.reason.as_str())
+            .bind(entry.status.as_str())
+            .bind(entry.status.as_str())
+            .bind(entry.expires_at)
+            .bind(entry.expires_at)
+            .bind(entry.chain_type.as_str())
+            .bind(canonical_address(&entry.address))
+            .execute(&mut *tx)
+            .await?;
+
+        let new = self.entry_by_key(&mut tx, entry).await?;
+        if let (Some(old), Some(new)) = (&old, &new) {
+            if !is_unchanged(old, new) {
+                self.append_history(&mut tx, ChangeAction::Merge, context, Some(old), Some(new))
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+
+        let was_listed = old.is_some_and(|old| old.status == EntryStatus::Active.as_str());
+        let is_listed = new.is_some_and(|new| new.status == EntryStatus::Active.as_str());
+        Ok(match (was_listed, is_listed) {
+            (_, false) => UpsertOutcome::Held,
+            (false, true) => UpsertOutcome::Reactivated,
+            (true, true) => UpsertOutcome::Merged,
+        })
+    }
+
+    async fn get(&self, id: i64) -> Result<Option<BlacklistEntry>, sqlx::Error> {
+        let table = &self.table;
+        let query = DB::sql(format!("SELECT {ENTRY_COLUMNS} FROM {table} WHERE id = ?"));
+
+        sqlx::query_as::<_, BlacklistEntry>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn find(&self, address: &Address) -> Result<Option<BlacklistEntry>, sqlx::Error> {
+        let table = &self.table;
+        let query = DB::sql(format!(
+            "SELECT {ENTRY_COLUMNS} FROM {table} WHERE address = ? ORDER BY id LIMIT 1"
+        ));
+
+        sqlx::query_as::<_, BlacklistEntry>(&query)
+            .bind(canonical_address(address))
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn find_in_chain(
+        &self,
+        chain_type: &str,
+        address: &Address,
+    ) -> Result<Option<BlacklistEntry>, sqlx::Error> {
+        let table = &self.table;
+        let query = DB::sql(format!(
+            "SELECT {ENTRY_COLUMNS} FROM {table} WHERE chain_type = ? AND address = ?"
+        ));
+
+        sqlx::query_as::<_, BlacklistEntry>(&query)
+            .bind(chain_type.to_string())
+            .bind(canonical_address(address))
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn list(&self) -> Result<Vec<BlacklistEntry>, sqlx::Error> {
+        let table = &self.table;
+        let query = format!("SELECT {ENTRY_COLUMNS} FROM {table}");
+
+        sqlx::query_as::<_, BlacklistEntry>(&query)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn query(&self, query: &BlacklistQuery) -> Result<BlacklistPage> {
+        let table = &self.table;
+        // 过滤条件已按方言生成占位符
+        let (filter, args) = query.filter(DB::DIALECT, self.evidence_table, true)?;
+        let order = query.order_and_limit(DB::DIALECT);
+        let sql = format!("SELECT {ENTRY_COLUMNS} FROM {table} {filter} {order}");
+
+        let entries = bind_args(sqlx::query_as::<_, BlacklistEntry>(&sql), args)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(query.page(entries))
+    }
+
+    async fn count(&self, query: &BlacklistQuery) -> Result<i64> {
+        let table = &self.table;
+        let (filter, args) = query.filter(DB::DIALECT, self.evidence_table, false)?;
+        let sql = format!("SELECT COUNT(*) FROM {table} {filter}");
+
+        let count: (i64,) = bind_args(sqlx::query_as(&sql), args)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count.0)
+    }
+
+    async fn list_active_addresses(&self) -> Result<Vec<String>, sqlx::Error> {
+        let table = &self.table;
+        let query = format!("SELECT address FROM {table} WHERE status = 'active'");
+
+        let rows: Vec<(String,)> = sqlx::query_as(&query).fetch_all(&self.pool).await?;
+
+        Ok(rows.into_iter().map(|(address,)| address).collect())
+    }
+
+    async fn list_expired(&self, now: DateTime<Utc>) -> Result<Vec<BlacklistEntry>, sqlx::Error> {
+        let table = &self.table;
+        let expires_at = DB::DIALECT.time("expires_at");
+        let now_param = DB::DIALECT.time("?");
+        let query = DB::sql(format!(
+            r#"
+            SELECT {ENTRY_COLUMNS}
+            FROM {table}
+            WHERE status = 'active' AND expires_at IS NOT NULL AND {expires_at} <= {now_param}
+        "#
+        ));
+
+        sqlx::query_as::<_, BlacklistEntry>(&query)
+            .bind(now)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn update(
+        &self,
+        id: i64,
+        status: EntryStatus,
+        expires_at: Option<DateTime<Utc>>,
+        context: &ChangeContext,
+    ) -> Result<()> {
+        let table = &self.table;
+        let query = DB::sql(format!(
+            r#"
+            UPDATE {table}
+            SET status_changed_at = CASE
+                    WHEN status <> ? THEN CURRENT_TIMESTAMP
+                    ELSE status_changed_at
+                END,
+                status = ?,
+                expires_at = ?,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+        "#
+        ));
+
+        let mut tx = self.pool.begin().await?;
+        let Some(old) = self.entry_by_id(&mut tx, id).await? else {
+            return Ok(());
+        };
+        old.status.parse::<EntryStatus>()?.transition(status)?;
+        sqlx::query(&query)
+            .bind(status.as_str())
+            .bind(status.as_str())
+            .bind(expires_at)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if let Some(new) = self.entry_by_id(&mut tx, id).await? {
+            if !is_unchanged(&old, &new) {
+                let action = ChangeAction::for_update(&old, &new);
+                self.append_history(&mut tx, action, context, Some(&old), Some(&new))
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: i64, context: &ChangeContext) -> Result<(), sqlx::Error> {
+        let table = &self.table;
+        let query = DB::sql(format!("DELETE FROM {table} WHERE id = ?"));
+
+        let mut tx = self.pool.begin().await?;
+        let Some(old) = self.entry_by_id(&mut tx, id).await? else {
+            return Ok(());
+        };
+        sqlx::query(&query).bind(id).execute(&mut *tx).await?;
+        self.append_history(&mut tx, ChangeAction::Delete, context, Some(&old), None)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn exists(&self, address: &Address) -> Result<bool, sqlx::Error> {
+        let table = &self.table;
+        let query = DB::sql(format!("SELECT COUNT(*) FROM {table} WHERE address = ?"));
+
+        let count: (i64,) = sqlx::query_as(&query)
+            .bind(canonical_address(address))
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count.0 > 0)
+    }
+
+    async fn search(&self, keyword: &str) -> Result<Vec<BlacklistEntry>, sqlx::Error> {
+        let table = &self.table;
+        let ilike = DB::ILIKE;
+        let query = DB::sql(format!(
+            r#"
+            SELECT {ENTRY_COLUMNS}
+            FROM {table} WHERE address LIKE ? ESCAPE '{LIKE_ESCAPE}'
+                OR reason {ilike} ? ESCAPE '{LIKE_ESCAPE}'
+            ORDER BY id
+        "#
+        ));
+
+        sqlx::query_as::<_, BlacklistEntry>(&query)
+            .bind(format!("{}%", escape_like(&keyword.trim().to_lowercase())))
+            .bind(format!("%{}%", escape_like(keyword.trim())))
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn add_evidence(&self, evidence: &Evidence) -> Result<bool, sqlx::Error> {
+        let table = &self.table;
+        let evidence_table = self.evidence_table;
+        let (insert, on_conflict) = (DB::INSERT_IGNORE, DB::ON_CONFLICT_IGNORE);
+        let query = DB::sql(format!(
+            r#"
+            {insert} {evidence_table} (
+                entry_id, tx_hash, block_number, block_hash, chain_type, detector, severity,
+                project, func_sig, loss_wei, call_path
+            )
+            SELECT id, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+            FROM {table} WHERE chain_type = ? AND address = ?
+            {on_conflict}
+        "#
+        ));
+
+        let result = sqlx::query(&query)
+            .bind(evidence.tx_hash.map(|hash| hash.to_string()))
+            .bind(evidence.block_number.map(|number| number as i64))
+            .bind(evidence.block_hash.to_string())
+            .bind(evidence.chain_type.as_str())
+            .bind(evidence.detector.as_str())
+            .bind(evidence.severity.as_str())
+            .bind(canonical_address(&evidence.project))
+            .bind(evidence.selector.map(|selector| selector.to_string()))
+            .bind(evidence.loss.to_string())
+            .bind(evidence.call_path_json())
+            .bind(evidence.chain_type.as_str())
+            .bind(canonical_address(&evidence.attacker))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(DB::rows_affected(&result) > 0)
+    }
+
+    async fn list_evidence(&self, entry_id: i64) -> Result<Vec<EvidenceRecord>, sqlx::Error> {
+        let evidence_table = self.evidence_table;
+        let query = DB::sql(format!(
+            r#"
+            SELECT id, entry_id, tx_hash, block_number, block_hash, chain_type, detector, severity,
+                project, func_sig, loss_wei, call_path, created_at
+            FROM {evidence_table} WHERE entry_id = ? ORDER BY id
+        "#
+        ));
+
+        sqlx::query_as::<_, EvidenceRecord>(&query)
+            .bind(entry_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn history(&self, address: &Address) -> Result<Vec<HistoryRecord>, sqlx::Error> {
+        let history_table = self.history_table;
+        let query = DB::sql(format!(
+            r#"
+            SELECT id, entry_id, address, chain_type, action, source, actor, old_value, new_value,
+                changed_at
+            FROM {history_table} WHERE address = ? ORDER BY id
+        "#
+        ));
+
+        sqlx::query_as::<_, HistoryRecord>(&query)
+            .bind(canonical_address(address))
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn allow(&self, entry: &NewAllowlistEntry) -> Result<(), sqlx::Error> {
+        let on_conflict = DB::on_conflict_update("chain_type, address", &["reason", "owner"]);
+        let query = DB::sql(format!(
+            r#"
+            INSERT INTO {ALLOWLIST_TABLE} (address, chain_type, reason, owner)
+            VALUES (?, ?, ?, ?)
+            {on_conflict}
+        "#
+        ));
+
+        sqlx::query(&query)
+            .bind(canonical_address(&entry.address))
+            .bind(entry.chain_type.as_str())
+            .bind(entry.reason.as_str())
+            .bind(entry.owner.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn disallow(&self, address: &Address) -> Result<bool, sqlx::Error> {
+        let query = DB::sql(format!("DELETE FROM {ALLOWLIST_TABLE} WHERE address = ?"));
+
+        let result = sqlx::query(&query)
+            .bind(canonical_address(address))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(DB::rows_affected(&result) > 0)
+    }
+
+    async fn list_allowlist(&self) -> Result<Vec<AllowlistEntry>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT address, chain_type, reason, owner, added_at, suppressed_count, last_suppressed_at
+            FROM {ALLOWLIST_TABLE} ORDER BY added_at
+        "#
+        );
+
+        sqlx::query_as::<_, AllowlistEntry>(&query)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn record_suppressed(&self, address: &Address) -> Result<(), sqlx::Error> {
+        let query = DB::sql(format!(
+            r#"
+            UPDATE {ALLOWLIST_TABLE}
+            SET suppressed_count = suppressed_count + 1, last_suppressed_at = CURRENT_TIMESTAMP
+            WHERE address = ?
+        "#
+        ));
+
+        sqlx::query(&query)
+            .bind(canonical_address(address))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn mark_pending(
+        &self,
+        project: Address,
+        addresses: &[Address],
+    ) -> Result<(), sqlx::Error> {
+        if addresses.is_empty() {
+            return Ok(());
+        }
+        let (insert, on_conflict) = (DB::INSERT_IGNORE, DB::ON_CONFLICT_IGNORE);
+        let rows = vec!["(?, ?, ?)"; addresses.len()].join(", ");
+        let query = DB::sql(format!(
+            "{insert} {SYNC_TABLE} (address, project, status) VALUES {rows} {on_conflict}"
+        ));
+
+        let mut query = sqlx::query(&query);
+        for address in addresses {
+            query = query
+                .bind(canonical_address(address))
+                .bind(canonical_address(&project))
+                .bind(SyncStatus::Pending.as_str());
+        }
+        query.execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn set_sync_status(
+        &self,
+        project: Address,
+        addresses: &[Address],
+        status: SyncStatus,
+        tx_hash: Option<TxHash>,
+        nonce: Option<u64>,
+    ) -> Result<(), sqlx::Error> {
+        self.update_sync(project, addresses, status, tx_hash, nonce)
+            .await
+    }
+
+    async fn load_unsynced(&self) -> Result<Vec<UnsyncedAddress>, sqlx::Error> {
+        let query = DB::sql(format!(
+            "SELECT address, project, status, tx_hash FROM {SYNC_TABLE} WHERE status <> ?"
+        ));
+
+        let rows: Vec<(String, String, String, Option<String>)> = sqlx::query_as(&query)
+            .bind(SyncStatus::Synced.as_str())
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(UnsyncedAddress::from_row)
+            .collect())
+    }
+
+    async fn pushed_projects(&self, address: &Address) -> Result<Vec<Address>, sqlx::Error> {
+        let query = DB::sql(format!(
+            "SELECT project FROM {SYNC_TABLE} WHERE address = ? AND status <> ?"
+        ));
+
+        let rows: Vec<(String,)> = sqlx::query_as(&query)
+            .bind(canonical_address(address))
+            .bind(SyncStatus::Pending.as_str())
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(project,)| project.parse().ok())
+            .collect())
+    }
+
+    async fn delete_sync_state(&self, address: &Address) -> Result<(), sqlx::Error> {
+        let query = DB::sql(format!("DELETE FROM {SYNC_TABLE} WHERE address = ?"));
+
+        sqlx::query(&query)
+            .bind(canonical_address(address))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_function_scope(&self, scope: &FunctionScope) -> Result<(), sqlx::Error> {
+        let scope_table = self.scope_table;
+        let (insert, on_conflict) = (DB::INSERT_IGNORE, DB::ON_CONFLICT_IGNORE);
+        let query = DB::sql(format!(
+            "{insert} {scope_table} (address, project, func_sig) VALUES (?, ?, ?) {on_conflict}"
+        ));
+
+        sqlx::query(&query)
+            .bind(canonical_address(&scope.address))
+            .bind(canonical_address(&scope.project))
+            .bind(scope.selector.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn mark_scope_synced(&self, scope: &FunctionScope) -> Result<(), sqlx::Error> {
+        let scope_table = self.scope_table;
+        let query = DB::sql(format!(
+            "UPDATE {scope_table} SET synced = TRUE WHERE address = ? AND project = ? AND func_sig = ?"
+        ));
+
+        sqlx::query(&query)
+            .bind(canonical_address(&scope.address))
+            .bind(canonical_address(&scope.project))
+            .bind(scope.selector.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list_scopes(
+        &self,
+        address: &Address,
+    ) -> Result<Vec<(FunctionScope, bool)>, sqlx::Error> {
+        let scope_table = self.scope_table;
+        let query = DB::sql(format!(
+            "SELECT address, project, func_sig, synced FROM {scope_table} WHERE address = ?"
+        ));
+
+        let rows: Vec<(String, String, String, bool)> = sqlx::query_as(&query)
+            .bind(canonical_address(address))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(parse_rows(rows))
+    }
+
+    async fn list_all_scopes(&self) -> Result<Vec<(FunctionScope, bool)>, sqlx::Error> {
+        let scope_table = self.scope_table;
+        let query = format!("SELECT address, project, func_sig, synced FROM {scope_table}");
+
+        let rows: Vec<(String, String, String, bool)> =
+            sqlx::query_as(&query).fetch_all(&self.pool).await?;
+
+        Ok(parse_rows(rows))
+    }
+
+    async fn load_unsynced_scopes(&self) -> Result<Vec<FunctionScope>, sqlx::Error> {
+        let scope_table = self.scope_table;
+        let query = format!(
+            "SELECT address, project, func_sig, synced FROM {scope_table} WHERE synced = FALSE"
+        );
+
+        let rows: Vec<(String, String, String, bool)> =
+            sqlx::query_as(&query).fetch_all(&self.pool).await?;
+
+        Ok(parse_rows(rows)
+            .into_iter()
+            .map(|(scope, _)| scope)
+            .collect())
+    }
+
+    async fn delete_scopes(&self, address: &Address) -> Result<(), sqlx::Error> {
+        let scope_table = self.scope_table;
+        let query = DB::sql(format!("DELETE FROM {scope_table} WHERE address = ?"));
+
+        sqlx::query(&query)
+            .bind(canonical_address(address))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reserve_nonce(
+        &self,
+        chain_id: u64,
+        sender: Address,
+        chain_nonce: u64,
+    ) -> Result<u64, sqlx::Error> {
+        let (insert, on_conflict) = (DB::INSERT_IGNORE, DB::ON_CONFLICT_IGNORE);
+        let insert = DB::sql(format!(
+            "{insert} {NONCE_TABLE} (chain_id, sender, next_nonce) VALUES (?, ?, ?) {on_conflict}"
+        ));
+        let for_update = DB::FOR_UPDATE;
+        let select = DB::sql(format!(
+            "SELECT next_nonce FROM {NONCE_TABLE} WHERE chain_id = ? AND sender = ? {for_update}"
+        ));
+        let update = DB::sql(format!(
+            r#"
+            UPDATE {NONCE_TABLE} SET next_nonce = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE chain_id = ? AND sender = ?
+        "#
+        ));
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&insert)
+            .bind(chain_id as i64)
+            .bind(canonical_address(&sender))
+            .bind(chain_nonce as i64)
+            .execute(&mut *tx)
+            .await?;
+        let (stored,): (i64,) = sqlx::query_as(&select)
+            .bind(chain_id as i64)
+            .bind(canonical_address(&sender))
+            .fetch_one(&mut *tx)
+            .await?;
+        let nonce = (stored as u64).max(chain_nonce);
+        sqlx::query(&update)
+            .bind((nonce + 1) as i64)
+            .bind(chain_id as i64)
+            .bind(canonical_address(&sender))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(nonce)
+    }
+
+    async fn release_nonce(
+        &self,
+        chain_id: u64,
+        sender: Address,
+        nonce: u64,
+    ) -> Result<(), sqlx::Error> {
+        let query = DB::sql(format!(
+            r#"
+            UPDATE {NONCE_TABLE} SET next_nonce = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE chain_id = ? AND sender = ? AND next_nonce = ?
+        "#
+        ));
+
+        sqlx::query(&query)
+            .bind(nonce as i64)
+            .bind(chain_id as i64)
+            .bind(canonical_address(&sender))
+            .bind((nonce + 1) as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_pause(
+        &self,
+        target: &PauseTarget,
+        attacker: Address,
+    ) -> Result<(), sqlx::Error> {
+        let on_conflict = DB::on_conflict_update("project, func_sig", &["attacker"]);
+        let query = DB::sql(format!(
+            r#"
+            INSERT INTO {PAUSE_TABLE} (project, func_sig, attacker) VALUES (?, ?, ?)
+            {on_conflict}, paused_at = CURRENT_TIMESTAMP, unpaused_at = NULL
+        "#
+        ));
+
+        sqlx::query(&query)
+            .bind(canonical_address(&target.project()))
+            .bind(target.func_sig())
+            .bind(canonical_address(&attacker))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_unpause(&self, target: &PauseTarget) -> Result<(), sqlx::Error> {
+        let query = DB::sql(format!(
+            "UPDATE {PAUSE_TABLE} SET unpaused_at = CURRENT_TIMESTAMP WHERE project = ? AND func_sig = ?"
+        ));
+
+        sqlx::query(&query)
+            .bind(canonical_address(&target.project()))
+            .bind(target.func_sig())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list_active_pauses(&self) -> Result<Vec<PauseRecord>, sqlx::Error> {
+        let query = format!(
+            "SELECT project, func_sig, attacker, paused_at FROM {PAUSE_TABLE} WHERE unpaused_at IS NULL ORDER BY paused_at"
+        );
+
+        let rows: Vec<(String, String, String, DateTime<Utc>)> =
+            sqlx::query_as(&query).fetch_all(&self.pool).await?;
+
+        Ok(rows.into_iter().filter_map(PauseRecord::from_row).collect())
+    }
+}
@@ -0,0 +1,55 @@
+use sqlx::{migrate::Migrator, sqlite::SqliteQueryResult, Sqlite};
+
+use crate::auth::{
+    data::{merged_status_sql, REASON_SEPARATOR},
+    query::Dialect,
+    schema::{SQLITE_MIGRATIONS_TABLE_QUERY, SQLITE_MIGRATOR},
+    store::sql::{Backend, SqlStore},
+};
+
+pub type SqliteStore = SqlStore<Sqlite>;
+
+impl Backend for Sqlite {
+    const DIALECT: Dialect = Dialect::Sqlite;
+    const MIGRATIONS_TABLE_QUERY: &'static str = SQLITE_MIGRATIONS_TABLE_QUERY;
+    const INSERT_IGNORE: &'static str = "INSERT OR IGNORE INTO";
+    const ON_CONFLICT_IGNORE: &'static str = "";
+    const ILIKE: &'static str = "LIKE";
+    // SQLite 的写事务本身串行执行
+    const FOR_UPDATE: &'static str = "";
+
+    fn migrator() -> &'static Migrator {
+        &SQLITE_MIGRATOR
+    }
+
+    fn rows_affected(result: &SqliteQueryResult) -> u64 {
+        result.rows_affected()
+    }
+
+    fn merge_entry(table: &str) -> String {
+        let merged = merged_status_sql("?");
+        format!(
+            r#"
+            UPDATE {table}
+            SET reason = CASE
+                    WHEN instr('{REASON_SEPARATOR}' || reason || '{REASON_SEPARATOR}',
+                               '{REASON_SEPARATOR}' || ? || '{REASON_SEPARATOR}') > 0
+                    THEN reason
+                    ELSE reason || '{REASON_SEPARATOR}' || ?
+                END,
+                status_changed_at = CASE
+                    WHEN {merged} <> status THEN CURRENT_TIMESTAMP
+                    ELSE status_changed_at
+                END,
+                status = {merged},
+                expires_at = CASE
+                    WHEN expires_at IS NULL THEN NULL
+                    WHEN datetime(expires_at) >= datetime(?) THEN expires_at
+                    ELSE ?
+                END,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE chain_type = ? AND address = ?
+        "#
+        )
+    }
+}
@@ -0,0 +1,211 @@
+use alloy::primitives::{Address, TxHash};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::{env, str::FromStr, time::Duration};
+
+use crate::auth::store::BlacklistStore;
+
+/// 链上同步状态表，记录每个黑名单地址在各项目上的同步进度，重启后据此恢复未完成的同步
+pub const SYNC_TABLE: &str = "blacklist_project_sync";
+
+/// 地址的同步状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncStatus {
+    /// 已检测，尚未发送更新交易
+    Pending,
+    /// 更新交易已发送，等待确认
+    Submitted,
+    /// 已导出为未签名交易，等待多签执行
+    Exported,
+    /// 更新交易已确认
+    Synced,
+}
+
+impl SyncStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Submitted => "submitted",
+            Self::Exported => "exported",
+            Self::Synced => "synced",
+        }
+    }
+
+    pub(crate) fn from_db(status: &str) -> Self {
+        match status {
+            "submitted" => Self::Submitted,
+            "exported" => Self::Exported,
+            "synced" => Self::Synced,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// 更新交易的确认与重试配置
+#[derive(Debug, Clone)]
+pub struct SyncConfig {
+    /// 交易所需的确认区块数
+    pub confirmations: u64,
+    /// 单次等待回执的超时时间
+    pub receipt_timeout: Duration,
+    /// 超时后以提高手续费的方式重发的最大次数
+    pub max_retries: u32,
+}
+
+impl SyncConfig {
+    pub fn from_env() -> Self {
+        Self {
+            confirmations: env_or("SYNC_CONFIRMATIONS", 2),
+            receipt_timeout: Duration::from_secs(env_or("SYNC_RECEIPT_TIMEOUT_SECS", 120)),
+            max_retries: env_or("SYNC_MAX_RETRIES", 3),
+        }
+    }
+}
+
+/// 更新交易的发送与回执查询，由 `track_receipt` 驱动重试
+#[async_trait]
+pub trait ReceiptTracker: Send {
+    type Receipt: Send;
+
+    /// 发送当前交易，attempt 从 0 开始计数
+    async fn send(&mut self, attempt: u32) -> Result<TxHash>;
+
+    /// 等待 tx_hash 的回执；超时后依次查询 sent 中已发送的交易（被替换的旧交易也可能上链），
+    /// 都未上链时返回 None
+    async fn wait(&mut self, tx_hash: TxHash, sent: &[TxHash]) -> Result<Option<Self::Receipt>>;
+
+    /// 为替换交易提高手续费，无法再提高时返回错误
+    async fn bump(&mut self) -> Result<()>;
+}
+
+/// 发送交易并等待回执，超时后提高手续费以相同 nonce 重发，最多重发 `max_retries` 次。
+/// 替换交易发送失败时不再重发，在剩余的次数内继续等待已发送的交易。
+/// 返回上链交易的回执；重试用尽或手续费无法再提高时返回 None
+pub async fn track_receipt<T: ReceiptTracker>(
+    tracker: &mut T,
+    max_retries: u32,
+) -> Result<Option<T::Receipt>> {
+    let mut sent = vec![];
+    let mut replaceable = true;
+    for attempt in 0..=max_retries {
+        let tx_hash = match sent.last() {
+            Some(&last) if !replaceable => last,
+            _ => match tracker.send(attempt).await {
+                Ok(tx_hash) => {
+                    sent.push(tx_hash);
+                    tx_hash
+                }
+                // nonce too low 说明之前的交易已经上链，replacement underpriced 说明它仍在等待
+                Err(err) if !sent.is_empty() => {
+                    eprintln!("替换交易发送失败，继续等待已发送的交易: {}", err);
+                    replaceable = false;
+                    sent[sent.len() - 1]
+                }
+                Err(err) => return Err(err),
+            },
+        };
+        if let Some(receipt) = tracker.wait(tx_hash, &sent).await? {
+            return Ok(Some(receipt));
+        }
+        if attempt == max_retries || !replaceable {
+            continue;
+        }
+        if let Err(err) = tracker.bump().await {
+            eprintln!("更新交易 {:?} 无法继续替换: {}", tx_hash, err);
+            break;
+        }
+    }
+    Ok(None)
+}
+
+pub(crate) fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+// 记录承载这些地址的更新交易
+pub async fn mark_submitted(
+    store: &dyn BlacklistStore,
+    project: Address,
+    addresses: &[Address],
+    tx_hash: TxHash,
+    nonce: u64,
+) -> Result<(), sqlx::Error> {
+    store
+        .set_sync_status(
+            project,
+            addresses,
+            SyncStatus::Submitted,
+            Some(tx_hash),
+            Some(nonce),
+        )
+        .await
+}
+
+pub async fn mark_exported(
+    store: &dyn BlacklistStore,
+    project: Address,
+    addresses: &[Address],
+) -> Result<(), sqlx::Error> {
+    store
+        .set_sync_status(project, addresses, SyncStatus::Exported, None, None)
+        .await
+}
+
+pub async fn mark_synced(
+    store: &dyn BlacklistStore,
+    project: Address,
+    addresses: &[Address],
+    tx_hash: TxHash,
+) -> Result<(), sqlx::Error> {
+    store
+        .set_sync_status(project, addresses, SyncStatus::Synced, Some(tx_hash), None)
+        .await
+}
+
+// 链上已是拉黑状态，无需发送交易
+pub async fn mark_in_sync(
+    store: &dyn BlacklistStore,
+    project: Address,
+    addresses: &[Address],
+) -> Result<(), sqlx::Error> {
+    store
+        .set_sync_status(project, addresses, SyncStatus::Synced, None, None)
+        .await
+}
+
+// 交易失败后回退为待同步，等待下一批次重新发送
+pub async fn mark_failed(
+    store: &dyn BlacklistStore,
+    project: Address,
+    addresses: &[Address],
+) -> Result<(), sqlx::Error> {
+    store
+        .set_sync_status(project, addresses, SyncStatus::Pending, None, None)
+        .await
+}
+
+/// 未同步完成的地址及其最近一次的更新交易
+#[derive(Debug, Clone)]
+pub struct UnsyncedAddress {
+    pub address: Address,
+    pub project: Address,
+    pub status: SyncStatus,
+    pub tx_hash: Option<TxHash>,
+}
+
+impl UnsyncedAddress {
+    // 读取 (address, project, status, tx_hash) 行，无法解析时返回 None
+    pub(crate) fn from_row(
+        (address, project, status, tx_hash): (String, String, String, Option<String>),
+    ) -> Option<Self> {
+        Some(Self {
+            address: address.parse().ok()?,
+            project: project.parse().ok()?,
+            status: SyncStatus::from_db(&status),
+            tx_hash: tx_hash.and_then(|hash| hash.parse().ok()),
+        })
+    }
+}
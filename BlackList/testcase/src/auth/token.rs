@@ -0,0 +1,133 @@
+use alloy::{
+    primitives::Address,
+    providers::Provider,
+    rpc::types::Filter,
+    sol,
+    sol_types::{SolCall, SolEvent},
+    transports::Transport,
+};
+use anyhow::Result;
+use std::{collections::HashSet, env};
+
+use crate::auth::{data::REASON_SEPARATOR, reconcile::get_logs_chunked};
+
+sol! {
+    /// RegulatedToken 的代币级黑名单接口，写操作需要 REGULATOR_ROLE
+    #[sol(rpc)]
+    interface IRegulatedToken {
+        event Blacklisted(address indexed account);
+        event UnBlacklisted(address indexed account);
+
+        function addToBlacklist(address account) external;
+        function removeFromBlacklist(address account) external;
+        function isBlacklisted(address account) external view returns (bool);
+    }
+}
+
+/// 需要同步黑名单的 RegulatedToken 部署
+#[derive(Debug, Clone, Default)]
+pub struct TokenTargets {
+    pub tokens: Vec<Address>,
+    /// 只同步这些原因的记录，为 None 时同步全部。
+    /// 可以只写原因的开头一个词，例如 `reentrancy` 匹配检测器生成的全部原因
+    reasons: Option<HashSet<String>>,
+}
+
+impl TokenTargets {
+    /// `REGULATED_TOKENS` 以逗号分隔列出代币地址，`REGULATED_TOKEN_REASONS` 限定同步的原因
+    pub fn from_env() -> Result<Self> {
+        Self::parse(
+            env::var("REGULATED_TOKENS").ok().as_deref(),
+            env::var("REGULATED_TOKEN_REASONS").ok().as_deref(),
+        )
+    }
+
+    /// 按 `from_env` 的格式解析，参数为 None 时表示未设置
+    pub fn parse(tokens: Option<&str>, reasons: Option<&str>) -> Result<Self> {
+        let tokens = match tokens {
+            Some(tokens) => tokens
+                .split(',')
+                .filter(|t| !t.trim().is_empty())
+                .map(|t| t.trim().parse())
+                .collect::<Result<_, _>>()?,
+            None => vec![],
+        };
+        let reasons = reasons.map(|reasons| {
+            reasons
+                .split(',')
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty())
+                .collect()
+        });
+        Ok(Self { tokens, reasons })
+    }
+
+    pub fn contains(&self, target: &Address) -> bool {
+        self.tokens.contains(target)
+    }
+
+    /// 合并后的原因中任意一项命中即选中
+    pub fn selects(&self, reason: &str) -> bool {
+        let Some(reasons) = &self.reasons else {
+            return true;
+        };
+        reason.split(REASON_SEPARATOR).any(|part| {
+            reasons.iter().any(|selected| {
+                part == selected
+                    || part
+                        .strip_prefix(selected.as_str())
+                        .is_some_and(|rest| rest.starts_with(' '))
+            })
+        })
+    }
+}
+
+pub fn add_calldata(account: Address) -> Vec<u8> {
+    IRegulatedToken::addToBlacklistCall { account }.abi_encode()
+}
+
+pub fn remove_calldata(account: Address) -> Vec<u8> {
+    IRegulatedToken::removeFromBlacklistCall { account }.abi_encode()
+}
+
+pub async fn is_blacklisted<P, T>(provider: &P, token: Address, account: Address) -> Result<bool>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let token = IRegulatedToken::new(token, provider);
+    Ok(token.isBlacklisted(account).call().await?._0)
+}
+
+/// 通过 Blacklisted/UnBlacklisted 事件与数据库地址得到候选，再逐个调用 isBlacklisted 确认
+pub async fn onchain_token_blacklist<P, T>(
+    provider: &P,
+    token: Address,
+    from_block: u64,
+    db: &HashSet<Address>,
+) -> Result<HashSet<Address>>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let latest = provider.get_block_number().await?;
+    let filter = Filter::new().address(token).event_signature(vec![
+        IRegulatedToken::Blacklisted::SIGNATURE_HASH,
+        IRegulatedToken::UnBlacklisted::SIGNATURE_HASH,
+    ]);
+    let mut candidates = db.clone();
+    for log in get_logs_chunked(provider, filter, from_block, latest).await? {
+        // 两个事件的唯一参数都是 indexed 的账户地址
+        if let Some(topic) = log.topics().get(1) {
+            candidates.insert(Address::from_word(*topic));
+        }
+    }
+
+    let mut onchain = HashSet::new();
+    for account in candidates {
+        if is_blacklisted(provider, token, account).await? {
+            onchain.insert(account);
+        }
+    }
+    Ok(onchain)
+}
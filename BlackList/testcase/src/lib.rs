@@ -1 +1 @@
-mod auth;
+pub mod auth;
@@ -1,26 +1,522 @@
-use std::sync::Arc;
+use std::{
+    env,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+    sync::Arc,
+};
 
-use anyhow::Result;
-use auth::generate_data::{insert_random_blacklist_entries, setup_test_db};
+use alloy::primitives::{Address, FixedBytes};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use clap::{Parser, Subcommand};
+use testcase::auth::{
+    self,
+    allowlist::NewAllowlistEntry,
+    auth::{BlackListUpdater, RunMode, Startup},
+    bulk::{self, Format, ImportOptions},
+    data::{EntryStatus, BLACKLIST_TABLE},
+    expiry::ExpiryPolicy,
+    history::{ChangeContext, ChangeSource},
+    ofac::{self, SdnFormat, SdnList, OFAC_SDN_SOURCE},
+    pause::PauseTarget,
+    query::{BlacklistQuery, Cursor, SortField, SortOrder, DEFAULT_PAGE_SIZE},
+    store::{connect, database_url, BlacklistStore},
+};
 
-mod auth;
 /// 监控链上交易，更新黑白名单模块
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 监控新区块并同步黑名单（默认）
+    Run,
+    /// 离线回放录制的区块 trace
+    Replay {
+        /// 录制文件所在目录
+        dir: PathBuf,
+    },
+    /// 对比数据库与链上鉴权模块、RegulatedToken 的黑名单
+    Reconcile {
+        /// 开始索引事件的区块
+        #[arg(long, default_value_t = 0)]
+        from_block: u64,
+        /// 发送修复交易，否则只输出差异
+        #[arg(long)]
+        apply: bool,
+    },
+    /// 执行数据库迁移
+    Migrate,
+    /// 按条件分页列出黑名单记录
+    List {
+        #[command(flatten)]
+        filters: Filters,
+        /// id、added_at、updated_at 或 address
+        #[arg(long, default_value = "id")]
+        sort: SortField,
+        /// asc 或 desc
+        #[arg(long, default_value = "asc")]
+        order: SortOrder,
+        #[arg(long, default_value_t = DEFAULT_PAGE_SIZE)]
+        limit: u32,
+        /// 上一页输出的游标
+        #[arg(long)]
+        after: Option<Cursor>,
+    },
+    /// 从 CSV、JSON 或 JSONL 文件批量导入黑名单
+    Import {
+        file: PathBuf,
+        /// csv、json 或 jsonl，默认按扩展名推断
+        #[arg(long)]
+        format: Option<Format>,
+        /// 只校验并统计，不写入数据库
+        #[arg(long)]
+        dry_run: bool,
+        /// 记录缺少原因时使用
+        #[arg(long, default_value = "Imported")]
+        reason: String,
+        /// 记录缺少状态时使用
+        #[arg(long, default_value = "active")]
+        status: EntryStatus,
+        /// 记录缺少到期时间时的有效天数，默认按原因使用到期策略
+        #[arg(long)]
+        expires_in_days: Option<i64>,
+        /// 记录缺少创建人时使用，例如合作方名称
+        #[arg(long)]
+        created_by: Option<String>,
+    },
+    /// 将满足条件的黑名单及证据引用导出为 CSV、JSON 或 JSONL
+    Export {
+        file: PathBuf,
+        /// csv、json 或 jsonl，默认按扩展名推断
+        #[arg(long)]
+        format: Option<Format>,
+        #[command(flatten)]
+        filters: Filters,
+    },
+    /// 导入 OFAC SDN 名单中的数字货币地址，与上次导入的版本对比后新增或撤销
+    Sanctions {
+        /// 从 OFAC 下载的 sdn.xml 或 sdn.csv
+        file: PathBuf,
+        /// xml 或 csv，默认按扩展名推断
+        #[arg(long)]
+        format: Option<SdnFormat>,
+        /// 写入数据库并同步链上，否则只输出差异
+        #[arg(long)]
+        apply: bool,
+    },
+    /// 查看地址的黑名单记录及其证据
+    Evidence {
+        /// 黑名单地址
+        address: Address,
+    },
+    /// 修改地址的黑名单状态，进入或离开 active 时同步链上
+    Status {
+        /// 黑名单地址
+        address: Address,
+        /// pending_review、active、suspended、expired、revoked 或 appealed
+        status: EntryStatus,
+    },
+    /// 查看地址的完整变更历史
+    History {
+        /// 黑名单地址
+        address: Address,
+    },
+    /// 将地址加入白名单，检测结果与同步都不会再拉黑该地址
+    Allow {
+        /// 白名单地址
+        address: Address,
+        /// 加入白名单的原因
+        #[arg(long)]
+        reason: String,
+        /// 负责人，默认为当前用户
+        #[arg(long)]
+        owner: Option<String>,
+    },
+    /// 将地址移出白名单
+    Disallow {
+        /// 白名单地址
+        address: Address,
+    },
+    /// 列出白名单及各地址被抑制的检测次数
+    Allowlist,
+    /// 列出自动暂停且尚未恢复的项目与函数
+    Pauses,
+    /// 人工确认后恢复被自动暂停的项目或函数
+    Unpause {
+        /// 项目地址
+        project: Address,
+        /// 被暂停的函数选择器，不指定时恢复整个项目
+        #[arg(long)]
+        selector: Option<FixedBytes<4>>,
+    },
+}
+
+/// 列表与导出共用的过滤条件
+#[derive(clap::Args, Debug)]
+struct Filters {
+    /// 链类型，例如 ETH
+    #[arg(long)]
+    chain: Option<String>,
+    /// 状态，可重复指定
+    #[arg(long)]
+    status: Vec<EntryStatus>,
+    /// 原因包含的关键字
+    #[arg(long)]
+    reason: Option<String>,
+    #[arg(long)]
+    created_by: Option<String>,
+    /// 有该检测器或名单来源的证据，例如 reentrancy、ofac-sdn
+    #[arg(long)]
+    detector: Option<String>,
+    /// 加入时间不早于，RFC 3339 格式
+    #[arg(long)]
+    added_after: Option<DateTime<Utc>>,
+    /// 加入时间早于，RFC 3339 格式
+    #[arg(long)]
+    added_before: Option<DateTime<Utc>>,
+    /// 到期时间不早于，RFC 3339 格式
+    #[arg(long)]
+    expires_after: Option<DateTime<Utc>>,
+    /// 到期时间早于，RFC 3339 格式
+    #[arg(long)]
+    expires_before: Option<DateTime<Utc>>,
+    /// 地址前缀
+    #[arg(long)]
+    prefix: Option<String>,
+}
+
+impl Filters {
+    fn query(self) -> BlacklistQuery {
+        BlacklistQuery {
+            chain_type: self.chain,
+            statuses: self.status,
+            reason: self.reason,
+            created_by: self.created_by,
+            detector: self.detector,
+            added_after: self.added_after,
+            added_before: self.added_before,
+            expires_after: self.expires_after,
+            expires_before: self.expires_before,
+            address_prefix: self.prefix,
+            ..Default::default()
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv::dotenv().ok();
+    let args = Args::parse();
 
-    // let pool = setup_test_db().await;
+    // let store = setup_test_db().await;
 
     // // 插入50条黑名单数据
-    // if let Err(e) = insert_random_blacklist_entries(&pool).await {
+    // if let Err(e) = insert_random_blacklist_entries(store.as_ref(), &ExpiryPolicy::from_env()?).await {
     //     eprintln!("Error inserting entries: {}", e);
     // }
 
-    let updater = Arc::new(auth::auth::BlackListUpdater::new().await?);
-    if let Ok(mut set) = updater.run().await {
-        while let Some(res) = set.join_next().await {
-            println!("res: {:?}", res);
+    match args.command.unwrap_or(Command::Run) {
+        Command::Run => {
+            let updater = Arc::new(BlackListUpdater::new().await?);
+            if let Ok(mut set) = updater.run().await {
+                while let Some(res) = set.join_next().await {
+                    println!("res: {:?}", res);
+                }
+            }
+        }
+        Command::Migrate => {
+            let url = database_url().ok_or_else(|| anyhow!("未配置 DATABASE_URL/MYSQL_URL"))?;
+            connect(&url, BLACKLIST_TABLE).await?.migrate().await?;
+            println!("数据库迁移完成");
+        }
+        Command::List {
+            filters,
+            sort,
+            order,
+            limit,
+            after,
+        } => {
+            let store = open_store().await?;
+            let mut query = filters.query().sort_by(sort, order).limit(limit);
+            query.after = after;
+            let page = store.query(&query).await?;
+            for entry in &page.entries {
+                println!(
+                    "#{} {} [{}] {} {} 加入于 {} 到期 {:?}",
+                    entry.id,
+                    entry.address,
+                    entry.status,
+                    entry.chain_type,
+                    entry.reason,
+                    entry.added_at,
+                    entry.expires_at
+                );
+            }
+            println!("共 {} 条", store.count(&query).await?);
+            if let Some(next) = page.next {
+                println!("下一页: --after {}", next);
+            }
+        }
+        Command::Import {
+            file,
+            format,
+            dry_run,
+            reason,
+            status,
+            expires_in_days,
+            created_by,
+        } => {
+            let store = open_store().await?;
+            let format = match format {
+                Some(format) => format,
+                None => Format::from_path(&file)?,
+            };
+            let options = ImportOptions {
+                dry_run,
+                default_reason: reason,
+                default_status: status,
+                default_ttl: expires_in_days.map(Duration::days),
+                created_by,
+                ..Default::default()
+            };
+            let context = ChangeContext {
+                source: ChangeSource::Import,
+                actor: env::var("USER").ok(),
+            };
+            let report = bulk::import(
+                store.as_ref(),
+                File::open(&file)?,
+                format,
+                &options,
+                &ExpiryPolicy::from_env()?,
+                &context,
+            )
+            .await?;
+            for error in &report.errors {
+                println!("第 {} 行: {}", error.line, error.message);
+            }
+            println!(
+                "{}共 {} 条：新增 {}，合并 {}，文件内重复 {}，未生效 {}，无效 {}",
+                if dry_run { "试运行，" } else { "" },
+                report.total,
+                report.inserted,
+                report.merged,
+                report.duplicates,
+                report.held,
+                report.errors.len()
+            );
+            if !dry_run && report.inserted > 0 {
+                println!("执行 reconcile --apply 将新增的地址同步上链");
+            }
+        }
+        Command::Export {
+            file,
+            format,
+            filters,
+        } => {
+            let store = open_store().await?;
+            let format = match format {
+                Some(format) => format,
+                None => Format::from_path(&file)?,
+            };
+            let writer = BufWriter::new(File::create(&file)?);
+            let count = bulk::export(store.as_ref(), writer, format, &filters.query()).await?;
+            println!("已导出 {} 条到 {}", count, file.display());
+        }
+        Command::Sanctions {
+            file,
+            format,
+            apply,
+        } => {
+            let store = open_store().await?;
+            let format = match format {
+                Some(format) => format,
+                None => SdnFormat::from_path(&file)?,
+            };
+            let list = SdnList::parse(BufReader::new(File::open(&file)?), format)?;
+            println!(
+                "名单发布于 {}，EVM 地址 {} 个，其他链地址 {} 个未导入",
+                list.published.as_deref().unwrap_or("-"),
+                list.addresses.len(),
+                list.unsupported.len()
+            );
+            let diff = ofac::diff(store.as_ref(), &list).await?;
+            for sdn in &diff.to_add {
+                println!("新增 {} [{}] {}", sdn.address, sdn.chain_type, sdn.reason());
+            }
+            for entry in &diff.to_revoke {
+                println!(
+                    "撤销 {} [{}] {}",
+                    entry.address, entry.chain_type, entry.reason
+                );
+            }
+            for entry in &diff.retained {
+                println!(
+                    "保留 {} [{}] 已移出名单，但有其他证据",
+                    entry.address, entry.chain_type
+                );
+            }
+            if apply {
+                let updater = BlackListUpdater::start(Startup::Command).await?;
+                let context = ChangeContext::new(ChangeSource::Import).with_actor(OFAC_SDN_SOURCE);
+                let applied = ofac::apply(store.as_ref(), &updater, &diff, &context).await?;
+                println!(
+                    "新增 {}，合并 {}，未生效 {}，撤销 {}，同步失败 {}",
+                    applied.inserted, applied.merged, applied.held, applied.revoked, applied.failed
+                );
+            }
+        }
+        Command::Evidence { address } => {
+            let store = open_store().await?;
+            let entry = store
+                .find(&address)
+                .await?
+                .ok_or_else(|| anyhow!("{} 不在黑名单中", address))?;
+            println!("{} [{}] {}", entry.address, entry.status, entry.reason);
+            for evidence in store.list_evidence(entry.id).await? {
+                println!(
+                    "{} {} 区块 {:?} 交易 {:?} 项目 {} 函数 {:?} 损失 {} wei",
+                    evidence.detector,
+                    evidence.severity,
+                    evidence.block_number,
+                    evidence.tx_hash,
+                    evidence.project,
+                    evidence.func_sig,
+                    evidence.loss_wei
+                );
+                println!("  调用路径: {}", evidence.call_path);
+            }
+        }
+        Command::Status { address, status } => {
+            let updater = BlackListUpdater::start(Startup::Command).await?;
+            let from = updater
+                .set_status(address, status, &ChangeContext::cli())
+                .await?;
+            updater.flush_address(address).await?;
+            println!("{} 状态 {} -> {}", address, from, status);
+        }
+        Command::History { address } => {
+            let store = open_store().await?;
+            for record in store.history(&address).await? {
+                println!(
+                    "{} #{} {} 来源 {} 操作人 {}",
+                    record.changed_at,
+                    record.entry_id,
+                    record.action,
+                    record.source,
+                    record.actor.as_deref().unwrap_or("-")
+                );
+                println!("  旧值: {}", record.old_value.as_deref().unwrap_or("-"));
+                println!("  新值: {}", record.new_value.as_deref().unwrap_or("-"));
+            }
+        }
+        Command::Allow {
+            address,
+            reason,
+            owner,
+        } => {
+            let store = open_store().await?;
+            let owner = owner
+                .or_else(|| env::var("USER").ok())
+                .ok_or_else(|| anyhow!("请通过 --owner 指定负责人"))?;
+            store
+                .allow(&NewAllowlistEntry {
+                    address,
+                    chain_type: "ETH".to_string(),
+                    reason,
+                    owner,
+                })
+                .await?;
+            println!("已将 {} 加入白名单", address);
+            // 白名单只阻止新的拉黑，已生效的记录需要人工撤销
+            if let Some(entry) = store.find(&address).await? {
+                if entry.status.parse::<EntryStatus>()?.is_listed() {
+                    println!(
+                        "{} 仍在黑名单中，执行 status {} revoked 从链上移除",
+                        address, address
+                    );
+                }
+            }
+        }
+        Command::Disallow { address } => {
+            let store = open_store().await?;
+            if store.disallow(&address).await? {
+                println!("已将 {} 移出白名单", address);
+            } else {
+                println!("{} 不在白名单中", address);
+            }
+        }
+        Command::Allowlist => {
+            let store = open_store().await?;
+            for entry in store.list_allowlist().await? {
+                println!(
+                    "{} [{}] {} 负责人 {} 加入于 {} 抑制 {} 次 最近 {:?}",
+                    entry.address,
+                    entry.chain_type,
+                    entry.reason,
+                    entry.owner,
+                    entry.added_at,
+                    entry.suppressed_count,
+                    entry.last_suppressed_at
+                );
+            }
+        }
+        Command::Replay { dir } => {
+            // 未配置数据库时只输出检测结果
+            let store = match database_url() {
+                Some(_) => Some(open_store().await?),
+                None => None,
+            };
+            let found = auth::replay::replay(&dir, store.as_deref()).await?;
+            println!("回放完成，共检测到 {} 个地址", found.len());
+        }
+        Command::Reconcile { from_block, apply } => {
+            // 只输出差异时不需要签名
+            let startup = if apply {
+                Startup::Command
+            } else {
+                Startup::ReadOnly
+            };
+            let updater = BlackListUpdater::start(startup).await?;
+            for plan in updater.reconcile(from_block, apply).await? {
+                println!("目标 {}", plan.project);
+                println!("待添加: {:?}", plan.to_add);
+                println!("待移除: {:?}", plan.to_remove);
+                println!("待添加函数级拦截: {:?}", plan.scopes_to_add);
+                println!("待移除函数级拦截: {:?}", plan.scopes_to_remove);
+            }
+        }
+        Command::Pauses => {
+            for record in open_store().await?.list_active_pauses().await? {
+                println!(
+                    "{:?} 攻击者 {} 暂停于 {}",
+                    record.target, record.attacker, record.paused_at
+                );
+            }
+        }
+        Command::Unpause { project, selector } => {
+            let target = match selector {
+                Some(selector) => PauseTarget::Function { project, selector },
+                None => PauseTarget::Project(project),
+            };
+            let updater = BlackListUpdater::start(Startup::Command).await?;
+            updater.unpause(target).await?;
+            println!("已恢复 {:?}", target);
         }
     }
     Ok(())
 }
+
+// 连接当前模式下的黑名单表并检查 schema 版本
+async fn open_store() -> Result<Arc<dyn BlacklistStore>> {
+    let url = database_url().ok_or_else(|| anyhow!("未配置 DATABASE_URL/MYSQL_URL"))?;
+    let store = connect(&url, RunMode::from_env().table()).await?;
+    store.verify_schema().await?;
+    Ok(store)
+}
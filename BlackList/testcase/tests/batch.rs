@@ -0,0 +1,137 @@
+use alloy::primitives::Address;
+use std::time::Duration;
+use testcase::auth::{
+    batch::{BatchPolicy, PendingQueue},
+    evidence::Severity,
+};
+use tokio::time::Instant;
+
+fn policy() -> BatchPolicy {
+    BatchPolicy {
+        max_batch_size: 3,
+        max_latency: Duration::from_secs(30),
+        gas_budget: 200_000,
+        flush_severity: Some(Severity::High),
+    }
+}
+
+fn addresses(count: u8) -> Vec<Address> {
+    (1..=count).map(Address::repeat_byte).collect()
+}
+
+/// 每个地址约 50k gas，另加 21k 基础开销
+async fn estimate(batch: Vec<Address>) -> anyhow::Result<u64> {
+    Ok(21_000 + 50_000 * batch.len() as u64)
+}
+
+#[test]
+fn urgency_follows_severity() {
+    let policy = policy();
+    assert!(!policy.is_urgent(Severity::Medium));
+    assert!(policy.is_urgent(Severity::High));
+    assert!(policy.is_urgent(Severity::Critical));
+
+    let disabled = BatchPolicy {
+        flush_severity: None,
+        ..policy
+    };
+    assert!(!disabled.is_urgent(Severity::Critical));
+}
+
+#[test]
+fn flushes_on_size_latency_or_urgency() {
+    let policy = policy();
+    let mut queue = PendingQueue::default();
+    assert!(!policy.should_flush(&queue, Instant::now()));
+
+    queue.push(Address::repeat_byte(1), false);
+    queue.push(Address::repeat_byte(2), false);
+    // 重复入队不增加长度
+    queue.push(Address::repeat_byte(2), false);
+    assert_eq!(queue.len(), 2);
+    assert!(!policy.should_flush(&queue, Instant::now()));
+    // 最早的地址等待超时
+    assert!(policy.should_flush(&queue, Instant::now() + policy.max_latency));
+
+    queue.push(Address::repeat_byte(3), false);
+    assert!(policy.should_flush(&queue, Instant::now()));
+
+    let mut queue = PendingQueue::default();
+    queue.push(Address::repeat_byte(1), false);
+    queue.push(Address::repeat_byte(1), true);
+    assert!(policy.should_flush(&queue, Instant::now()));
+    queue.retain(|_| false);
+    assert!(!policy.should_flush(&queue, Instant::now()));
+}
+
+#[test]
+fn flush_severity_is_validated() {
+    assert_eq!(
+        BatchPolicy::parse_flush_severity(None).unwrap(),
+        Some(Severity::High)
+    );
+    assert_eq!(BatchPolicy::parse_flush_severity(Some(" ")).unwrap(), None);
+    assert_eq!(
+        BatchPolicy::parse_flush_severity(Some("critical")).unwrap(),
+        Some(Severity::Critical)
+    );
+    assert!(BatchPolicy::parse_flush_severity(Some("urgent")).is_err());
+}
+
+#[test]
+fn chunks_by_batch_size() {
+    let chunks = policy().chunks(&addresses(7));
+    let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+    assert_eq!(sizes, [3, 3, 1]);
+    assert_eq!(chunks.concat(), addresses(7));
+}
+
+#[tokio::test]
+async fn splits_batches_over_gas_budget() {
+    let policy = policy();
+    // 3 个地址 171k，在预算内
+    let split = policy.split_by_gas(addresses(3), estimate).await.unwrap();
+    assert_eq!(split.batches, [(addresses(3), 171_000)]);
+    assert!(split.oversized.is_empty());
+
+    // 7 个地址拆分为 3 + 2 + 2，顺序不变
+    let split = policy.split_by_gas(addresses(7), estimate).await.unwrap();
+    let sizes: Vec<usize> = split.batches.iter().map(|(batch, _)| batch.len()).collect();
+    assert_eq!(sizes, [3, 2, 2]);
+    let joined: Vec<Address> = split
+        .batches
+        .into_iter()
+        .flat_map(|(batch, _)| batch)
+        .collect();
+    assert_eq!(joined, addresses(7));
+
+    // 单个地址超出预算时不发送
+    let tight = BatchPolicy {
+        gas_budget: 100_000,
+        ..policy
+    };
+    let split = tight
+        .split_by_gas(addresses(2), |batch| async move {
+            // 第一个地址需要额外 gas
+            let extra = if batch[0] == Address::repeat_byte(1) {
+                50_000
+            } else {
+                0
+            };
+            Ok(21_000 + 50_000 * batch.len() as u64 + extra)
+        })
+        .await
+        .unwrap();
+    assert_eq!(split.batches, [(vec![Address::repeat_byte(2)], 71_000)]);
+    assert_eq!(split.oversized, [(Address::repeat_byte(1), 121_000)]);
+    assert!(split
+        .batches
+        .iter()
+        .all(|(_, gas)| *gas <= tight.gas_budget));
+
+    // 估算失败时返回错误
+    let failed = policy
+        .split_by_gas(addresses(1), |_| async { Err(anyhow::anyhow!("reverted")) })
+        .await;
+    assert!(failed.is_err());
+}
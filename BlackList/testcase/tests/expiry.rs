@@ -0,0 +1,114 @@
+use chrono::{DateTime, Duration, Utc};
+use testcase::auth::{
+    evidence::{Severity, REENTRANCY_DETECTOR},
+    expiry::ExpiryPolicy,
+};
+
+fn now() -> DateTime<Utc> {
+    "2024-01-01T00:00:00Z".parse().unwrap()
+}
+
+#[test]
+fn defaults_apply_without_overrides() {
+    let policy = ExpiryPolicy::parse(None, None, None).unwrap();
+    let from = now();
+    assert_eq!(
+        policy.expires_at("Scam", from),
+        Some(from + Duration::days(180))
+    );
+    assert_eq!(policy.expires_at("Money laundering", from), None);
+    assert_eq!(
+        policy.expires_at("unlisted", from),
+        Some(from + Duration::days(30))
+    );
+    assert_eq!(
+        policy.expires_for(REENTRANCY_DETECTOR, Severity::Medium, from),
+        Some(from + Duration::days(30))
+    );
+    // 触发自动暂停的检测结果与 High 一样到期，不会永久拉黑
+    assert_eq!(
+        policy.expires_for(REENTRANCY_DETECTOR, Severity::Critical, from),
+        Some(from + Duration::days(180))
+    );
+}
+
+#[test]
+fn overrides_replace_defaults() {
+    let policy = ExpiryPolicy::parse(
+        Some("0"),
+        Some(" Scam = 7 ,Phishing attempt=0,"),
+        Some("medium=1, critical=365"),
+    )
+    .unwrap();
+    let from = now();
+    assert_eq!(
+        policy.expires_at("Scam", from),
+        Some(from + Duration::days(7))
+    );
+    assert_eq!(policy.expires_at("Phishing attempt", from), None);
+    // 默认值为 0 时未列出的原因永久有效
+    assert_eq!(policy.expires_at("unlisted", from), None);
+    assert_eq!(
+        policy.expires_for(REENTRANCY_DETECTOR, Severity::Medium, from),
+        Some(from + Duration::days(1))
+    );
+    assert_eq!(
+        policy.expires_for(REENTRANCY_DETECTOR, Severity::High, from),
+        Some(from + Duration::days(180))
+    );
+    assert_eq!(
+        policy.expires_for(REENTRANCY_DETECTOR, Severity::Critical, from),
+        Some(from + Duration::days(365))
+    );
+}
+
+#[test]
+fn detector_reason_overrides_severity() {
+    let policy = ExpiryPolicy::parse(None, Some("reentrancy=0"), None).unwrap();
+    let from = now();
+    // 永久拉黑只能按检测器原因显式配置
+    assert_eq!(
+        policy.expires_for(REENTRANCY_DETECTOR, Severity::Medium, from),
+        None
+    );
+    assert_eq!(
+        policy.expires_for("other", Severity::Critical, from),
+        Some(from + Duration::days(180))
+    );
+
+    // 默认原因表中的永久原因不影响检测结果
+    let policy = ExpiryPolicy::parse(None, None, None).unwrap();
+    assert_eq!(
+        policy.expires_for("Money laundering", Severity::High, from),
+        Some(from + Duration::days(180))
+    );
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    for default_days in ["-1", "abc", "", "9223372036854775807"] {
+        assert!(
+            ExpiryPolicy::parse(Some(default_days), None, None).is_err(),
+            "{default_days:?}"
+        );
+    }
+    for reasons in ["Scam", "Scam=-5", "Scam=ten", "Scam=9223372036854775807"] {
+        assert!(
+            ExpiryPolicy::parse(None, Some(reasons), None).is_err(),
+            "{reasons:?}"
+        );
+    }
+    for severities in ["severe=7", "high", "high=-1", "critical=0"] {
+        assert!(
+            ExpiryPolicy::parse(None, None, Some(severities)).is_err(),
+            "{severities:?}"
+        );
+    }
+}
+
+#[test]
+fn expiry_beyond_representable_time_is_permanent() {
+    // 在有效范围内但加到起点后超出 DateTime 上限
+    let policy = ExpiryPolicy::parse(Some("100000000000"), None, None).unwrap();
+    assert_eq!(policy.expires_at("unlisted", now()), None);
+}
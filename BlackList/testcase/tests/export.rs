@@ -0,0 +1,217 @@
+use alloy::{
+    primitives::{address, b256, bytes, Address, B256, U256},
+    rpc::types::trace::geth::{CallFrame, GethTrace, TraceResult},
+};
+use std::{env, fs, time::Duration};
+use testcase::auth::export::{
+    archive_batch, load_pending, serve_listener, write_batch, ExportConfig, ExportedBatch,
+    HttpExport, RawTransaction,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+const SAFE: Address = address!("5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe");
+const REGISTRY: Address = address!("cccccccccccccccccccccccccccccccccccccccc");
+const PROJECT: Address = address!("dddddddddddddddddddddddddddddddddddddddd");
+const ATTACKER: Address = address!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+const EXECUTED: B256 = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+
+fn batch(id: &str) -> ExportedBatch {
+    ExportedBatch {
+        id: id.to_string(),
+        transactions: vec![
+            RawTransaction {
+                to: REGISTRY,
+                value: U256::ZERO,
+                data: bytes!("01"),
+            },
+            RawTransaction {
+                to: REGISTRY,
+                value: U256::from(1),
+                data: bytes!("02"),
+            },
+        ],
+        project: PROJECT,
+        addresses: vec![ATTACKER],
+        scope: None,
+    }
+}
+
+/// 每个测试使用独立的导出目录
+fn config(name: &str) -> ExportConfig {
+    let dir = env::temp_dir().join(format!("blacklist-export-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    ExportConfig {
+        dir,
+        safe: SAFE,
+        http: None,
+        check_interval: Duration::from_secs(300),
+    }
+}
+
+fn call(to: Address, input: &'static [u8], calls: Vec<CallFrame>) -> CallFrame {
+    CallFrame {
+        to: Some(to),
+        input: input.into(),
+        calls,
+        ..Default::default()
+    }
+}
+
+fn trace(frame: CallFrame) -> TraceResult {
+    TraceResult::Success {
+        result: GethTrace::CallTracer(frame),
+        tx_hash: Some(EXECUTED),
+    }
+}
+
+#[test]
+fn transaction_builder_json_lists_every_call() {
+    let json = batch("7").transaction_builder_json(11155111, SAFE);
+    assert_eq!(json["chainId"], "11155111");
+    assert_eq!(json["meta"]["name"], "blacklist-7");
+    assert_eq!(json["meta"]["createdFromSafeAddress"], SAFE.to_string());
+
+    let transactions = json["transactions"].as_array().unwrap();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[0]["to"], REGISTRY.to_string());
+    assert_eq!(transactions[0]["data"], "0x01");
+    assert_eq!(transactions[1]["value"], "1");
+}
+
+#[test]
+fn executed_batch_is_found_in_nested_calls() {
+    let batch = batch("1");
+    // Safe 通过 MultiSend 调用注册表
+    let multisend = call(
+        SAFE,
+        b"exec",
+        vec![call(PROJECT, b"multi", vec![call(REGISTRY, &[2], vec![])])],
+    );
+    assert_eq!(batch.executed_in(&[trace(multisend)]), Some(EXECUTED));
+
+    // 只执行了前面的交易时不算完成
+    let partial = call(SAFE, b"exec", vec![call(REGISTRY, &[1], vec![])]);
+    assert_eq!(batch.executed_in(&[trace(partial)]), None);
+}
+
+#[test]
+fn reverted_calls_do_not_count() {
+    let batch = batch("1");
+    let mut outer = call(SAFE, b"exec", vec![call(REGISTRY, &[2], vec![])]);
+    outer.error = Some("execution reverted".to_string());
+    assert_eq!(batch.executed_in(&[trace(outer)]), None);
+
+    let mut inner = call(REGISTRY, &[2], vec![]);
+    inner.error = Some("execution reverted".to_string());
+    let outer = call(SAFE, b"exec", vec![inner]);
+    assert_eq!(batch.executed_in(&[trace(outer)]), None);
+}
+
+#[test]
+fn pending_batches_round_trip_and_archive() {
+    let config = config("round-trip");
+    assert!(load_pending(&config).unwrap().is_empty());
+
+    write_batch(&config, 1, &batch("2")).unwrap();
+    write_batch(&config, 1, &batch("1")).unwrap();
+    let pending = load_pending(&config).unwrap();
+    let ids: Vec<_> = pending.iter().map(|batch| batch.id.as_str()).collect();
+    assert_eq!(ids, ["1", "2"]);
+    assert_eq!(pending[0].transactions, batch("1").transactions);
+    assert_eq!(pending[0].addresses, [ATTACKER]);
+
+    archive_batch(&config, &pending[0]).unwrap();
+    let ids: Vec<_> = load_pending(&config)
+        .unwrap()
+        .into_iter()
+        .map(|batch| batch.id)
+        .collect();
+    assert_eq!(ids, ["2"]);
+    for name in ["1.json", "1.raw.json", "1.batch.json"] {
+        assert!(config.dir.join("executed").join(name).exists());
+    }
+}
+
+#[test]
+fn http_export_defaults_to_localhost_and_requires_token() {
+    let http = HttpExport::parse("8080", Some("secret".to_string())).unwrap();
+    assert_eq!(http.addr, "127.0.0.1:8080");
+    let http = HttpExport::parse(":8080", Some("secret".to_string())).unwrap();
+    assert_eq!(http.addr, "127.0.0.1:8080");
+    let http = HttpExport::parse("0.0.0.0:8080", Some("secret".to_string())).unwrap();
+    assert_eq!(http.addr, "0.0.0.0:8080");
+
+    assert!(HttpExport::parse("8080", None).is_err());
+    assert!(HttpExport::parse("8080", Some(String::new())).is_err());
+}
+
+async fn get(addr: &str, request: String) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn http_export_rejects_requests_without_token() {
+    let config = config("http");
+    write_batch(&config, 1, &batch("1")).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(serve_listener(config, "secret".to_string(), listener));
+
+    let response = get(&addr, "GET / HTTP/1.1\r\n\r\n".to_string()).await;
+    assert!(response.starts_with("HTTP/1.1 401"));
+    let response = get(
+        &addr,
+        "GET / HTTP/1.1\r\nAuthorization: Bearer wrong!\r\n\r\n".to_string(),
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 401"));
+
+    let response = get(
+        &addr,
+        "GET / HTTP/1.1\r\nHost: localhost\r\nauthorization: Bearer secret\r\n\r\n".to_string(),
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.ends_with(r#"["1.batch.json","1.json","1.raw.json"]"#));
+
+    let response = get(
+        &addr,
+        "GET /../secret HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n".to_string(),
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 404"));
+}
+
+#[tokio::test]
+async fn http_export_reads_long_headers_and_rejects_oversized_ones() {
+    let config = config("http-headers");
+    write_batch(&config, 1, &batch("1")).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(serve_listener(config, "secret".to_string(), listener));
+
+    // 令牌位于第一个 1024 字节之后
+    let padding = "x".repeat(2000);
+    let response = get(
+        &addr,
+        format!(
+            "GET /1.json HTTP/1.1\r\nCookie: {padding}\r\nAuthorization: Bearer secret\r\n\r\n"
+        ),
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.contains("blacklist-1"));
+
+    // 请求头超过上限仍未结束
+    let mut request = "GET / HTTP/1.1\r\nCookie: ".to_string();
+    request.push_str(&"x".repeat(8200 - request.len()));
+    let response = get(&addr, request).await;
+    assert!(response.starts_with("HTTP/1.1 400"));
+}
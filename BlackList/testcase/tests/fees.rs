@@ -0,0 +1,95 @@
+use alloy::{providers::utils::Eip1559Estimation, rpc::types::TransactionRequest};
+use testcase::auth::fees::FeeConfig;
+
+const GWEI: u128 = 1_000_000_000;
+
+fn config() -> FeeConfig {
+    FeeConfig {
+        max_fee_cap: 100 * GWEI,
+        priority_fee_cap: 5 * GWEI,
+        gas_margin_percent: 20,
+        gas_limit_cap: 1_000_000,
+        bump_percent: 15,
+    }
+}
+
+fn fees(max_fee_gwei: u128, priority_fee_gwei: u128) -> Eip1559Estimation {
+    Eip1559Estimation {
+        max_fee_per_gas: max_fee_gwei * GWEI,
+        max_priority_fee_per_gas: priority_fee_gwei * GWEI,
+    }
+}
+
+#[test]
+fn gas_limit_adds_margin_up_to_cap() {
+    let config = config();
+    let mut tx = TransactionRequest::default();
+    config.set_gas_limit(&mut tx, 500_000).unwrap();
+    assert_eq!(tx.gas, Some(600_000));
+
+    let err = config.set_gas_limit(&mut tx, 900_000).unwrap_err();
+    assert!(err.to_string().contains("超过上限"));
+    // 被拒绝时不修改交易
+    assert_eq!(tx.gas, Some(600_000));
+}
+
+#[test]
+fn fees_respect_caps() {
+    let config = config();
+    let mut tx = TransactionRequest::default();
+    config.set_fees(&mut tx, fees(30, 8)).unwrap();
+    assert_eq!(tx.max_fee_per_gas, Some(30 * GWEI));
+    // 小费按上限截断
+    assert_eq!(tx.max_priority_fee_per_gas, Some(5 * GWEI));
+
+    // maxFeePerGas 不低于小费
+    config.set_fees(&mut tx, fees(2, 3)).unwrap();
+    assert_eq!(tx.max_fee_per_gas, Some(3 * GWEI));
+
+    assert!(config.set_fees(&mut tx, fees(101, 1)).is_err());
+}
+
+#[test]
+fn bump_raises_fees_by_percent_or_to_current() {
+    let config = config();
+    let mut tx = TransactionRequest::default();
+    config.set_fees(&mut tx, fees(20, 2)).unwrap();
+
+    config.bump_fees(&mut tx, fees(10, 1)).unwrap();
+    assert_eq!(tx.max_fee_per_gas, Some(23 * GWEI));
+    assert_eq!(tx.max_priority_fee_per_gas, Some(2 * GWEI + 3 * GWEI / 10));
+
+    // 当前估算高于提高后的手续费时取当前值
+    config.bump_fees(&mut tx, fees(40, 4)).unwrap();
+    assert_eq!(tx.max_fee_per_gas, Some(40 * GWEI));
+    assert_eq!(tx.max_priority_fee_per_gas, Some(4 * GWEI));
+}
+
+#[test]
+fn bump_always_increases_and_stops_at_cap() {
+    let config = config();
+    // 原手续费为 0 时也至少提高 1 wei
+    let mut tx = TransactionRequest::default();
+    config
+        .bump_fees(
+            &mut tx,
+            Eip1559Estimation {
+                max_fee_per_gas: 0,
+                max_priority_fee_per_gas: 0,
+            },
+        )
+        .unwrap();
+    assert_eq!(tx.max_fee_per_gas, Some(1));
+    assert_eq!(tx.max_priority_fee_per_gas, Some(1));
+
+    let mut tx = TransactionRequest::default();
+    config.set_fees(&mut tx, fees(90, 1)).unwrap();
+    let err = config.bump_fees(&mut tx, fees(10, 1)).unwrap_err();
+    assert!(err.to_string().contains("超过上限"));
+    assert_eq!(tx.max_fee_per_gas, Some(90 * GWEI));
+
+    // 小费达到上限后无法再替换
+    let mut tx = TransactionRequest::default();
+    config.set_fees(&mut tx, fees(20, 5)).unwrap();
+    assert!(config.bump_fees(&mut tx, fees(20, 5)).is_err());
+}
@@ -0,0 +1,153 @@
+use alloy::primitives::{address, fixed_bytes, Address, FixedBytes, U256};
+use std::time::Duration;
+use testcase::auth::{
+    auth::Finding,
+    evidence::REENTRANCY_DETECTOR,
+    pause::{EscalationPolicy, PauseLimiter, PauseTarget},
+};
+use tokio::time::Instant;
+
+const PROJECT: Address = address!("dddddddddddddddddddddddddddddddddddddddd");
+const OTHER: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+const WITHDRAW: FixedBytes<4> = fixed_bytes!("2e1a7d4d");
+const DEPOSIT: FixedBytes<4> = fixed_bytes!("d0e30db0");
+
+fn policy(max_pauses: usize) -> EscalationPolicy {
+    EscalationPolicy::new(
+        [PROJECT, OTHER],
+        false,
+        max_pauses,
+        Duration::from_secs(60),
+        U256::from(1),
+    )
+}
+
+fn function(project: Address, selector: FixedBytes<4>) -> PauseTarget {
+    PauseTarget::Function { project, selector }
+}
+
+fn finding(target: Address, selector: Option<FixedBytes<4>>) -> Finding {
+    Finding {
+        address: address!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+        target,
+        selector,
+        tx_hash: None,
+        detector: REENTRANCY_DETECTOR,
+        loss: U256::from(1000),
+        call_path: vec![],
+    }
+}
+
+#[test]
+fn only_opted_in_projects_are_paused() {
+    let policy = policy(3);
+    assert!(policy.is_enabled());
+    assert_eq!(
+        policy.target(&finding(PROJECT, Some(WITHDRAW))),
+        Some(function(PROJECT, WITHDRAW))
+    );
+    // 经 fallback 重入时暂停整个项目
+    assert_eq!(
+        policy.target(&finding(PROJECT, None)),
+        Some(PauseTarget::Project(PROJECT))
+    );
+    let outsider = address!("ffffffffffffffffffffffffffffffffffffffff");
+    assert_eq!(policy.target(&finding(outsider, Some(WITHDRAW))), None);
+
+    let whole = EscalationPolicy::new([PROJECT], true, 3, Duration::from_secs(60), U256::ZERO);
+    assert_eq!(
+        whole.target(&finding(PROJECT, Some(WITHDRAW))),
+        Some(PauseTarget::Project(PROJECT))
+    );
+    assert!(!EscalationPolicy::new([], false, 3, Duration::from_secs(60), U256::ZERO).is_enabled());
+}
+
+#[test]
+fn only_confirmed_drains_are_paused() {
+    // 没有转出资金的重入不暂停，阈值为 0 时也至少需要 1 wei
+    let mut drainless = finding(PROJECT, Some(WITHDRAW));
+    drainless.loss = U256::ZERO;
+    assert_eq!(policy(3).target(&drainless), None);
+    let zero = EscalationPolicy::new([PROJECT], false, 3, Duration::from_secs(60), U256::ZERO);
+    assert_eq!(zero.target(&drainless), None);
+
+    let strict = EscalationPolicy::new(
+        [PROJECT],
+        false,
+        3,
+        Duration::from_secs(60),
+        U256::from(1000),
+    );
+    assert!(strict.target(&finding(PROJECT, Some(WITHDRAW))).is_some());
+    let mut small = finding(PROJECT, Some(WITHDRAW));
+    small.loss = U256::from(999);
+    assert_eq!(strict.target(&small), None);
+}
+
+#[test]
+fn pauses_are_limited_per_window() {
+    let policy = policy(2);
+    let mut limiter = PauseLimiter::default();
+    let now = Instant::now();
+    assert!(limiter.allow(&policy, function(PROJECT, WITHDRAW), now));
+    assert!(limiter.allow(&policy, function(PROJECT, DEPOSIT), now));
+    assert!(!limiter.allow(&policy, PauseTarget::Project(OTHER), now));
+
+    // 窗口过后恢复额度
+    let later = now + Duration::from_secs(60);
+    assert!(limiter.allow(&policy, PauseTarget::Project(OTHER), later));
+}
+
+#[test]
+fn paused_targets_are_not_paused_again() {
+    let policy = policy(10);
+    let now = Instant::now();
+    let mut limiter = PauseLimiter::new([function(PROJECT, WITHDRAW)]);
+    assert!(!limiter.allow(&policy, function(PROJECT, WITHDRAW), now));
+    assert!(limiter.allow(&policy, function(PROJECT, DEPOSIT), now));
+    assert!(!limiter.allow(&policy, function(PROJECT, DEPOSIT), now));
+
+    // 整个项目已暂停时不再暂停其中的函数
+    let mut limiter = PauseLimiter::new([PauseTarget::Project(PROJECT)]);
+    assert!(!limiter.allow(&policy, function(PROJECT, WITHDRAW), now));
+    assert!(limiter.allow(&policy, function(OTHER, WITHDRAW), now));
+}
+
+#[test]
+fn revoked_pause_can_be_retried() {
+    let policy = policy(10);
+    let now = Instant::now();
+    let mut limiter = PauseLimiter::default();
+    let target = function(PROJECT, WITHDRAW);
+    assert!(limiter.allow(&policy, target, now));
+    limiter.revoke(&target);
+    assert!(limiter.allow(&policy, target, now));
+}
+
+#[test]
+fn failed_pauses_do_not_use_up_the_window() {
+    let policy = policy(1);
+    let now = Instant::now();
+    let mut limiter = PauseLimiter::default();
+    let target = function(PROJECT, WITHDRAW);
+    for _ in 0..3 {
+        assert!(limiter.allow(&policy, target, now));
+        limiter.revoke(&target);
+    }
+    // 额度仍可用于另一个目标
+    assert!(limiter.allow(&policy, PauseTarget::Project(OTHER), now));
+    assert!(!limiter.allow(&policy, target, now));
+}
+
+#[test]
+fn manual_unpause_keeps_rate_limit() {
+    let policy = policy(1);
+    let now = Instant::now();
+    let mut limiter = PauseLimiter::default();
+    let target = function(PROJECT, WITHDRAW);
+    assert!(limiter.allow(&policy, target, now));
+    limiter.unpaused(&target);
+    // 已发起的暂停仍占用窗口内的额度
+    assert!(!limiter.allow(&policy, target, now));
+    assert!(limiter.allow(&policy, target, now + policy.window));
+}
@@ -0,0 +1,188 @@
+use alloy::{
+    dyn_abi::{DynSolType, DynSolValue, JsonAbiExt},
+    json_abi::JsonAbi,
+    primitives::{address, fixed_bytes, Address, B256},
+    sol_types::SolCall,
+};
+use serde_json::Value;
+use std::{fs, path::PathBuf};
+use testcase::auth::{
+    auth::RegistryProxy,
+    payload::{
+        AuthModuleCall, BatchSetInfoPayload, ChangeModePayload, RemoveInfoPayload, SetInfoPayload,
+    },
+};
+
+const PROJECT: Address = address!("7BAB0b362CacbBB3DBBa0Ca9B7DAf3735DAe8e40");
+const AUTH_MODULE: Address = address!("5e68f07f7D52B1a45B2BfDEefBbe2BC9547a846C");
+const ATTACKER: Address = address!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+
+fn repo_path(relative: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(relative)
+}
+
+/// 从 AuthenticationModule.sol 中取出某个函数 `abi.decode(data, (...))` 的类型列表
+fn solidity_decode_type(function: &str) -> DynSolType {
+    let source = fs::read_to_string(repo_path(
+        "../../Accuracy/example/src/Implemention/AuthenticationModule.sol",
+    ))
+    .unwrap();
+    let body = &source[source
+        .find(&format!("function {}(", function))
+        .unwrap_or_else(|| panic!("function {} not found", function))..];
+    let decode = &body[body.find("abi.decode(").unwrap()..];
+    let tuple = &decode[decode.find(',').unwrap() + 1..];
+    let tuple = &tuple[tuple.find('(').unwrap()..];
+
+    let mut depth = 0;
+    let end = tuple
+        .char_indices()
+        .find_map(|(i, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        })
+        .unwrap();
+    let signature: String = tuple[..=end].split_whitespace().collect();
+    DynSolType::parse(&signature).unwrap()
+}
+
+fn decode_payload(function: &str, call: &AuthModuleCall) -> Vec<DynSolValue> {
+    let ty = solidity_decode_type(function);
+    match ty.abi_decode_params(&call.payload()).unwrap() {
+        DynSolValue::Tuple(values) => values,
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+/// 按注册表 artifact 的 ABI 解码 calldata，返回函数名与参数
+fn decode_registry_call(calldata: &[u8]) -> (String, Vec<DynSolValue>) {
+    let artifact: Value =
+        serde_json::from_str(&fs::read_to_string(repo_path("abi/registry.json")).unwrap()).unwrap();
+    let mut abi: JsonAbi = serde_json::from_value(artifact["abi"].clone()).unwrap();
+    // abi/registry.json 中没有这两个函数：batchSetInfo 在 Registry.sol 与 RegistryV2.sol 中都有，
+    // changeMode 仅存在于 RegistryV2.sol
+    for signature in [
+        "function batchSetInfo(address module_address, bytes data)",
+        "function changeMode(address module_address, bytes data)",
+    ] {
+        let function = alloy::json_abi::Function::parse(signature).unwrap();
+        abi.functions
+            .entry(function.name.clone())
+            .or_default()
+            .push(function);
+    }
+    let function = abi
+        .functions()
+        .find(|f| f.selector().as_slice() == &calldata[..4])
+        .expect("selector not in registry ABI");
+    (
+        function.name.clone(),
+        function.abi_decode_input(&calldata[4..], true).unwrap(),
+    )
+}
+
+#[test]
+fn set_info_matches_solidity_decoder() {
+    let selector = fixed_bytes!("3ccfd60b");
+    let call = AuthModuleCall::SetInfo(SetInfoPayload::function(PROJECT, selector, ATTACKER, true));
+    assert_eq!(
+        decode_payload("setInfo", &call),
+        vec![
+            DynSolValue::Address(PROJECT),
+            DynSolValue::Bool(true),
+            DynSolValue::FixedBytes(B256::right_padding_from(selector.as_slice()), 4),
+            DynSolValue::Address(ATTACKER),
+            DynSolValue::Bool(true),
+            DynSolValue::Bool(true),
+        ]
+    );
+}
+
+#[test]
+fn batch_set_info_matches_solidity_decoder() {
+    let other = address!("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+    let call =
+        AuthModuleCall::BatchSetInfo(BatchSetInfoPayload::new(PROJECT, vec![ATTACKER, other]));
+    assert_eq!(
+        decode_payload("batchSetInfo", &call),
+        vec![
+            DynSolValue::Address(PROJECT),
+            DynSolValue::Array(vec![
+                DynSolValue::Address(ATTACKER),
+                DynSolValue::Address(other)
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn change_mode_matches_solidity_decoder() {
+    let call = AuthModuleCall::ChangeMode(ChangeModePayload::new(PROJECT, true, false));
+    assert_eq!(
+        decode_payload("changeMode", &call),
+        vec![
+            DynSolValue::Address(PROJECT),
+            DynSolValue::Bool(true),
+            DynSolValue::Bool(false),
+        ]
+    );
+}
+
+#[test]
+fn remove_info_matches_solidity_decoder() {
+    let selector = fixed_bytes!("3ccfd60b");
+    let call = AuthModuleCall::RemoveInfo(RemoveInfoPayload::new(PROJECT, selector, ATTACKER));
+    assert_eq!(
+        decode_payload("removeInfo", &call),
+        vec![
+            DynSolValue::Address(PROJECT),
+            DynSolValue::FixedBytes(B256::right_padding_from(selector.as_slice()), 4),
+            DynSolValue::Address(ATTACKER),
+        ]
+    );
+}
+
+#[test]
+fn calls_are_routed_through_registry_entry_points() {
+    let cases = [
+        (
+            AuthModuleCall::SetInfo(SetInfoPayload::global(PROJECT, ATTACKER, true)),
+            "updataModuleInfo",
+        ),
+        (
+            AuthModuleCall::BatchSetInfo(BatchSetInfoPayload::new(PROJECT, vec![ATTACKER])),
+            "batchSetInfo",
+        ),
+        (
+            AuthModuleCall::ChangeMode(ChangeModePayload::new(PROJECT, false, true)),
+            "changeMode",
+        ),
+        (
+            AuthModuleCall::RemoveInfo(RemoveInfoPayload::new(
+                PROJECT,
+                Default::default(),
+                ATTACKER,
+            )),
+            "removeModuleInfo",
+        ),
+    ];
+    for (call, entry) in cases {
+        let (name, args) = decode_registry_call(&call.registry_calldata(AUTH_MODULE));
+        assert_eq!(name, entry);
+        assert_eq!(
+            args,
+            vec![
+                DynSolValue::Address(AUTH_MODULE),
+                DynSolValue::Bytes(call.payload().to_vec()),
+            ]
+        );
+
+        let call_on =
+            RegistryProxy::CallOnCall::abi_decode(&call.proxy_calldata(AUTH_MODULE), true).unwrap();
+        assert_eq!(call_on._data, call.registry_calldata(AUTH_MODULE));
+    }
+}
@@ -0,0 +1,120 @@
+use alloy::{
+    primitives::{address, fixed_bytes, Address, FixedBytes},
+    rpc::types::Log,
+    sol_types::SolEvent,
+};
+use std::collections::BTreeSet;
+use testcase::auth::{
+    auth::Registry,
+    projects::{ProjectDirectory, ProjectMode, ProjectPolicy},
+};
+
+const REGISTRY: Address = address!("cccccccccccccccccccccccccccccccccccccccc");
+const AUTH_MODULE: Address = address!("a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0");
+const OTHER_MODULE: Address = address!("b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0");
+const PROJECT: Address = address!("dddddddddddddddddddddddddddddddddddddddd");
+const OTHER: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+const STATIC: Address = address!("ffffffffffffffffffffffffffffffffffffffff");
+const WITHDRAW: FixedBytes<4> = fixed_bytes!("2e1a7d4d");
+const DEPOSIT: FixedBytes<4> = fixed_bytes!("d0e30db0");
+
+fn log<E: SolEvent>(event: &E) -> Log {
+    Log {
+        inner: alloy::primitives::Log {
+            address: REGISTRY,
+            data: event.encode_log_data(),
+        },
+        ..Default::default()
+    }
+}
+
+fn register(project: Address, selector: FixedBytes<4>, modules: Vec<Address>) -> Log {
+    log(&Registry::RegisterInfo {
+        project,
+        funcSig: selector,
+        manager: Address::ZERO,
+        params: vec![],
+        enableModules: modules,
+    })
+}
+
+fn remove(project: Address, selector: FixedBytes<4>, module: Address) -> Log {
+    log(&Registry::RemoveModuleForProject {
+        project,
+        funcSig: selector,
+        module_description: "auth".to_string(),
+        module_address: module,
+    })
+}
+
+#[test]
+fn policy_overrides_default_and_skips() {
+    let policy = ProjectPolicy::parse(
+        Some("function"),
+        Some(&format!(" {PROJECT}=all, {OTHER}=Skip ,")),
+        Some(&format!("{STATIC},")),
+    )
+    .unwrap();
+    assert_eq!(policy.mode(&PROJECT), ProjectMode::All);
+    assert_eq!(policy.mode(&OTHER), ProjectMode::Skip);
+    assert_eq!(policy.mode(&STATIC), ProjectMode::FunctionOnly);
+
+    // 固定项目与发现的项目合并，跳过的项目不推送
+    let discovered: BTreeSet<_> = [PROJECT, OTHER].into_iter().collect();
+    assert_eq!(
+        policy.targets(&discovered),
+        [
+            (PROJECT, ProjectMode::All),
+            (STATIC, ProjectMode::FunctionOnly)
+        ]
+    );
+
+    let default = ProjectPolicy::parse(None, None, None).unwrap();
+    assert_eq!(default.mode(&PROJECT), ProjectMode::All);
+    assert!(default.targets(&BTreeSet::new()).is_empty());
+}
+
+#[test]
+fn invalid_policy_is_rejected() {
+    assert!(ProjectPolicy::parse(Some("global"), None, None).is_err());
+    assert!(ProjectPolicy::parse(None, Some(&PROJECT.to_string()), None).is_err());
+    assert!(ProjectPolicy::parse(None, Some(&format!("{PROJECT}=never")), None).is_err());
+    assert!(ProjectPolicy::parse(None, Some("0x12=all"), None).is_err());
+    assert!(ProjectPolicy::parse(None, None, Some("not-an-address")).is_err());
+}
+
+#[test]
+fn directory_applies_logs_in_order() {
+    let mut directory = ProjectDirectory::new(AUTH_MODULE, 0);
+    directory.apply(&register(
+        PROJECT,
+        WITHDRAW,
+        vec![OTHER_MODULE, AUTH_MODULE],
+    ));
+    directory.apply(&register(OTHER, WITHDRAW, vec![OTHER_MODULE]));
+    assert_eq!(directory.projects(), [PROJECT].into_iter().collect());
+
+    // 同一函数重新登记时覆盖模块列表
+    directory.apply(&register(PROJECT, WITHDRAW, vec![OTHER_MODULE]));
+    assert!(directory.projects().is_empty());
+    directory.apply(&register(PROJECT, WITHDRAW, vec![AUTH_MODULE]));
+    directory.apply(&register(PROJECT, DEPOSIT, vec![AUTH_MODULE]));
+
+    // 移除其他模块不影响；移除一个函数上的模块后，另一个函数仍启用
+    directory.apply(&remove(PROJECT, WITHDRAW, OTHER_MODULE));
+    directory.apply(&remove(PROJECT, WITHDRAW, AUTH_MODULE));
+    assert_eq!(directory.projects(), [PROJECT].into_iter().collect());
+    directory.apply(&remove(PROJECT, DEPOSIT, AUTH_MODULE));
+    assert!(directory.projects().is_empty());
+
+    // 移除之后再次登记重新启用
+    directory.apply(&register(PROJECT, DEPOSIT, vec![AUTH_MODULE]));
+    assert_eq!(directory.projects(), [PROJECT].into_iter().collect());
+}
+
+#[test]
+fn unrelated_logs_are_ignored() {
+    let mut directory = ProjectDirectory::new(AUTH_MODULE, 0);
+    directory.apply(&log(&Registry::pauseProjectInteract { project: PROJECT }));
+    assert!(directory.projects().is_empty());
+}
@@ -0,0 +1,145 @@
+use alloy::primitives::{address, fixed_bytes, Address, FixedBytes, U256};
+use std::collections::HashSet;
+use testcase::auth::{
+    payload::{AuthModuleCall, BatchSetInfoPayload, RemoveInfoPayload, SetInfoPayload},
+    reconcile::{
+        black_list_mode_slot, decode_black_list_mode, function_blacklist_slot,
+        global_blacklist_slot, scope_candidates, ReconcilePlan,
+    },
+    scope::FunctionScope,
+};
+
+const PROJECT: Address = address!("dddddddddddddddddddddddddddddddddddddddd");
+const OTHER_PROJECT: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+const ATTACKER: Address = address!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+const OTHER: Address = address!("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+const THIRD: Address = address!("cccccccccccccccccccccccccccccccccccccccc");
+const TRANSFER: FixedBytes<4> = fixed_bytes!("a9059cbb");
+const WITHDRAW: FixedBytes<4> = fixed_bytes!("2e1a7d4d");
+
+fn scope(address: Address, selector: FixedBytes<4>) -> FunctionScope {
+    FunctionScope {
+        address,
+        project: PROJECT,
+        selector,
+    }
+}
+
+fn set<T: Copy + Eq + std::hash::Hash>(items: &[T]) -> HashSet<T> {
+    items.iter().copied().collect()
+}
+
+// 期望值为 keccak256(abi.encode(...)) 按 AuthModule 存储布局独立计算的结果
+#[test]
+fn storage_slots_follow_auth_module_layout() {
+    // globalAccessBlacklist 位于槽位 1
+    assert_eq!(
+        global_blacklist_slot(PROJECT, ATTACKER),
+        U256::from_be_bytes(
+            fixed_bytes!("580bd1d6aa4a20c8f2320948490cd9a7486eb24cb3f0e35a79bdc1071acc6655").0
+        )
+    );
+    // functionAccessBlacklist 位于槽位 0，bytes4 键右侧补零
+    assert_eq!(
+        function_blacklist_slot(PROJECT, TRANSFER, ATTACKER),
+        U256::from_be_bytes(
+            fixed_bytes!("d9edd219ad4b552846adf356dfcaaea70b15769e80baca20ff59cbd0d0e4ec86").0
+        )
+    );
+    // blackListMode 位于槽位 2
+    assert_eq!(
+        black_list_mode_slot(PROJECT),
+        U256::from_be_bytes(
+            fixed_bytes!("08c7eb44227491a2a3a93efcaeb33a36f43d3522bd02a5b5b1bc79d89fc2f159").0
+        )
+    );
+}
+
+#[test]
+fn black_list_mode_is_packed_in_low_bytes() {
+    assert_eq!(decode_black_list_mode(U256::ZERO), (false, false));
+    assert_eq!(decode_black_list_mode(U256::from(0x01)), (true, false));
+    assert_eq!(decode_black_list_mode(U256::from(0x0100)), (false, true));
+    assert_eq!(decode_black_list_mode(U256::from(0x0101)), (true, true));
+    // 更高位的字节不属于这两个字段
+    assert_eq!(decode_black_list_mode(U256::from(0x010000)), (false, false));
+}
+
+#[test]
+fn diff_is_sorted_and_symmetric() {
+    let db = set(&[THIRD, ATTACKER, OTHER]);
+    let onchain = set(&[OTHER, address!("1111111111111111111111111111111111111111")]);
+    let plan = ReconcilePlan::diff(PROJECT, &db, &onchain);
+    assert_eq!(plan.to_add, [ATTACKER, THIRD]);
+    assert_eq!(
+        plan.to_remove,
+        [address!("1111111111111111111111111111111111111111")]
+    );
+    assert!(plan.scopes_to_add.is_empty());
+    assert!(!plan.is_empty());
+
+    assert!(ReconcilePlan::diff(PROJECT, &db, &db).is_empty());
+}
+
+#[test]
+fn scope_diff_is_sorted_by_selector_and_address() {
+    let db = set(&[
+        scope(OTHER, WITHDRAW),
+        scope(ATTACKER, WITHDRAW),
+        scope(ATTACKER, TRANSFER),
+    ]);
+    let onchain = set(&[scope(ATTACKER, TRANSFER), scope(THIRD, TRANSFER)]);
+    let plan =
+        ReconcilePlan::diff(PROJECT, &HashSet::new(), &HashSet::new()).with_scopes(&db, &onchain);
+    assert_eq!(
+        plan.scopes_to_add,
+        [scope(ATTACKER, WITHDRAW), scope(OTHER, WITHDRAW)]
+    );
+    assert_eq!(plan.scopes_to_remove, [scope(THIRD, TRANSFER)]);
+    assert!(plan.to_add.is_empty() && plan.to_remove.is_empty());
+    assert!(!plan.is_empty());
+}
+
+#[test]
+fn calls_add_in_one_batch_and_remove_one_by_one() {
+    let plan = ReconcilePlan::diff(PROJECT, &set(&[OTHER, ATTACKER]), &set(&[THIRD])).with_scopes(
+        &set(&[scope(OTHER, TRANSFER)]),
+        &set(&[scope(THIRD, WITHDRAW)]),
+    );
+    assert_eq!(
+        plan.calls(),
+        [
+            AuthModuleCall::BatchSetInfo(BatchSetInfoPayload::new(PROJECT, vec![ATTACKER, OTHER])),
+            AuthModuleCall::SetInfo(SetInfoPayload::function(PROJECT, TRANSFER, OTHER, true)),
+            AuthModuleCall::RemoveInfo(RemoveInfoPayload::new(PROJECT, FixedBytes::ZERO, THIRD)),
+            AuthModuleCall::RemoveInfo(RemoveInfoPayload::new(PROJECT, WITHDRAW, THIRD)),
+        ]
+    );
+    assert!(ReconcilePlan::default().calls().is_empty());
+}
+
+#[test]
+fn scope_candidates_combine_events_with_known_selectors() {
+    let known = [
+        scope(ATTACKER, TRANSFER),
+        scope(OTHER, WITHDRAW),
+        FunctionScope {
+            address: THIRD,
+            project: OTHER_PROJECT,
+            selector: TRANSFER,
+        },
+    ];
+    let candidates = scope_candidates(PROJECT, &set(&[THIRD]), &known);
+    assert_eq!(
+        candidates,
+        set(&[
+            scope(ATTACKER, TRANSFER),
+            scope(OTHER, WITHDRAW),
+            scope(THIRD, TRANSFER),
+            scope(THIRD, WITHDRAW),
+        ])
+    );
+
+    // 项目没有任何函数级记录时无法从事件推断函数
+    assert!(scope_candidates(OTHER_PROJECT, &set(&[ATTACKER]), &[]).is_empty());
+}
@@ -0,0 +1,45 @@
+use alloy::primitives::Address;
+use std::time::Duration;
+use testcase::auth::retry::RetryBackoff;
+use tokio::time::Instant;
+
+fn backoff() -> RetryBackoff<Address> {
+    RetryBackoff::new(Duration::from_secs(5), Duration::from_secs(60))
+}
+
+#[test]
+fn failures_back_off_exponentially_up_to_max() {
+    let mut backoff = backoff();
+    let target = Address::repeat_byte(1);
+    let now = Instant::now();
+    assert!(backoff.ready(&target, now));
+
+    let delays: Vec<Duration> = (0..6).map(|_| backoff.failed(target, now)).collect();
+    let secs: Vec<u64> = delays.iter().map(Duration::as_secs).collect();
+    assert_eq!(secs, [5, 10, 20, 40, 60, 60]);
+
+    assert!(!backoff.ready(&target, now + Duration::from_secs(59)));
+    assert!(backoff.ready(&target, now + Duration::from_secs(60)));
+    // 其他目标不受影响
+    assert!(backoff.ready(&Address::repeat_byte(2), now));
+
+    backoff.succeeded(&target);
+    assert!(backoff.ready(&target, now));
+    assert_eq!(backoff.failed(target, now), Duration::from_secs(5));
+}
+
+#[test]
+fn same_error_alerts_once_until_recovered() {
+    let mut backoff = backoff();
+    let target = Address::repeat_byte(1);
+    assert!(backoff.should_alert(target, "permission denied"));
+    assert!(!backoff.should_alert(target, "permission denied"));
+    // 同一目标的新错误与其他目标的相同错误仍然告警
+    assert!(backoff.should_alert(target, "gas too high"));
+    assert!(backoff.should_alert(Address::repeat_byte(2), "gas too high"));
+
+    backoff.failed(target, Instant::now());
+    assert!(!backoff.should_alert(target, "gas too high"));
+    backoff.succeeded(&target);
+    assert!(backoff.should_alert(target, "gas too high"));
+}
@@ -0,0 +1,127 @@
+use alloy::{
+    consensus::{SignableTransaction, TxEip1559},
+    hex,
+    network::TxSigner,
+    primitives::{address, b256, Address, B256},
+    signers::{local::PrivateKeySigner, SignerSync},
+};
+use std::{env, fs, path::PathBuf};
+use testcase::auth::signer::{RemoteSigner, SignerEndpoint};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixListener,
+};
+
+const KEY: B256 = b256!("0101010101010101010101010101010101010101010101010101010101010101");
+const OTHER_KEY: B256 = b256!("0202020202020202020202020202020202020202020202020202020202020202");
+
+/// 在 Unix socket 上用 `key` 响应一次签名请求，返回收到的请求
+fn mock_signer(name: &str, key: B256) -> (PathBuf, tokio::task::JoinHandle<serde_json::Value>) {
+    let path = env::temp_dir().join(format!(
+        "blacklist-signer-{}-{}.sock",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    let signer = PrivateKeySigner::from_bytes(&key).unwrap();
+    let handle = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+        let hash: B256 = request["hash"].as_str().unwrap().parse().unwrap();
+        let signature = signer.sign_hash_sync(&hash).unwrap();
+        let response =
+            serde_json::json!({ "signature": hex::encode_prefixed(signature.as_bytes()) });
+        let mut stream = reader.into_inner();
+        stream
+            .write_all(format!("{}\n", response).as_bytes())
+            .await
+            .unwrap();
+        request
+    });
+    (path, handle)
+}
+
+fn tx() -> TxEip1559 {
+    TxEip1559 {
+        chain_id: 11155111,
+        nonce: 7,
+        gas_limit: 100_000,
+        to: address!("cccccccccccccccccccccccccccccccccccccccc").into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn endpoint_is_parsed_by_scheme() {
+    match SignerEndpoint::parse("unix:///run/signer.sock").unwrap() {
+        SignerEndpoint::Unix(path) => assert_eq!(path, PathBuf::from("/run/signer.sock")),
+        other => panic!("期望 Unix socket，得到 {:?}", other),
+    }
+    match SignerEndpoint::parse("http://127.0.0.1:9000/sign").unwrap() {
+        SignerEndpoint::Http(url) => assert_eq!(url.path(), "/sign"),
+        other => panic!("期望 HTTP，得到 {:?}", other),
+    }
+    assert!(SignerEndpoint::parse("not a url").is_err());
+}
+
+#[tokio::test]
+async fn signature_from_configured_address_is_accepted() {
+    let expected = PrivateKeySigner::from_bytes(&KEY).unwrap().address();
+    let (path, handle) = mock_signer("accepted", KEY);
+    let signer = RemoteSigner::new(SignerEndpoint::Unix(path), expected);
+
+    let mut tx = tx();
+    let signature = signer.sign_transaction(&mut tx).await.unwrap();
+    assert_eq!(
+        signature
+            .recover_address_from_prehash(&tx.signature_hash())
+            .unwrap(),
+        expected
+    );
+
+    let request = handle.await.unwrap();
+    let address: Address = request["address"].as_str().unwrap().parse().unwrap();
+    assert_eq!(address, expected);
+    assert_eq!(request["chainId"], 11155111);
+    assert_eq!(request["hash"], tx.signature_hash().to_string());
+    assert_eq!(
+        request["payload"],
+        hex::encode_prefixed(tx.encoded_for_signing())
+    );
+}
+
+#[tokio::test]
+async fn signature_from_other_address_is_rejected() {
+    let expected = PrivateKeySigner::from_bytes(&KEY).unwrap().address();
+    let (path, handle) = mock_signer("rejected", OTHER_KEY);
+    let signer = RemoteSigner::new(SignerEndpoint::Unix(path), expected);
+
+    let err = signer.sign_transaction(&mut tx()).await.unwrap_err();
+    assert!(err.to_string().contains(&expected.to_string()));
+    handle.await.unwrap();
+}
+
+#[tokio::test]
+async fn malformed_signature_is_rejected() {
+    let path = env::temp_dir().join(format!(
+        "blacklist-signer-malformed-{}.sock",
+        std::process::id()
+    ));
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        stream
+            .write_all(b"{\"signature\":\"0x1234\"}\n")
+            .await
+            .unwrap();
+    });
+
+    let expected = PrivateKeySigner::from_bytes(&KEY).unwrap().address();
+    let signer = RemoteSigner::new(SignerEndpoint::Unix(path), expected);
+    assert!(signer.sign_transaction(&mut tx()).await.is_err());
+}
@@ -0,0 +1,1035 @@
+use alloy::primitives::{address, b256, fixed_bytes, Address, B256, U256};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sqlx::Postgres;
+use std::{
+    env, fs,
+    sync::{Arc, Mutex},
+};
+use testcase::auth::{
+    allowlist::NewAllowlistEntry,
+    auth::record_findings,
+    bulk::{self, Format, ImportOptions},
+    data::{BlacklistEntry, EntryStatus, NewBlacklistEntry, UpsertOutcome, BLACKLIST_TABLE},
+    evidence::{Evidence, Severity, REENTRANCY_DETECTOR},
+    expiry::ExpiryPolicy,
+    history::{ChangeContext, ChangeSource},
+    ofac::{self, SdnFormat, SdnList, SdnSync, OFAC_SDN_SOURCE},
+    pause::PauseTarget,
+    query::{BlacklistQuery, SortField, SortOrder},
+    scope::FunctionScope,
+    store::{connect, Backend, BlacklistStore},
+    sync::SyncStatus,
+};
+
+const ATTACKER: Address = address!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+const OTHER: Address = address!("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+
+/// 每个测试使用独立的 SQLite 文件并执行迁移
+async fn sqlite_store(name: &str) -> Arc<dyn BlacklistStore> {
+    let path = env::temp_dir().join(format!("blacklist-{}-{}.db", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    let url = format!("sqlite://{}?mode=rwc", path.display());
+    let store = connect(&url, BLACKLIST_TABLE).await.unwrap();
+    store.migrate().await.unwrap();
+    store.verify_schema().await.unwrap();
+    store
+}
+
+fn cli() -> ChangeContext {
+    ChangeContext::new(ChangeSource::Cli).with_actor("tester")
+}
+
+fn entry(address: Address, reason: &str) -> NewBlacklistEntry {
+    NewBlacklistEntry {
+        address,
+        reason: reason.to_string(),
+        status: EntryStatus::Active,
+        expires_at: None,
+        chain_type: "ETH".to_string(),
+        created_by: None,
+    }
+}
+
+#[tokio::test]
+async fn unmigrated_schema_is_reported() {
+    let path = env::temp_dir().join(format!("blacklist-unmigrated-{}.db", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let url = format!("sqlite://{}?mode=rwc", path.display());
+    let store = connect(&url, BLACKLIST_TABLE).await.unwrap();
+    let err = store.verify_schema().await.unwrap_err();
+    assert!(err.to_string().contains("尚未初始化"));
+
+    store.migrate().await.unwrap();
+    store.verify_schema().await.unwrap();
+}
+
+#[tokio::test]
+async fn create_find_and_delete() {
+    let store = sqlite_store("crud").await;
+    store
+        .create(&entry(ATTACKER, "Scam"), &cli())
+        .await
+        .unwrap();
+
+    assert!(store.exists(&ATTACKER).await.unwrap());
+    assert!(!store.exists(&OTHER).await.unwrap());
+
+    let found = store.find(&ATTACKER).await.unwrap().unwrap();
+    assert_eq!(found.address, format!("{:#x}", ATTACKER));
+    assert_eq!(store.get(found.id).await.unwrap().unwrap().reason, "Scam");
+    assert_eq!(store.list().await.unwrap().len(), 1);
+    assert_eq!(store.search("Sca").await.unwrap().len(), 1);
+
+    store.delete(found.id, &cli()).await.unwrap();
+    assert!(store.find(&ATTACKER).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn upsert_merges_reasons_and_extends_expiry() {
+    let store = sqlite_store("upsert").await;
+    let mut first = entry(ATTACKER, "Scam");
+    first.expires_at = Some(Utc::now() + Duration::days(1));
+    let mut second = entry(ATTACKER, "Money laundering");
+    second.expires_at = Some(Utc::now() + Duration::days(7));
+
+    assert_eq!(
+        store.upsert(&first, &cli()).await.unwrap(),
+        UpsertOutcome::Inserted
+    );
+    assert_eq!(
+        store.upsert(&second, &cli()).await.unwrap(),
+        UpsertOutcome::Merged
+    );
+    // 同一原因不重复追加
+    assert_eq!(
+        store.upsert(&second, &cli()).await.unwrap(),
+        UpsertOutcome::Merged
+    );
+
+    let merged = store.find(&ATTACKER).await.unwrap().unwrap();
+    assert_eq!(merged.reason, "Scam; Money laundering");
+    assert!(merged.expires_at.unwrap() > Utc::now() + Duration::days(6));
+    assert_eq!(store.list().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn expired_entries_are_listed_until_updated() {
+    let store = sqlite_store("expiry").await;
+    let mut expired = entry(ATTACKER, "Scam");
+    expired.expires_at = Some(Utc::now() - Duration::hours(1));
+    store.create(&expired, &cli()).await.unwrap();
+    store.create(&entry(OTHER, "Scam"), &cli()).await.unwrap();
+
+    let listed = store.list_expired(Utc::now()).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(store.list_active_addresses().await.unwrap().len(), 2);
+
+    store
+        .update(
+            listed[0].id,
+            EntryStatus::Expired,
+            listed[0].expires_at,
+            &cli(),
+        )
+        .await
+        .unwrap();
+    assert!(store.list_expired(Utc::now()).await.unwrap().is_empty());
+    assert_eq!(
+        store.list_active_addresses().await.unwrap(),
+        vec![format!("{:#x}", OTHER)]
+    );
+}
+
+#[tokio::test]
+async fn evidence_is_linked_to_entry() {
+    let store = sqlite_store("evidence").await;
+    store
+        .create(&entry(ATTACKER, "Scam"), &cli())
+        .await
+        .unwrap();
+    let evidence = Evidence {
+        attacker: ATTACKER,
+        tx_hash: Some(b256!(
+            "1111111111111111111111111111111111111111111111111111111111111111"
+        )),
+        block_number: Some(100),
+        block_hash: b256!("2222222222222222222222222222222222222222222222222222222222222222"),
+        chain_type: "ETH".to_string(),
+        detector: REENTRANCY_DETECTOR.to_string(),
+        severity: Severity::High,
+        project: OTHER,
+        selector: Some(fixed_bytes!("2e1a7d4d")),
+        loss: U256::from(10u64).pow(U256::from(18u64)),
+        call_path: vec![format!("{}|0x2e1a7d4d", OTHER)],
+    };
+
+    assert!(store.add_evidence(&evidence).await.unwrap());
+    // 同一交易重复写入被忽略
+    assert!(!store.add_evidence(&evidence).await.unwrap());
+    // 没有对应记录的地址不写入证据
+    let mut orphan = evidence.clone();
+    orphan.attacker = OTHER;
+    assert!(!store.add_evidence(&orphan).await.unwrap());
+
+    let found = store.find(&ATTACKER).await.unwrap().unwrap();
+    let records = store.list_evidence(found.id).await.unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].severity, "high");
+    assert_eq!(records[0].block_number, Some(100));
+    assert_eq!(records[0].func_sig.as_deref(), Some("0x2e1a7d4d"));
+    assert_eq!(records[0].loss_wei, "1000000000000000000");
+
+    store.delete(found.id, &cli()).await.unwrap();
+    assert!(store.list_evidence(found.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn history_records_every_change() {
+    let store = sqlite_store("history").await;
+    let detector = ChangeContext::new(ChangeSource::Detector).with_actor(REENTRANCY_DETECTOR);
+    store
+        .upsert(&entry(ATTACKER, "Scam"), &detector)
+        .await
+        .unwrap();
+    store
+        .upsert(&entry(ATTACKER, "Money laundering"), &detector)
+        .await
+        .unwrap();
+    // 没有变化的合并不记录
+    store
+        .upsert(&entry(ATTACKER, "Scam"), &detector)
+        .await
+        .unwrap();
+    let found = store.find(&ATTACKER).await.unwrap().unwrap();
+    store
+        .update(
+            found.id,
+            EntryStatus::Expired,
+            None,
+            &ChangeContext::new(ChangeSource::Expiry),
+        )
+        .await
+        .unwrap();
+    store.delete(found.id, &cli()).await.unwrap();
+
+    let history = store.history(&ATTACKER).await.unwrap();
+    let actions: Vec<_> = history.iter().map(|r| r.action.as_str()).collect();
+    assert_eq!(actions, ["create", "merge", "status_change", "delete"]);
+    assert!(history.iter().all(|r| r.entry_id == found.id));
+    assert_eq!(history[0].source, "detector");
+    assert_eq!(history[0].actor.as_deref(), Some(REENTRANCY_DETECTOR));
+    assert!(history[0].old_value.is_none());
+    assert!(history[2]
+        .old_value
+        .as_deref()
+        .unwrap()
+        .contains("\"active\""));
+    assert!(history[2]
+        .new_value
+        .as_deref()
+        .unwrap()
+        .contains("\"expired\""));
+    assert_eq!(history[3].source, "cli");
+    assert!(history[3].new_value.is_none());
+}
+
+#[tokio::test]
+async fn status_lifecycle_is_enforced() {
+    let store = sqlite_store("lifecycle").await;
+    store
+        .create(&entry(ATTACKER, "Scam"), &cli())
+        .await
+        .unwrap();
+    let id = store.find(&ATTACKER).await.unwrap().unwrap().id;
+
+    store
+        .update(id, EntryStatus::Suspended, None, &cli())
+        .await
+        .unwrap();
+    let suspended = store.find(&ATTACKER).await.unwrap().unwrap();
+    assert_eq!(suspended.status, "suspended");
+    assert!(suspended.status_changed_at.is_some());
+    // 暂停中的记录再次被检测到时保持暂停
+    assert_eq!(
+        store
+            .upsert(&entry(ATTACKER, "Scam"), &cli())
+            .await
+            .unwrap(),
+        UpsertOutcome::Held
+    );
+
+    store
+        .update(id, EntryStatus::Expired, None, &cli())
+        .await
+        .unwrap();
+    // 不允许的转换不修改记录
+    assert!(store
+        .update(id, EntryStatus::Suspended, None, &cli())
+        .await
+        .is_err());
+    assert_eq!(store.get(id).await.unwrap().unwrap().status, "expired");
+    // 到期的记录再次被检测到时重新激活
+    assert_eq!(
+        store
+            .upsert(&entry(ATTACKER, "Scam"), &cli())
+            .await
+            .unwrap(),
+        UpsertOutcome::Reactivated
+    );
+
+    store
+        .update(id, EntryStatus::Revoked, None, &cli())
+        .await
+        .unwrap();
+    // 已撤销的记录再次被检测到时重新进入审核
+    assert_eq!(
+        store
+            .upsert(&entry(ATTACKER, "Scam"), &cli())
+            .await
+            .unwrap(),
+        UpsertOutcome::Held
+    );
+    assert_eq!(
+        store.get(id).await.unwrap().unwrap().status,
+        "pending_review"
+    );
+}
+
+#[tokio::test]
+async fn allowlist_blocks_blacklisting() {
+    let store = sqlite_store("allowlist").await;
+    store
+        .allow(&NewAllowlistEntry {
+            address: ATTACKER,
+            chain_type: "ETH".to_string(),
+            reason: "DEX router".to_string(),
+            owner: "tester".to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        store
+            .upsert(&entry(ATTACKER, "Scam"), &cli())
+            .await
+            .unwrap(),
+        UpsertOutcome::Allowlisted
+    );
+    assert!(store
+        .create(&entry(ATTACKER, "Scam"), &cli())
+        .await
+        .is_err());
+    assert!(!store.exists(&ATTACKER).await.unwrap());
+    assert!(store.history(&ATTACKER).await.unwrap().is_empty());
+
+    store.record_suppressed(&ATTACKER).await.unwrap();
+    store.record_suppressed(&ATTACKER).await.unwrap();
+    let allowlist = store.list_allowlist().await.unwrap();
+    assert_eq!(allowlist.len(), 1);
+    assert_eq!(allowlist[0].address, format!("{:#x}", ATTACKER));
+    assert_eq!(allowlist[0].suppressed_count, 2);
+    assert!(allowlist[0].last_suppressed_at.is_some());
+
+    assert!(store.disallow(&ATTACKER).await.unwrap());
+    assert!(!store.disallow(&ATTACKER).await.unwrap());
+    assert_eq!(
+        store
+            .upsert(&entry(ATTACKER, "Scam"), &cli())
+            .await
+            .unwrap(),
+        UpsertOutcome::Inserted
+    );
+}
+
+#[tokio::test]
+async fn query_filters_sorts_and_pages() {
+    let store = sqlite_store("query").await;
+    let addresses: Vec<Address> = (1u8..=5).map(|i| Address::repeat_byte(i * 0x11)).collect();
+    for (i, address) in addresses.iter().enumerate() {
+        let mut new = entry(
+            *address,
+            if i % 2 == 0 {
+                "Scam"
+            } else {
+                "Phishing attempt"
+            },
+        );
+        new.created_by = Some("importer".to_string());
+        new.expires_at = Some(Utc::now() + Duration::days(i as i64 + 1));
+        store.create(&new, &cli()).await.unwrap();
+    }
+    let id = store.find(&addresses[4]).await.unwrap().unwrap().id;
+    store
+        .update(id, EntryStatus::Suspended, None, &cli())
+        .await
+        .unwrap();
+
+    let active = BlacklistQuery::new()
+        .chain_type("ETH")
+        .status(EntryStatus::Active)
+        .reason("Scam");
+    assert_eq!(store.count(&active).await.unwrap(), 2);
+    assert_eq!(
+        store
+            .count(&BlacklistQuery::new().address_prefix("0X3333"))
+            .await
+            .unwrap(),
+        1
+    );
+    let soon = BlacklistQuery::new()
+        .created_by("importer")
+        .expires_between(None, Some(Utc::now() + Duration::hours(60)));
+    assert_eq!(store.count(&soon).await.unwrap(), 2);
+
+    // 逐页倒序读取全部记录，不重复也不遗漏
+    let mut query = BlacklistQuery::new()
+        .sort_by(SortField::Address, SortOrder::Desc)
+        .limit(2);
+    let mut seen = vec![];
+    loop {
+        let page = store.query(&query).await.unwrap();
+        assert!(page.entries.len() <= 2);
+        seen.extend(page.entries.iter().map(|entry| entry.address.clone()));
+        match page.next {
+            Some(cursor) => query = query.after(cursor.to_string().parse().unwrap()),
+            None => break,
+        }
+    }
+    let expected: Vec<String> = addresses
+        .iter()
+        .rev()
+        .map(|address| format!("{:#x}", address))
+        .collect();
+    assert_eq!(seen, expected);
+
+    let first = store
+        .query(
+            &BlacklistQuery::new()
+                .sort_by(SortField::AddedAt, SortOrder::Asc)
+                .limit(3),
+        )
+        .await
+        .unwrap();
+    let rest = store
+        .query(
+            &BlacklistQuery::new()
+                .sort_by(SortField::AddedAt, SortOrder::Asc)
+                .after(first.next.unwrap()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(rest.entries.len(), 2);
+    assert!(rest.next.is_none());
+}
+
+#[tokio::test]
+async fn like_wildcards_in_input_match_literally() {
+    let store = sqlite_store("like").await;
+    store
+        .create(&entry(ATTACKER, "100% drained"), &cli())
+        .await
+        .unwrap();
+    store
+        .create(&entry(OTHER, "1000 drained"), &cli())
+        .await
+        .unwrap();
+
+    let percent = BlacklistQuery::new().reason("0%");
+    assert_eq!(store.count(&percent).await.unwrap(), 1);
+    let underscore = BlacklistQuery::new().reason("10_0");
+    assert_eq!(store.count(&underscore).await.unwrap(), 0);
+    let prefix = BlacklistQuery::new().address_prefix("0x_");
+    assert_eq!(store.count(&prefix).await.unwrap(), 0);
+
+    let found = store.search("%").await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].address, format!("{:#x}", ATTACKER));
+    assert_eq!(store.search("!").await.unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn bulk_import_validates_and_exports_round_trip() {
+    let store = sqlite_store("bulk").await;
+    let csv = format!(
+        "address,reason,status,expires_at\n\
+         {ATTACKER},Scam,,\n\
+         not-an-address,Scam,,\n\
+         {OTHER},,suspended,2099-01-01T00:00:00Z\n\
+         {ATTACKER},Phishing attempt,,\n\
+         {OTHER},Scam,unknown,\n"
+    );
+    let options = ImportOptions {
+        dry_run: true,
+        created_by: Some("partner".to_string()),
+        ..Default::default()
+    };
+    let context = ChangeContext::new(ChangeSource::Import);
+
+    let report = bulk::import(
+        store.as_ref(),
+        csv.as_bytes(),
+        Format::Csv,
+        &options,
+        &ExpiryPolicy::from_env().unwrap(),
+        &context,
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.total, 5);
+    assert_eq!(report.inserted, 2);
+    assert_eq!(report.duplicates, 1);
+    let lines: Vec<_> = report.errors.iter().map(|error| error.line).collect();
+    assert_eq!(lines, [3, 6]);
+    // 试运行不写入
+    assert!(store.list().await.unwrap().is_empty());
+
+    let options = ImportOptions {
+        dry_run: false,
+        ..options
+    };
+    let report = bulk::import(
+        store.as_ref(),
+        csv.as_bytes(),
+        Format::Csv,
+        &options,
+        &ExpiryPolicy::from_env().unwrap(),
+        &context,
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.inserted, 2);
+    let other = store.find(&OTHER).await.unwrap().unwrap();
+    assert_eq!(other.reason, "Imported");
+    assert_eq!(other.status, "suspended");
+    assert_eq!(other.created_by.as_deref(), Some("partner"));
+    assert_eq!(store.history(&OTHER).await.unwrap()[0].source, "import");
+
+    // 试运行按 (链类型, 地址) 判断是否已存在
+    let other_chain = format!("address,chain_type\n{ATTACKER},BSC\n{ATTACKER},ETH\n{OTHER},ETH\n");
+    let options = ImportOptions {
+        dry_run: true,
+        ..ImportOptions::default()
+    };
+    let report = bulk::import(
+        store.as_ref(),
+        other_chain.as_bytes(),
+        Format::Csv,
+        &options,
+        &ExpiryPolicy::from_env().unwrap(),
+        &context,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        (report.inserted, report.merged, report.held),
+        (1, 1, 1),
+        "{:?}",
+        report
+    );
+
+    for format in [Format::Csv, Format::Json, Format::Jsonl] {
+        let mut exported = vec![];
+        let query = BlacklistQuery::new().status(EntryStatus::Active);
+        let count = bulk::export(store.as_ref(), &mut exported, format, &query)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        // 导出的文件可以原样导入另一个库
+        let copy = sqlite_store(&format!("bulk-copy-{:?}", format)).await;
+        let report = bulk::import(
+            copy.as_ref(),
+            exported.as_slice(),
+            format,
+            &ImportOptions::default(),
+            &ExpiryPolicy::from_env().unwrap(),
+            &context,
+        )
+        .await
+        .unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        let copied = copy.find(&ATTACKER).await.unwrap().unwrap();
+        assert_eq!(copied.reason, "Scam");
+        assert_eq!(copied.created_by.as_deref(), Some("partner"));
+    }
+}
+
+#[tokio::test]
+async fn bulk_import_follows_status_transitions() {
+    let store = sqlite_store("bulk-status").await;
+    store
+        .upsert(&entry(ATTACKER, "Scam"), &cli())
+        .await
+        .unwrap();
+    store.upsert(&entry(OTHER, "Scam"), &cli()).await.unwrap();
+    let other = store.find(&OTHER).await.unwrap().unwrap();
+    store
+        .update(other.id, EntryStatus::Expired, None, &cli())
+        .await
+        .unwrap();
+
+    // 已上链的记录不能通过导入下架，expired 也不能直接改为 suspended
+    let csv = format!(
+        "address,status\n\
+         {ATTACKER},revoked\n\
+         {ATTACKER},pending_review\n\
+         {OTHER},suspended\n"
+    );
+    let context = ChangeContext::new(ChangeSource::Import);
+    for dry_run in [true, false] {
+        let options = ImportOptions {
+            dry_run,
+            ..Default::default()
+        };
+        let report = bulk::import(
+            store.as_ref(),
+            csv.as_bytes(),
+            Format::Csv,
+            &options,
+            &ExpiryPolicy::from_env().unwrap(),
+            &context,
+        )
+        .await
+        .unwrap();
+        let lines: Vec<_> = report.errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, [2, 4], "{:?}", report.errors);
+        assert_eq!(report.duplicates, 1);
+    }
+    assert_eq!(
+        store.find(&ATTACKER).await.unwrap().unwrap().status,
+        "active"
+    );
+    assert_eq!(store.find(&OTHER).await.unwrap().unwrap().status, "expired");
+
+    // 允许的转换照常合并
+    let csv = format!("address,status\n{OTHER},active\n");
+    let report = bulk::import(
+        store.as_ref(),
+        csv.as_bytes(),
+        Format::Csv,
+        &ImportOptions::default(),
+        &ExpiryPolicy::from_env().unwrap(),
+        &context,
+    )
+    .await
+    .unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert_eq!(store.find(&OTHER).await.unwrap().unwrap().status, "active");
+}
+
+#[tokio::test]
+async fn bulk_json_array_is_read_element_by_element() {
+    let store = sqlite_store("bulk-json").await;
+    let context = ChangeContext::new(ChangeSource::Import);
+    let import = |json: String| {
+        let store = store.clone();
+        let context = context.clone();
+        async move {
+            bulk::import(
+                store.as_ref(),
+                json.as_bytes(),
+                Format::Json,
+                &ImportOptions::default(),
+                &ExpiryPolicy::from_env().unwrap(),
+                &context,
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    let report = import(" [ ] ".to_string()).await;
+    assert_eq!(report.total, 0);
+
+    // 字段无效只影响该条
+    let report = import(format!(
+        r#"[{{"address": "{ATTACKER}"}}, {{"address": 1}},
+            {{"address": "{OTHER}", "reason": "Scam"}}]"#
+    ))
+    .await;
+    assert_eq!(report.total, 3);
+    assert_eq!(report.inserted, 2);
+    let lines: Vec<_> = report.errors.iter().map(|error| error.line).collect();
+    assert_eq!(lines, [2]);
+
+    // 数组格式错误时停止
+    let report = import(format!(
+        r#"[{{"address": "{ATTACKER}"}} {{"address": "{OTHER}"}}]"#
+    ))
+    .await;
+    assert_eq!(report.total, 2);
+    assert_eq!(report.merged, 1);
+    assert_eq!(report.errors.len(), 1);
+    let report = import("{}".to_string()).await;
+    assert_eq!(report.errors.len(), 1);
+}
+
+const SDN_XML: &str = r#"<?xml version="1.0" standalone="yes"?>
+<sdnList xmlns="http://tempuri.org/sdnList.xsd">
+  <publshInformation>
+    <Publish_Date>01/02/2025</Publish_Date>
+  </publshInformation>
+  <sdnEntry>
+    <uid>100</uid>
+    <lastName>LAZARUS GROUP</lastName>
+    <sdnType>Entity</sdnType>
+    <programList>
+      <program>DPRK3</program>
+      <program>CYBER2</program>
+    </programList>
+    <idList>
+      <id>
+        <uid>1</uid>
+        <idType>Digital Currency Address - ETH</idType>
+        <idNumber>0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa</idNumber>
+      </id>
+      <id>
+        <uid>2</uid>
+        <idType>Digital Currency Address - XBT</idType>
+        <idNumber>1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2</idNumber>
+      </id>
+    </idList>
+  </sdnEntry>
+  <sdnEntry>
+    <uid>200</uid>
+    <firstName>John</firstName>
+    <lastName>DOE</lastName>
+    <sdnType>Individual</sdnType>
+    <programList>
+      <program>CYBER2</program>
+    </programList>
+    <idList>
+      <id>
+        <uid>3</uid>
+        <idType>Digital Currency Address - USDT</idType>
+        <idNumber>0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb</idNumber>
+      </id>
+    </idList>
+  </sdnEntry>
+</sdnList>
+"#;
+
+/// 记录名单同步的调用，撤销直接写入数据库；链上移除失败的地址不修改记录
+struct RecordingSync {
+    store: Arc<dyn BlacklistStore>,
+    listed: Mutex<Vec<Address>>,
+    failing: Option<Address>,
+}
+
+impl RecordingSync {
+    fn new(store: &Arc<dyn BlacklistStore>) -> Self {
+        Self {
+            store: Arc::clone(store),
+            listed: Mutex::new(vec![]),
+            failing: None,
+        }
+    }
+}
+
+#[async_trait]
+impl SdnSync for RecordingSync {
+    async fn listed(&self, address: Address, _reason: &str) -> anyhow::Result<()> {
+        self.listed.lock().unwrap().push(address);
+        Ok(())
+    }
+
+    async fn revoke(&self, entry: &BlacklistEntry, context: &ChangeContext) -> anyhow::Result<()> {
+        if self.failing == Some(entry.address.parse()?) {
+            return Err(anyhow::anyhow!("链上移除失败"));
+        }
+        self.store
+            .update(entry.id, EntryStatus::Revoked, entry.expires_at, context)
+            .await
+    }
+}
+
+#[tokio::test]
+async fn sanctions_list_versions_add_and_revoke() {
+    let store = sqlite_store("sanctions").await;
+    let context = ChangeContext::new(ChangeSource::Import).with_actor(OFAC_SDN_SOURCE);
+
+    let list = SdnList::parse(SDN_XML.as_bytes(), SdnFormat::Xml).unwrap();
+    assert_eq!(list.published.as_deref(), Some("01/02/2025"));
+    assert_eq!(list.unsupported.len(), 1);
+    assert_eq!(list.addresses.len(), 2);
+    assert_eq!(
+        list.addresses[0].reason(),
+        "OFAC SDN: LAZARUS GROUP [DPRK3, CYBER2]"
+    );
+    assert_eq!(list.addresses[1].name, "John DOE");
+    assert_eq!(list.addresses[1].chain_type, "ETH");
+
+    let diff = ofac::diff(store.as_ref(), &list).await.unwrap();
+    assert_eq!(diff.to_add.len(), 2);
+    let sync = RecordingSync::new(&store);
+    let applied = ofac::apply(store.as_ref(), &sync, &diff, &context)
+        .await
+        .unwrap();
+    assert_eq!(applied.inserted, 2);
+    // 新拉黑的地址立即推送上链
+    assert_eq!(*sync.listed.lock().unwrap(), [ATTACKER, OTHER]);
+    let entry = store.find(&ATTACKER).await.unwrap().unwrap();
+    assert_eq!(entry.created_by.as_deref(), Some(OFAC_SDN_SOURCE));
+    assert!(entry.expires_at.is_none());
+    // 同一版名单再次导入没有差异
+    let diff = ofac::diff(store.as_ref(), &list).await.unwrap();
+    assert!(diff.to_add.is_empty() && diff.to_revoke.is_empty());
+
+    // 新版名单只保留 LAZARUS GROUP
+    let csv = format!(
+        "100,\"LAZARUS GROUP\",-0- ,\"DPRK3] [CYBER2\",-0-,-0-,-0-,-0-,-0-,-0-,-0-,\
+         \"Digital Currency Address - ETH {ATTACKER}; Digital Currency Address - XBT 1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2.\"\n\
+         300,\"OTHER PERSON\",individual,\"SDGT\",-0-,-0-,-0-,-0-,-0-,-0-,-0-,\"DOB 1970.\"\n\x1a"
+    );
+    let list = SdnList::parse(csv.as_bytes(), SdnFormat::Csv).unwrap();
+    assert_eq!(list.addresses.len(), 1);
+    assert_eq!(list.addresses[0].programs, ["DPRK3", "CYBER2"]);
+    let diff = ofac::diff(store.as_ref(), &list).await.unwrap();
+    assert!(diff.to_add.is_empty());
+    assert_eq!(diff.to_revoke.len(), 1);
+
+    // 链上移除失败时记录保持 active，下次导入仍会撤销
+    let failing = RecordingSync {
+        failing: Some(OTHER),
+        ..RecordingSync::new(&store)
+    };
+    let applied = ofac::apply(store.as_ref(), &failing, &diff, &context)
+        .await
+        .unwrap();
+    assert_eq!((applied.revoked, applied.failed), (0, 1));
+    assert_eq!(store.find(&OTHER).await.unwrap().unwrap().status, "active");
+
+    let diff = ofac::diff(store.as_ref(), &list).await.unwrap();
+    let applied = ofac::apply(store.as_ref(), &sync, &diff, &context)
+        .await
+        .unwrap();
+    assert_eq!((applied.revoked, applied.failed), (1, 0));
+    assert_eq!(store.find(&OTHER).await.unwrap().unwrap().status, "revoked");
+    assert_eq!(
+        store.find(&ATTACKER).await.unwrap().unwrap().status,
+        "active"
+    );
+}
+
+#[tokio::test]
+async fn sanctions_listing_is_tracked_by_evidence() {
+    let store = sqlite_store("sanctions-evidence").await;
+    let context = ChangeContext::new(ChangeSource::Import).with_actor(OFAC_SDN_SOURCE);
+    let detected = |attacker: Address, tx: u8| Evidence {
+        attacker,
+        tx_hash: Some(B256::repeat_byte(tx)),
+        block_number: Some(100),
+        block_hash: B256::repeat_byte(0x22),
+        chain_type: "ETH".to_string(),
+        detector: REENTRANCY_DETECTOR.to_string(),
+        severity: Severity::High,
+        project: ATTACKER,
+        selector: None,
+        loss: U256::ZERO,
+        call_path: vec![],
+    };
+    // OTHER 先由检测器拉黑
+    let detector = ChangeContext::new(ChangeSource::Detector).with_actor(REENTRANCY_DETECTOR);
+    store
+        .upsert(&entry(OTHER, "Scam"), &detector)
+        .await
+        .unwrap();
+    store.add_evidence(&detected(OTHER, 0x11)).await.unwrap();
+
+    let list = SdnList::parse(SDN_XML.as_bytes(), SdnFormat::Xml).unwrap();
+    let diff = ofac::diff(store.as_ref(), &list).await.unwrap();
+    assert_eq!(diff.to_add.len(), 2);
+    let sync = RecordingSync::new(&store);
+    let applied = ofac::apply(store.as_ref(), &sync, &diff, &context)
+        .await
+        .unwrap();
+    assert_eq!((applied.inserted, applied.merged), (1, 1));
+    // 已拉黑的地址不重复推送
+    assert_eq!(*sync.listed.lock().unwrap(), [ATTACKER]);
+    // 合并后的记录创建人不变，但已有名单证据，不再重复列出
+    let other = store.find(&OTHER).await.unwrap().unwrap();
+    assert_eq!(other.created_by, None);
+    let diff = ofac::diff(store.as_ref(), &list).await.unwrap();
+    assert!(diff.to_add.is_empty() && diff.to_revoke.is_empty());
+    let listed = BlacklistQuery::new().detector(OFAC_SDN_SOURCE);
+    assert_eq!(store.count(&listed).await.unwrap(), 2);
+
+    // 名单列入后又有检测器证据的记录，移出名单时同样保留
+    store.add_evidence(&detected(ATTACKER, 0x33)).await.unwrap();
+    let diff = ofac::diff(store.as_ref(), &SdnList::default())
+        .await
+        .unwrap();
+    assert!(diff.to_revoke.is_empty());
+    assert_eq!(diff.retained.len(), 2);
+    ofac::apply(store.as_ref(), &sync, &diff, &context)
+        .await
+        .unwrap();
+    assert_eq!(
+        store.find(&ATTACKER).await.unwrap().unwrap().status,
+        "active"
+    );
+    assert_eq!(store.find(&OTHER).await.unwrap().unwrap().status, "active");
+}
+
+#[tokio::test]
+async fn findings_are_recorded_deterministically() {
+    let store = sqlite_store("findings").await;
+    let policy = ExpiryPolicy::from_env().unwrap();
+    let finding = |attacker: Address, severity: Severity| Evidence {
+        attacker,
+        tx_hash: Some(B256::repeat_byte(0x11)),
+        block_number: Some(100),
+        block_hash: B256::repeat_byte(0x22),
+        chain_type: "ETH".to_string(),
+        detector: REENTRANCY_DETECTOR.to_string(),
+        severity,
+        project: OTHER,
+        selector: Some(fixed_bytes!("2e1a7d4d")),
+        loss: U256::ZERO,
+        call_path: vec![],
+    };
+    let evidence = [
+        finding(ATTACKER, Severity::Medium),
+        finding(Address::repeat_byte(0xcc), Severity::Critical),
+    ];
+    let recorded = record_findings(store.as_ref(), &policy, &evidence).await;
+    assert_eq!(recorded.inserted.len(), 2);
+    // 同一攻击回放后原因不变
+    let recorded = record_findings(store.as_ref(), &policy, &evidence).await;
+    assert!(recorded.inserted.is_empty());
+
+    let medium = store.find(&ATTACKER).await.unwrap().unwrap();
+    assert_eq!(
+        medium.reason,
+        format!("reentrancy on {:#x} 0x2e1a7d4d", OTHER)
+    );
+    let days = (medium.expires_at.unwrap() - Utc::now()).num_days();
+    assert!((29..=30).contains(&days), "{}", days);
+    let critical = store
+        .find(&Address::repeat_byte(0xcc))
+        .await
+        .unwrap()
+        .unwrap();
+    // 严重程度不会让检测结果永久拉黑
+    let days = (critical.expires_at.unwrap() - Utc::now()).num_days();
+    assert!((179..=180).contains(&days), "{}", days);
+}
+
+#[tokio::test]
+async fn updater_tables_round_trip() {
+    let store = sqlite_store("updater").await;
+    let project = address!("cccccccccccccccccccccccccccccccccccccccc");
+    let tx_hash = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+
+    // 同步状态：重复标记为待同步不覆盖已提交的记录
+    store
+        .mark_pending(project, &[ATTACKER, OTHER])
+        .await
+        .unwrap();
+    store
+        .set_sync_status(
+            project,
+            &[ATTACKER],
+            SyncStatus::Submitted,
+            Some(tx_hash),
+            Some(7),
+        )
+        .await
+        .unwrap();
+    store.mark_pending(project, &[ATTACKER]).await.unwrap();
+    let mut unsynced = store.load_unsynced().await.unwrap();
+    unsynced.sort_by_key(|unsynced| unsynced.address);
+    assert_eq!(unsynced.len(), 2);
+    assert_eq!(unsynced[0].status, SyncStatus::Submitted);
+    assert_eq!(unsynced[0].tx_hash, Some(tx_hash));
+    assert_eq!(unsynced[1].status, SyncStatus::Pending);
+    assert_eq!(store.pushed_projects(&ATTACKER).await.unwrap(), [project]);
+    assert!(store.pushed_projects(&OTHER).await.unwrap().is_empty());
+
+    store
+        .set_sync_status(
+            project,
+            &[ATTACKER],
+            SyncStatus::Synced,
+            Some(tx_hash),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(store.load_unsynced().await.unwrap().len(), 1);
+    store.delete_sync_state(&OTHER).await.unwrap();
+    assert!(store.load_unsynced().await.unwrap().is_empty());
+
+    // 函数级黑名单
+    let scope = FunctionScope {
+        address: ATTACKER,
+        project,
+        selector: fixed_bytes!("a9059cbb"),
+    };
+    store.record_function_scope(&scope).await.unwrap();
+    store.record_function_scope(&scope).await.unwrap();
+    assert_eq!(store.load_unsynced_scopes().await.unwrap(), [scope]);
+    store.mark_scope_synced(&scope).await.unwrap();
+    assert!(store.load_unsynced_scopes().await.unwrap().is_empty());
+    assert_eq!(store.list_scopes(&ATTACKER).await.unwrap(), [(scope, true)]);
+    assert_eq!(store.list_all_scopes().await.unwrap(), [(scope, true)]);
+    store.delete_scopes(&ATTACKER).await.unwrap();
+    assert!(store.list_scopes(&ATTACKER).await.unwrap().is_empty());
+
+    // nonce：取数据库与链上较大者，只归还最近一次分配
+    assert_eq!(store.reserve_nonce(1, OTHER, 5).await.unwrap(), 5);
+    assert_eq!(store.reserve_nonce(1, OTHER, 5).await.unwrap(), 6);
+    assert_eq!(store.reserve_nonce(1, OTHER, 9).await.unwrap(), 9);
+    store.release_nonce(1, OTHER, 6).await.unwrap();
+    store.release_nonce(1, OTHER, 9).await.unwrap();
+    assert_eq!(store.reserve_nonce(1, OTHER, 0).await.unwrap(), 9);
+    assert_eq!(store.reserve_nonce(2, OTHER, 0).await.unwrap(), 0);
+
+    // 暂停记录：恢复后再次暂停重新生效
+    let target = PauseTarget::Function {
+        project,
+        selector: scope.selector,
+    };
+    store.record_pause(&target, ATTACKER).await.unwrap();
+    store
+        .record_pause(&PauseTarget::Project(OTHER), ATTACKER)
+        .await
+        .unwrap();
+    store.record_unpause(&target).await.unwrap();
+    let active = store.list_active_pauses().await.unwrap();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].target, PauseTarget::Project(OTHER));
+    store.record_pause(&target, OTHER).await.unwrap();
+    let active = store.list_active_pauses().await.unwrap();
+    assert_eq!(active.len(), 2);
+    assert!(active
+        .iter()
+        .any(|record| record.target == target && record.attacker == OTHER));
+}
+
+#[test]
+fn postgres_placeholders_are_numbered() {
+    let sql = <Postgres as Backend>::sql(
+        "UPDATE t SET reason = reason || '; ?' || ? WHERE id = ? AND x = ?::TIMESTAMPTZ".into(),
+    );
+    assert_eq!(
+        sql,
+        "UPDATE t SET reason = reason || '; ?' || $1 WHERE id = $2 AND x = $3::TIMESTAMPTZ"
+    );
+}
+
+#[tokio::test]
+async fn concurrent_nonce_reservations_are_distinct() {
+    let store = sqlite_store("nonce").await;
+    let reservations = (0..8).map(|_| {
+        let store = store.clone();
+        tokio::spawn(async move { store.reserve_nonce(1, ATTACKER, 3).await.unwrap() })
+    });
+    let mut nonces = vec![];
+    for reservation in reservations {
+        nonces.push(reservation.await.unwrap());
+    }
+    nonces.sort();
+    assert_eq!(nonces, (3..11).collect::<Vec<u64>>());
+}
@@ -0,0 +1,152 @@
+use alloy::primitives::{TxHash, B256};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use testcase::auth::sync::{track_receipt, ReceiptTracker};
+
+/// 按脚本返回结果的交易跟踪器：`mined_at` 为第几次发送的交易上链，
+/// 在第 `seen_at` 次等待时才查到
+#[derive(Default)]
+struct ScriptedTracker {
+    mined_at: Option<u32>,
+    seen_at: u32,
+    bump_fails_at: Option<u32>,
+    send_fails: bool,
+    replace_fails: bool,
+    sends: u32,
+    bumps: u32,
+    waited: Vec<Vec<TxHash>>,
+}
+
+fn hash(attempt: u32) -> TxHash {
+    B256::with_last_byte(attempt as u8 + 1)
+}
+
+#[async_trait]
+impl ReceiptTracker for ScriptedTracker {
+    type Receipt = TxHash;
+
+    async fn send(&mut self, attempt: u32) -> Result<TxHash> {
+        assert_eq!(attempt, self.sends);
+        if self.send_fails {
+            return Err(anyhow!("insufficient funds"));
+        }
+        if self.replace_fails && attempt > 0 {
+            return Err(anyhow!("nonce too low"));
+        }
+        self.sends += 1;
+        Ok(hash(attempt))
+    }
+
+    async fn wait(&mut self, tx_hash: TxHash, sent: &[TxHash]) -> Result<Option<TxHash>> {
+        assert_eq!(sent.last(), Some(&tx_hash));
+        self.waited.push(sent.to_vec());
+        let waits = self.waited.len() as u32 - 1;
+        Ok(match self.mined_at {
+            Some(mined) if waits >= self.seen_at => sent.get(mined as usize).copied(),
+            _ => None,
+        })
+    }
+
+    async fn bump(&mut self) -> Result<()> {
+        if self.bump_fails_at == Some(self.bumps) {
+            return Err(anyhow!("fee cap reached"));
+        }
+        self.bumps += 1;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn confirmed_transaction_is_not_resent() {
+    let mut tracker = ScriptedTracker {
+        mined_at: Some(0),
+        ..Default::default()
+    };
+    let receipt = track_receipt(&mut tracker, 3).await.unwrap();
+    assert_eq!(receipt, Some(hash(0)));
+    assert_eq!((tracker.sends, tracker.bumps), (1, 0));
+}
+
+#[tokio::test]
+async fn timeouts_bump_fees_and_resend() {
+    let mut tracker = ScriptedTracker {
+        mined_at: Some(2),
+        seen_at: 2,
+        ..Default::default()
+    };
+    let receipt = track_receipt(&mut tracker, 3).await.unwrap();
+    assert_eq!(receipt, Some(hash(2)));
+    assert_eq!((tracker.sends, tracker.bumps), (3, 2));
+    // 每次等待都能看到之前发送的全部交易
+    assert_eq!(tracker.waited[2], [hash(0), hash(1), hash(2)]);
+}
+
+#[tokio::test]
+async fn replaced_transaction_that_landed_is_returned() {
+    // 第一笔交易在重发之后才被查到上链
+    let mut tracker = ScriptedTracker {
+        mined_at: Some(0),
+        seen_at: 1,
+        ..Default::default()
+    };
+    let receipt = track_receipt(&mut tracker, 3).await.unwrap();
+    assert_eq!(receipt, Some(hash(0)));
+    assert_eq!((tracker.sends, tracker.bumps), (2, 1));
+}
+
+#[tokio::test]
+async fn retries_are_bounded() {
+    let mut tracker = ScriptedTracker::default();
+    assert_eq!(track_receipt(&mut tracker, 2).await.unwrap(), None);
+    // 最后一次超时后不再提高手续费
+    assert_eq!((tracker.sends, tracker.bumps), (3, 2));
+
+    let mut tracker = ScriptedTracker::default();
+    assert_eq!(track_receipt(&mut tracker, 0).await.unwrap(), None);
+    assert_eq!((tracker.sends, tracker.bumps), (1, 0));
+}
+
+#[tokio::test]
+async fn fee_cap_stops_retrying() {
+    let mut tracker = ScriptedTracker {
+        bump_fails_at: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(track_receipt(&mut tracker, 5).await.unwrap(), None);
+    assert_eq!((tracker.sends, tracker.bumps), (2, 1));
+}
+
+#[tokio::test]
+async fn send_errors_are_returned() {
+    let mut tracker = ScriptedTracker {
+        send_fails: true,
+        ..Default::default()
+    };
+    let err = track_receipt(&mut tracker, 3).await.unwrap_err();
+    assert!(err.to_string().contains("insufficient funds"));
+    assert!(tracker.waited.is_empty());
+}
+
+#[tokio::test]
+async fn failed_replacement_keeps_waiting_for_sent_transaction() {
+    // 替换交易因 nonce too low 被拒绝，原交易随后被查到上链
+    let mut tracker = ScriptedTracker {
+        mined_at: Some(0),
+        seen_at: 2,
+        replace_fails: true,
+        ..Default::default()
+    };
+    let receipt = track_receipt(&mut tracker, 3).await.unwrap();
+    assert_eq!(receipt, Some(hash(0)));
+    assert_eq!((tracker.sends, tracker.bumps), (1, 1));
+    assert_eq!(tracker.waited, vec![vec![hash(0)]; 3]);
+
+    // 一直未上链时在重试用尽后返回 None，而不是错误
+    let mut tracker = ScriptedTracker {
+        replace_fails: true,
+        ..Default::default()
+    };
+    assert_eq!(track_receipt(&mut tracker, 3).await.unwrap(), None);
+    assert_eq!((tracker.sends, tracker.bumps), (1, 1));
+    assert_eq!(tracker.waited.len(), 4);
+}
@@ -0,0 +1,58 @@
+use alloy::primitives::address;
+use testcase::auth::{data::REASON_SEPARATOR, ofac::OFAC_SDN_SOURCE, token::TokenTargets};
+
+#[test]
+fn tokens_are_parsed_from_list() {
+    let targets =
+        TokenTargets::parse(Some(" 0xcccccccccccccccccccccccccccccccccccccccc, ,"), None).unwrap();
+    assert_eq!(
+        targets.tokens,
+        [address!("cccccccccccccccccccccccccccccccccccccccc")]
+    );
+    assert!(targets.contains(&address!("cccccccccccccccccccccccccccccccccccccccc")));
+    assert!(TokenTargets::parse(Some("0x12"), None).is_err());
+    assert!(TokenTargets::parse(None, None).unwrap().tokens.is_empty());
+}
+
+#[test]
+fn all_reasons_are_selected_by_default() {
+    let targets = TokenTargets::parse(None, None).unwrap();
+    assert!(targets.selects("manual"));
+    assert!(targets.selects(""));
+}
+
+#[test]
+fn reason_prefix_matches_whole_first_word() {
+    let targets = TokenTargets::parse(None, Some("reentrancy, manual ,")).unwrap();
+    assert!(targets.selects("reentrancy"));
+    assert!(targets.selects("reentrancy on 0xab12 0x2e1a7d4d"));
+    assert!(targets.selects("manual"));
+    // 前缀必须以空格结束，不能只是单词的一部分
+    assert!(!targets.selects("reentrancyX on 0xab12"));
+    assert!(!targets.selects("manually added"));
+    assert!(!targets.selects("price manipulation"));
+    // 大小写敏感
+    assert!(!targets.selects("Reentrancy on 0xab12"));
+}
+
+#[test]
+fn merged_reasons_match_any_part() {
+    let targets = TokenTargets::parse(None, Some(OFAC_SDN_SOURCE)).unwrap();
+    let merged = [
+        "reentrancy on 0xab12 0x2e1a7d4d",
+        &format!("{} 12345", OFAC_SDN_SOURCE),
+    ]
+    .join(REASON_SEPARATOR);
+    assert!(targets.selects(&merged));
+
+    let targets = TokenTargets::parse(None, Some("manual")).unwrap();
+    assert!(!targets.selects(&merged));
+    // 分隔符中的空格不会让后一项的开头被当作前缀
+    assert!(!targets.selects(&format!("reentrancy{}manualX", REASON_SEPARATOR)));
+}
+
+#[test]
+fn empty_reason_list_selects_nothing() {
+    let targets = TokenTargets::parse(None, Some(" , ")).unwrap();
+    assert!(!targets.selects("reentrancy on 0xab12"));
+}